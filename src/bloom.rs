@@ -0,0 +1,157 @@
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use bytes::{Buf, Bytes};
+
+use parquet::basic::{ConvertedType, LogicalType, Type as PhysicalType};
+use parquet::bloom_filter::Sbbf;
+use parquet::errors::ParquetError;
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::{ChunkReader, Length};
+use parquet::file::serialized_reader::{ReadOptionsBuilder, SerializedFileReader};
+use parquet::format::BloomFilterHeader;
+use parquet::schema::types::ColumnDescriptor;
+use thrift::protocol::{TCompactInputProtocol, TSerializable};
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::InvalidColumnValue;
+use crate::footer::{check_footer, read_file_metadata, write_footer};
+
+// a bloom filter header is about 15 bytes, it only holds a length and three single field unions
+const BLOOM_FILTER_HEADER_SIZE_ESTIMATE: u64 = 64;
+
+/// A file reader which also loads the bloom filters of the given leaf columns. The reader of
+/// the parquet crate loads the filters of every column chunk of the row groups it reads, so it
+/// is given a footer where only these columns have one.
+pub fn bloom_filter_reader(
+    file: File,
+    columns: &[usize],
+) -> Result<SerializedFileReader<FooterOverlay>, PQRSError> {
+    let check = check_footer(&file)?;
+    let mut metadata = read_file_metadata(&file, &check)?;
+    for row_group in &mut metadata.row_groups {
+        for (i, column) in row_group.columns.iter_mut().enumerate() {
+            match column.meta_data.as_mut() {
+                Some(meta_data) if !columns.contains(&i) => meta_data.bloom_filter_offset = None,
+                _ => {}
+            }
+        }
+    }
+    let footer = write_footer(vec![], &metadata)?;
+    // the footer checks passed, the metadata starts there
+    let data_length = check.metadata_offset.unwrap_or_default();
+    let overlay = FooterOverlay { file, data_length, footer: footer.into() };
+
+    let options = ReadOptionsBuilder::new()
+        .with_reader_properties(ReaderProperties::builder().set_read_bloom_filter(true).build())
+        .build();
+    Ok(SerializedFileReader::new_with_options(overlay, options)?)
+}
+
+/// A file read with another footer, the bytes before its metadata are read as stored.
+pub struct FooterOverlay {
+    file: File,
+    data_length: u64,
+    footer: Bytes,
+}
+
+impl Length for FooterOverlay {
+    fn len(&self) -> u64 {
+        self.data_length + self.footer.len() as u64
+    }
+}
+
+impl ChunkReader for FooterOverlay {
+    type T = Box<dyn Read + Send>;
+
+    fn get_read(&self, start: u64) -> parquet::errors::Result<Self::T> {
+        let footer_start = start.saturating_sub(self.data_length) as usize;
+        let mut file = self.file.try_clone()?;
+        file.seek(SeekFrom::Start(start.min(self.data_length)))?;
+        let data = file.take(self.data_length.saturating_sub(start));
+        Ok(Box::new(data.chain(self.footer.slice(footer_start.min(self.footer.len())..).reader())))
+    }
+
+    fn get_bytes(&self, start: u64, length: usize) -> parquet::errors::Result<Bytes> {
+        if start + length as u64 <= self.data_length {
+            return self.file.get_bytes(start, length);
+        }
+        let mut bytes = Vec::with_capacity(length);
+        self.get_read(start)?.take(length as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != length {
+            return Err(ParquetError::EOF(format!("expected {} bytes, read {}", length, bytes.len())));
+        }
+        Ok(bytes.into())
+    }
+}
+
+/// Reads the header of the bloom filter stored at `offset`, returning it with its size.
+///
+/// The footer only tells where a bloom filter starts, its length is the size of this header
+/// plus the number of bytes of the bitset.
+pub fn read_bloom_filter_header(
+    file: &File,
+    offset: u64,
+) -> Result<(BloomFilterHeader, usize), PQRSError> {
+    let length = BLOOM_FILTER_HEADER_SIZE_ESTIMATE.min(file.len().saturating_sub(offset));
+    let bytes = file.get_bytes(offset, length as usize)?;
+    let mut cursor = Cursor::new(bytes.as_ref());
+    let header = {
+        let mut protocol = TCompactInputProtocol::new(&mut cursor);
+        BloomFilterHeader::read_from_in_protocol(&mut protocol)?
+    };
+    Ok((header, cursor.position() as usize))
+}
+
+fn parse<T: std::str::FromStr>(descr: &ColumnDescriptor, value: &str) -> Result<T, PQRSError> {
+    value
+        .parse()
+        .map_err(|_| InvalidColumnValue(descr.path().string(), value.to_string()))
+}
+
+/// Checks whether `value` might be in the bloom filter of a column. The value is parsed as the
+/// physical type of the column, as the filter hashes the stored bytes.
+pub fn bloom_filter_check(
+    sbbf: &Sbbf,
+    descr: &ColumnDescriptor,
+    value: &str,
+) -> Result<bool, PQRSError> {
+    let present = match descr.physical_type() {
+        PhysicalType::INT32 => sbbf.check(&parse::<i32>(descr, value)?),
+        PhysicalType::INT64 => sbbf.check(&parse::<i64>(descr, value)?),
+        PhysicalType::FLOAT => sbbf.check(&parse::<f32>(descr, value)?),
+        PhysicalType::DOUBLE => sbbf.check(&parse::<f64>(descr, value)?),
+        PhysicalType::BYTE_ARRAY | PhysicalType::FIXED_LEN_BYTE_ARRAY => sbbf.check(&value),
+        PhysicalType::BOOLEAN | PhysicalType::INT96 => {
+            return Err(InvalidColumnValue(descr.path().string(), value.to_string()));
+        }
+    };
+    Ok(present)
+}
+
+/// Whether a value of the column as printed is also its stored value, so an equality filter
+/// on the printed value can be checked against the bloom filter. Floats are left out as
+/// several printed forms map to the same value.
+pub fn prints_stored_value(descr: &ColumnDescriptor) -> bool {
+    match (descr.physical_type(), descr.logical_type()) {
+        (PhysicalType::INT32 | PhysicalType::INT64, None) => matches!(
+            descr.converted_type(),
+            ConvertedType::NONE
+                | ConvertedType::INT_8
+                | ConvertedType::INT_16
+                | ConvertedType::INT_32
+                | ConvertedType::INT_64
+        ),
+        (PhysicalType::INT32 | PhysicalType::INT64, Some(LogicalType::Integer { is_signed, .. })) => {
+            is_signed
+        }
+        (PhysicalType::BYTE_ARRAY, None) => matches!(
+            descr.converted_type(),
+            ConvertedType::UTF8 | ConvertedType::ENUM | ConvertedType::JSON
+        ),
+        (PhysicalType::BYTE_ARRAY, Some(LogicalType::String | LogicalType::Enum | LogicalType::Json)) => {
+            true
+        }
+        _ => false,
+    }
+}
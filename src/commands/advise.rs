@@ -0,0 +1,348 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use clap::{Parser, ValueEnum};
+use log::debug;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::{ArrowWriter, ProjectionMask};
+use parquet::basic::{Compression, Encoding, Type as PhysicalType};
+use parquet::file::properties::WriterProperties;
+use parquet::schema::types::ColumnDescriptor;
+use serde::Serialize;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, UnknownColumn};
+use crate::utils::{check_path_present, get_pretty_size, open_file, parse_codec};
+
+const DEFAULT_CODECS: &str = "uncompressed,snappy,gzip:6,lz4_raw,zstd:1,zstd:3,zstd:9,brotli:4";
+
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub enum Objective {
+    /// the smallest encoding and codec
+    Size,
+    /// the smallest one among those decoding at most twice as slow as the fastest one
+    Balanced,
+}
+
+// re-encodes the columns of sampled row groups to find the best codec and encoding for each
+#[derive(Parser, Debug)]
+pub struct AdviseCommandArgs {
+    file: PathBuf,
+
+    #[arg(short, long, help = "only advise on these top level columns")]
+    column: Vec<String>,
+
+    #[arg(short = 'g', long, default_value = "3", help = "number of row groups to sample, evenly spread in the file")]
+    row_groups: usize,
+
+    #[arg(long, default_value = DEFAULT_CODECS, value_delimiter = ',', help = "the codecs to try, as name or name:level")]
+    codecs: Vec<String>,
+
+    #[arg(short, long, value_enum, default_value = "size", help = "what to recommend")]
+    objective: Objective,
+
+    #[arg(short, long, help = "print the advice as JSON")]
+    json: bool,
+}
+
+/// How the values of a column are encoded, dictionary encoding falls back to plain.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ColumnEncoding {
+    Dictionary,
+    Encoding(Encoding),
+}
+
+impl fmt::Display for ColumnEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnEncoding::Dictionary => f.pad("dictionary"),
+            ColumnEncoding::Encoding(encoding) => f.pad(&encoding.to_string()),
+        }
+    }
+}
+
+impl Serialize for ColumnEncoding {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+// the encodings the writer supports for a leaf column. BYTE_STREAM_SPLIT would suit floating
+// point columns but the writer can not produce it yet.
+fn leaf_encodings(descr: &ColumnDescriptor) -> Vec<ColumnEncoding> {
+    let mut encodings = vec![ColumnEncoding::Dictionary, ColumnEncoding::Encoding(Encoding::PLAIN)];
+    let specific: &[Encoding] = match descr.physical_type() {
+        PhysicalType::INT32 | PhysicalType::INT64 => &[Encoding::DELTA_BINARY_PACKED],
+        PhysicalType::BYTE_ARRAY => &[Encoding::DELTA_LENGTH_BYTE_ARRAY, Encoding::DELTA_BYTE_ARRAY],
+        _ => &[],
+    };
+    encodings.extend(specific.iter().map(|e| ColumnEncoding::Encoding(*e)));
+    encodings
+}
+
+fn codec_spec(codec: &Compression) -> String {
+    match codec {
+        Compression::GZIP(level) => format!("GZIP({})", level.compression_level()),
+        Compression::BROTLI(level) => format!("BROTLI({})", level.compression_level()),
+        Compression::ZSTD(level) => format!("ZSTD({})", level.compression_level()),
+        other => format!("{:?}", other),
+    }
+}
+
+// how to build a codec in rust, for the recommended writer properties
+fn codec_code(codec: &Compression) -> String {
+    match codec {
+        Compression::GZIP(level) => format!("Compression::GZIP(GzipLevel::try_new({})?)", level.compression_level()),
+        Compression::BROTLI(level) => {
+            format!("Compression::BROTLI(BrotliLevel::try_new({})?)", level.compression_level())
+        }
+        Compression::ZSTD(level) => format!("Compression::ZSTD(ZstdLevel::try_new({})?)", level.compression_level()),
+        other => format!("Compression::{:?}", other),
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Trial {
+    encoding: ColumnEncoding,
+    codec: String,
+    /// Compressed size of the column chunks.
+    size: i64,
+    encode_ms: f64,
+    decode_ms: f64,
+    #[serde(skip)]
+    compression: Compression,
+}
+
+#[derive(Serialize, Debug)]
+struct ColumnAdvice {
+    column: String,
+    /// The paths of the leaf columns the writer properties are set for.
+    leaves: Vec<Vec<String>>,
+    /// Compressed size of the sampled column chunks as stored in the file.
+    stored_size: i64,
+    /// Trials from the smallest to the largest.
+    trials: Vec<Trial>,
+    recommended: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct Advice {
+    file: String,
+    row_groups: Vec<usize>,
+    columns: Vec<ColumnAdvice>,
+}
+
+// how to build a column path in rust, its parts are not split on dots
+fn column_path_code(parts: &[String]) -> String {
+    match parts {
+        [name] => format!("ColumnPath::from({:?})", name),
+        _ => format!(
+            "ColumnPath::new(vec![{}])",
+            parts.iter().map(|p| format!("{:?}.to_string()", p)).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+fn milliseconds(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+// writes the batches in memory with one encoding and codec, then reads them back
+fn run_trial(
+    batches: &[RecordBatch],
+    leaves: &[&ColumnDescriptor],
+    encoding: ColumnEncoding,
+    compression: Compression,
+) -> Result<Trial, PQRSError> {
+    let mut properties = WriterProperties::builder()
+        .set_compression(compression)
+        .set_dictionary_enabled(encoding == ColumnEncoding::Dictionary);
+    if let ColumnEncoding::Encoding(e) = encoding {
+        for leaf in leaves {
+            properties = properties.set_column_encoding(leaf.path().clone(), e);
+        }
+    }
+
+    let start = Instant::now();
+    let mut buffer = vec![];
+    let mut writer = ArrowWriter::try_new(&mut buffer, batches[0].schema(), Some(properties.build()))?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    let metadata = writer.close()?;
+    let encode_time = start.elapsed();
+    let size = metadata
+        .row_groups
+        .iter()
+        .flat_map(|r| r.columns.iter())
+        .filter_map(|c| c.meta_data.as_ref())
+        .map(|m| m.total_compressed_size)
+        .sum();
+
+    let start = Instant::now();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(buffer))?.build()?;
+    for batch in reader {
+        batch?;
+    }
+    let decode_time = start.elapsed();
+
+    Ok(Trial {
+        encoding,
+        codec: codec_spec(&compression),
+        size,
+        encode_ms: milliseconds(encode_time),
+        decode_ms: milliseconds(decode_time),
+        compression,
+    })
+}
+
+fn recommend(trials: &[Trial], objective: Objective) -> usize {
+    let fastest = trials.iter().map(|t| t.decode_ms).fold(f64::INFINITY, f64::min);
+    // trials are sorted by size, the first acceptable one is the smallest
+    trials
+        .iter()
+        .position(|t| objective == Objective::Size || t.decode_ms <= 2.0 * fastest)
+        .unwrap_or_default()
+}
+
+fn print_advice(advice: &Advice) {
+    println!(
+        "Advice for {}, from row groups {:?}:",
+        advice.file, advice.row_groups
+    );
+    for column in &advice.columns {
+        println!();
+        println!(
+            "Column {}, {} as stored:",
+            column.column,
+            get_pretty_size(column.stored_size)
+        );
+        println!(
+            "  {:<24} {:<12} {:>12} {:>7} {:>11} {:>11}",
+            "encoding", "codec", "size", "ratio", "encode", "decode"
+        );
+        for (i, trial) in column.trials.iter().enumerate() {
+            println!(
+                "{} {:<24} {:<12} {:>12} {:>7.2} {:>8.2} ms {:>8.2} ms",
+                if i == column.recommended { "*" } else { " " },
+                trial.encoding,
+                trial.codec,
+                trial.size,
+                trial.size as f64 / column.stored_size.max(1) as f64,
+                trial.encode_ms,
+                trial.decode_ms
+            );
+        }
+    }
+
+    println!();
+    println!("Recommended writer properties:");
+    println!("WriterProperties::builder()");
+    for column in &advice.columns {
+        let trial = &column.trials[column.recommended];
+        for leaf in &column.leaves {
+            let path = column_path_code(leaf);
+            println!("    .set_column_compression({}, {})", path, codec_code(&trial.compression));
+            println!(
+                "    .set_column_dictionary_enabled({}, {})",
+                path,
+                trial.encoding == ColumnEncoding::Dictionary
+            );
+            if let ColumnEncoding::Encoding(encoding) = trial.encoding {
+                println!("    .set_column_encoding({}, Encoding::{})", path, encoding);
+            }
+        }
+    }
+    println!("    .build()");
+}
+
+pub(crate) fn execute(opts: AdviseCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file name to read is: {}", opts.file.display());
+    debug!("Columns: {:?}, row groups: {}, codecs: {:?}", opts.column, opts.row_groups, opts.codecs);
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file.to_path_buf()));
+    }
+    let codecs = opts.codecs.iter().map(|c| parse_codec(c)).collect::<Result<Vec<_>, _>>()?;
+
+    let builder = ParquetRecordBatchReaderBuilder::try_new(open_file(&opts.file)?)?;
+    let metadata = builder.metadata().clone();
+    let schema = metadata.file_metadata().schema_descr();
+    let root_fields = schema.root_schema().get_fields();
+    for column in &opts.column {
+        if !root_fields.iter().any(|f| f.name() == column) {
+            return Err(UnknownColumn(column.to_string()));
+        }
+    }
+
+    // evenly spread row groups, the first one always in
+    let num_row_groups = metadata.num_row_groups();
+    let sample = opts.row_groups.min(num_row_groups);
+    let mut row_groups = (0..sample).map(|i| i * num_row_groups / sample).collect::<Vec<_>>();
+    row_groups.dedup();
+
+    let mut columns = vec![];
+    for (root, field) in root_fields.iter().enumerate() {
+        if !opts.column.is_empty() && !opts.column.iter().any(|c| c == field.name()) {
+            continue;
+        }
+        let leaves = (0..schema.num_columns())
+            .filter(|&i| schema.get_column_root_idx(i) == root)
+            .map(|i| schema.column(i))
+            .collect::<Vec<_>>();
+        let leaf_refs = leaves.iter().map(|l| l.as_ref()).collect::<Vec<_>>();
+        // encodings other than dictionary and plain only suit some types, they are tried on
+        // columns holding a single leaf
+        let encodings = match leaves.as_slice() {
+            [leaf] => leaf_encodings(leaf),
+            _ => vec![ColumnEncoding::Dictionary, ColumnEncoding::Encoding(Encoding::PLAIN)],
+        };
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(open_file(&opts.file)?)?
+            .with_projection(ProjectionMask::roots(schema, [root]))
+            .with_row_groups(row_groups.clone())
+            .build()?;
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        if batches.is_empty() {
+            continue;
+        }
+
+        let mut trials = vec![];
+        for encoding in &encodings {
+            for codec in &codecs {
+                trials.push(run_trial(&batches, &leaf_refs, *encoding, *codec)?);
+            }
+        }
+        trials.sort_by_key(|t| t.size);
+
+        let stored_size = row_groups
+            .iter()
+            .flat_map(|&rg| {
+                let row_group = metadata.row_group(rg);
+                leaves
+                    .iter()
+                    .filter_map(move |leaf| row_group.columns().iter().find(|c| c.column_path() == leaf.path()))
+            })
+            .map(|c| c.compressed_size())
+            .sum();
+        columns.push(ColumnAdvice {
+            column: field.name().to_string(),
+            leaves: leaves.iter().map(|l| l.path().parts().to_vec()).collect(),
+            stored_size,
+            recommended: recommend(&trials, opts.objective),
+            trials,
+        });
+    }
+
+    let advice = Advice { file: opts.file.display().to_string(), row_groups, columns };
+    if opts.json {
+        println!("{}", serde_json::to_string(&advice)?);
+    } else {
+        print_advice(&advice);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,248 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use clap::Parser;
+use log::debug;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ProjectionMask;
+use parquet::file::footer::parse_metadata;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use serde::Serialize;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, UnknownColumn};
+use crate::footer::check_footer;
+use crate::utils::{check_path_present, open_file};
+
+// measures how fast a file can be read, to compare layouts and track regressions
+#[derive(Parser, Debug)]
+pub struct BenchCommandArgs {
+    file: PathBuf,
+
+    #[arg(short, long, default_value = "5", help = "number of measured runs of each benchmark")]
+    runs: usize,
+
+    #[arg(short, long, default_value = "1", help = "number of unmeasured runs before the measured ones")]
+    warmup: usize,
+
+    #[arg(
+        short,
+        long,
+        default_values = ["1024", "8192"],
+        value_delimiter = ',',
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        help = "batch sizes of the arrow reader, the first one is used to decode single columns"
+    )]
+    batch_size: Vec<usize>,
+
+    #[arg(short, long, help = "only decode these top level columns on their own")]
+    column: Vec<String>,
+
+    #[arg(long, help = "skip the row iterator, which is much slower than the arrow reader")]
+    no_row_iter: bool,
+
+    #[arg(short, long, help = "print the measures as JSON")]
+    json: bool,
+}
+
+/// Timings of the runs of a benchmark, in milliseconds.
+#[derive(Serialize, Debug)]
+struct Summary {
+    min: f64,
+    max: f64,
+    mean: f64,
+    median: f64,
+    stddev: f64,
+}
+
+impl Summary {
+    fn new(runs: &[f64]) -> Self {
+        let mut sorted = runs.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let n = sorted.len();
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let median = match n % 2 {
+            0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0,
+            _ => sorted[n / 2],
+        };
+        // sample standard deviation, nothing to tell from a single run
+        let variance = match n {
+            1 => 0.0,
+            _ => sorted.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1) as f64,
+        };
+        Summary { min: sorted[0], max: sorted[n - 1], mean, median, stddev: variance.sqrt() }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Measure {
+    name: String,
+    /// Rows read by each run, none for the footer.
+    rows: Option<i64>,
+    /// Bytes of the file read by each run, compressed.
+    bytes: u64,
+    runs: Vec<f64>,
+    summary: Summary,
+    /// Rows per second, from the median run.
+    rows_per_second: Option<f64>,
+    /// Megabytes of the file per second, from the median run.
+    megabytes_per_second: f64,
+}
+
+#[derive(Serialize, Debug)]
+struct Bench {
+    file: String,
+    runs: usize,
+    warmup: usize,
+    measures: Vec<Measure>,
+}
+
+// runs a benchmark, the closure returns the rows it read so reading can not be optimized out
+fn measure<F>(
+    name: String,
+    bytes: u64,
+    runs: usize,
+    warmup: usize,
+    mut run: F,
+) -> Result<Measure, PQRSError>
+where
+    F: FnMut() -> Result<Option<i64>, PQRSError>,
+{
+    debug!("Benchmarking {}", name);
+    for _ in 0..warmup {
+        run()?;
+    }
+    let mut timings = vec![];
+    let mut rows = None;
+    for _ in 0..runs {
+        let start = Instant::now();
+        rows = run()?;
+        timings.push(start.elapsed().as_secs_f64() * 1000.0);
+    }
+
+    let summary = Summary::new(&timings);
+    let seconds = summary.median / 1000.0;
+    Ok(Measure {
+        name,
+        rows,
+        bytes,
+        rows_per_second: rows.map(|r| r as f64 / seconds),
+        megabytes_per_second: bytes as f64 / seconds / 1e6,
+        runs: timings,
+        summary,
+    })
+}
+
+fn scan_rows(path: &Path) -> Result<Option<i64>, PQRSError> {
+    let reader = SerializedFileReader::new(open_file(path)?)?;
+    let mut rows = 0;
+    for row in reader.get_row_iter(None)? {
+        std::hint::black_box(row);
+        rows += 1;
+    }
+    Ok(Some(rows))
+}
+
+fn scan_batches(path: &Path, batch_size: usize, roots: Option<usize>) -> Result<Option<i64>, PQRSError> {
+    let mut builder = ParquetRecordBatchReaderBuilder::try_new(open_file(path)?)?.with_batch_size(batch_size);
+    if let Some(root) = roots {
+        let mask = ProjectionMask::roots(builder.parquet_schema(), [root]);
+        builder = builder.with_projection(mask);
+    }
+    let mut rows = 0;
+    for batch in builder.build()? {
+        rows += std::hint::black_box(batch?).num_rows() as i64;
+    }
+    Ok(Some(rows))
+}
+
+fn print_bench(bench: &Bench) {
+    println!(
+        "Benchmark of {}, {} runs after {} warmup runs, times in ms:",
+        bench.file, bench.runs, bench.warmup
+    );
+    println!(
+        "{:<32} {:>10} {:>10} {:>10} {:>10} {:>10} {:>14} {:>10}",
+        "", "min", "median", "mean", "max", "stddev", "rows/s", "MB/s"
+    );
+    for measure in &bench.measures {
+        let summary = &measure.summary;
+        println!(
+            "{:<32} {:>10.3} {:>10.3} {:>10.3} {:>10.3} {:>10.3} {:>14} {:>10.2}",
+            measure.name,
+            summary.min,
+            summary.median,
+            summary.mean,
+            summary.max,
+            summary.stddev,
+            measure.rows_per_second.map_or("-".to_string(), |r| format!("{:.0}", r)),
+            measure.megabytes_per_second
+        );
+    }
+}
+
+pub(crate) fn execute(opts: BenchCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file name to read is: {}", opts.file.display());
+    debug!("Runs: {}, warmup: {}, batch sizes: {:?}", opts.runs, opts.warmup, opts.batch_size);
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file.to_path_buf()));
+    }
+    let runs = opts.runs.max(1);
+    let footer = check_footer(&open_file(&opts.file)?)?;
+    let reader = SerializedFileReader::new(open_file(&opts.file)?)?;
+    let metadata = reader.metadata();
+    let schema = metadata.file_metadata().schema_descr();
+    let root_fields = schema.root_schema().get_fields();
+    for column in &opts.column {
+        if !root_fields.iter().any(|f| f.name() == column) {
+            return Err(UnknownColumn(column.to_string()));
+        }
+    }
+    let data_size = metadata.row_groups().iter().map(|r| r.compressed_size() as u64).sum::<u64>();
+
+    let mut measures = vec![];
+    let footer_size = footer.metadata_length.unwrap_or_default() as u64 + 8;
+    measures.push(measure("footer".to_string(), footer_size, runs, opts.warmup, || {
+        std::hint::black_box(parse_metadata(&open_file(&opts.file)?)?);
+        Ok(None)
+    })?);
+    if !opts.no_row_iter {
+        measures.push(measure("row iterator".to_string(), data_size, runs, opts.warmup, || {
+            scan_rows(&opts.file)
+        })?);
+    }
+    for &batch_size in &opts.batch_size {
+        let name = format!("arrow, batches of {}", batch_size);
+        measures.push(measure(name, data_size, runs, opts.warmup, || {
+            scan_batches(&opts.file, batch_size, None)
+        })?);
+    }
+
+    let batch_size = opts.batch_size.first().copied().unwrap_or(8192);
+    for (root, field) in root_fields.iter().enumerate() {
+        if !opts.column.is_empty() && !opts.column.iter().any(|c| c == field.name()) {
+            continue;
+        }
+        let column_size = metadata
+            .row_groups()
+            .iter()
+            .flat_map(|r| r.columns().iter().enumerate())
+            .filter(|(i, _)| schema.get_column_root_idx(*i) == root)
+            .map(|(_, c)| c.compressed_size() as u64)
+            .sum();
+        let name = format!("column {}", field.name());
+        measures.push(measure(name, column_size, runs, opts.warmup, || {
+            scan_batches(&opts.file, batch_size, Some(root))
+        })?);
+    }
+
+    let bench = Bench { file: opts.file.display().to_string(), runs, warmup: opts.warmup, measures };
+    if opts.json {
+        println!("{}", serde_json::to_string(&bench)?);
+    } else {
+        print_bench(&bench);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+
+use clap::{Parser};
+use log::debug;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+
+use crate::bloom::{bloom_filter_check, bloom_filter_reader, read_bloom_filter_header};
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, UnknownColumn};
+use crate::utils::{check_path_present, open_file};
+
+// prints the bloom filters of the parquet file and probes them for values
+#[derive(Parser, Debug)]
+pub struct BloomCommandArgs {
+    file: PathBuf,
+
+    #[arg(short, long, help = "only look at the bloom filters of this column, given by its dotted path")]
+    column: Option<String>,
+
+    #[arg(short, long = "value", requires = "column", help = "print which row groups might contain this value of the column")]
+    values: Vec<String>,
+}
+
+pub(crate) fn execute(opts: BloomCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file name to read is: {}", opts.file.display());
+    debug!("Column: {:?}, values: {:?}", opts.column, opts.values);
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file.to_path_buf()));
+    }
+
+    let file = open_file(&opts.file)?;
+    let parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
+    let metadata = parquet_reader.metadata();
+    let schema = metadata.file_metadata().schema_descr();
+
+    let columns = match &opts.column {
+        Some(column) => {
+            let index = schema
+                .columns()
+                .iter()
+                .position(|c| &c.path().string() == column)
+                .ok_or_else(|| UnknownColumn(column.to_string()))?;
+            vec![index]
+        }
+        None => (0..schema.num_columns()).collect(),
+    };
+
+    if opts.values.is_empty() {
+        for (rg, row_group) in metadata.row_groups().iter().enumerate() {
+            for &i in &columns {
+                let column = row_group.column(i);
+                match column.bloom_filter_offset() {
+                    Some(offset) => {
+                        let (header, header_size) = read_bloom_filter_header(&file, offset as u64)?;
+                        println!(
+                            "Row group {}, column {}: bloom filter @ {}, {} bytes ({} header, {} bitset)",
+                            rg,
+                            column.column_path().string(),
+                            offset,
+                            header_size + header.num_bytes as usize,
+                            header_size,
+                            header.num_bytes
+                        );
+                    }
+                    None => println!(
+                        "Row group {}, column {}: no bloom filter",
+                        rg,
+                        column.column_path().string()
+                    ),
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    let i = columns[0];
+    let descr = schema.column(i);
+    let parquet_reader = bloom_filter_reader(file.try_clone()?, &columns)?;
+    for value in &opts.values {
+        let mut candidates = vec![];
+        for rg in 0..metadata.num_row_groups() {
+            let row_group_reader = parquet_reader.get_row_group(rg)?;
+            match row_group_reader.get_column_bloom_filter(i) {
+                Some(sbbf) => {
+                    if bloom_filter_check(sbbf, &descr, value)? {
+                        println!("Row group {}: {} might be present", rg, value);
+                        candidates.push(rg);
+                    } else {
+                        println!("Row group {}: {} is absent", rg, value);
+                    }
+                }
+                None => {
+                    println!("Row group {}: no bloom filter, {} might be present", rg, value);
+                    candidates.push(rg);
+                }
+            }
+        }
+
+        let row_groups = candidates.iter().map(|rg| rg.to_string()).collect::<Vec<_>>();
+        println!(
+            "Row groups which might contain {}: [{}]",
+            value,
+            row_groups.join(", ")
+        );
+    }
+
+    Ok(())
+}
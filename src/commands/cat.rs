@@ -8,7 +8,9 @@ use walkdir::WalkDir;
 
 use crate::errors::PQRSError;
 use crate::errors::PQRSError::FileNotFound;
-use crate::utils::{check_path_present, is_hidden, open_file, print_rows};
+use crate::geo::{BoundingBox, GeometryFormat};
+use crate::timestamp::TimestampArgs;
+use crate::utils::{check_path_present, Filter, is_hidden, open_file, print_rows, RowOptions, VirtualColumn};
 use crate::utils::Formats;
 
 #[derive(Parser, Debug)]
@@ -22,12 +24,27 @@ pub struct CatCommandArgs {
     json: bool,
     #[clap(short, long)]
     quiet: bool,
-    #[clap(short, long, conflicts_with_all = ["csv", "json"], default_value = "false", help = "print the timestamp in long value in default mode")]
-    raw_timestamp: bool,
+    #[command(flatten)]
+    timestamps: TimestampArgs,
+    #[clap(long, value_delimiter = ',', help = "top level columns to print, virtual columns included")]
+    columns: Option<Vec<String>>,
+    #[clap(short = 'V', long = "virtual-columns", value_enum, value_delimiter = ',', help = "append columns telling the file, row group and row index of each record")]
+    virtual_columns: Vec<VirtualColumn>,
+    #[clap(short, long = "filter", value_name = "COLUMN=VALUE", help = "only print the records whose column, as printed, equals the value")]
+    filters: Vec<Filter>,
+    #[clap(long, value_enum, help = "print the WKB geometries of GeoParquet files as WKT or GeoJSON")]
+    geometry: Option<GeometryFormat>,
+    #[clap(long, value_name = "XMIN,YMIN,XMAX,YMAX", help = "only print the records whose primary geometry intersects the box")]
+    bbox: Option<BoundingBox>,
     locations: Vec<PathBuf>,
 }
 
 pub(crate) fn execute(opts: CatCommandArgs) -> Result<(), PQRSError> {
+    let options = RowOptions {
+        geometry: opts.geometry,
+        bbox: opts.bbox,
+        ..RowOptions::new(opts.timestamps.options()?, opts.columns, opts.virtual_columns, opts.filters)
+    };
     let format = if opts.json {
         Formats::Json
     } else if opts.csv_no_header {
@@ -85,7 +102,7 @@ pub(crate) fn execute(opts: CatCommandArgs) -> Result<(), PQRSError> {
             eprintln!("{}", info_string);
             eprintln!("{}\n", "#".repeat(length));
         }
-        print_rows(file, None, format, opts.raw_timestamp)?;
+        print_rows(file, file_name, None, format, &options)?;
     }
 
     Ok(())
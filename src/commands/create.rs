@@ -0,0 +1,135 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::new_null_array;
+use arrow::csv::reader::Format;
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use clap::{Parser, ValueEnum};
+use log::debug;
+use parquet::arrow::{parquet_to_arrow_schema, ArrowWriter};
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::types::SchemaDescriptor;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileExists, FileNotFound, InvalidSchema, UnknownColumn};
+use crate::utils::{check_path_present, persist_output, read_schema, temporary_output, WriterArgs};
+
+// writes a file of a schema, empty or holding the rows of a JSON or CSV file
+#[derive(Parser, Debug)]
+#[command(about = "write a parquet file of a schema, empty or holding rows from JSON or CSV", long_about = None)]
+pub struct CreateCommandArgs {
+    #[arg(
+        short,
+        long,
+        help = "a message type as `schema` prints it, the output of `schema --json`, a JSON Schema or a Parquet file"
+    )]
+    schema: PathBuf,
+
+    #[arg(short, long, help = "rows to write, as JSON lines or CSV with a header")]
+    input: Option<PathBuf>,
+
+    #[arg(long, value_enum, help = "format of the input, from its extension by default")]
+    input_format: Option<InputFormat>,
+
+    #[clap(flatten)]
+    writer: WriterArgs,
+
+    output: PathBuf,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+enum InputFormat {
+    Json,
+    Csv,
+}
+
+type Batches = Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>>>;
+
+// the CSV reader takes the columns in order, they are read in the order of the header then
+// arranged as the schema, the columns missing from the header are null
+fn csv_batches(input: &Path, schema: SchemaRef, batch_size: usize) -> Result<Batches, PQRSError> {
+    let (header, _) = Format::default().with_header(true).infer_schema(File::open(input)?, Some(0))?;
+    let fields = header
+        .fields()
+        .iter()
+        .map(|f| schema.field_with_name(f.name()).cloned().map_err(|_| UnknownColumn(f.name().to_string())))
+        .collect::<Result<Vec<_>, _>>()?;
+    let reader = arrow::csv::ReaderBuilder::new(Arc::new(Schema::new(fields)))
+        .has_header(true)
+        .with_batch_size(batch_size)
+        .build(File::open(input)?)?;
+    Ok(Box::new(reader.map(move |batch| {
+        let batch = batch?;
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|f| match batch.column_by_name(f.name()) {
+                Some(column) => column.clone(),
+                None => new_null_array(f.data_type(), batch.num_rows()),
+            })
+            .collect();
+        RecordBatch::try_new(schema.clone(), columns)
+    })))
+}
+
+fn input_format(opts: &CreateCommandArgs, input: &Path) -> InputFormat {
+    if let Some(format) = opts.input_format {
+        return format;
+    }
+    match input.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+        Some("csv") => InputFormat::Csv,
+        _ => InputFormat::Json,
+    }
+}
+
+pub(crate) fn execute(opts: CreateCommandArgs) -> Result<(), PQRSError> {
+    debug!("The schema file is: {}", opts.schema.display());
+    debug!("The input file is: {:?}", opts.input);
+    debug!("The file name to write to: {}", opts.output.display());
+
+    if check_path_present(&opts.output) {
+        return Err(FileExists(opts.output.to_path_buf()));
+    }
+    for path in [Some(&opts.schema), opts.input.as_ref()].into_iter().flatten() {
+        if !check_path_present(path) {
+            return Err(FileNotFound(path.to_path_buf()));
+        }
+    }
+
+    let definition = read_schema(&opts.schema)?;
+    if !definition.schema.is_group() || definition.schema.get_fields().is_empty() {
+        return Err(InvalidSchema(format!("{} defines no columns", opts.schema.display())));
+    }
+    let props = opts.writer.properties(definition.key_value_metadata)?;
+
+    // without rows the schema is written as is
+    let temporary = temporary_output(&opts.output)?;
+    let Some(input) = &opts.input else {
+        let writer = SerializedFileWriter::new(temporary.as_file().try_clone()?, definition.schema, Arc::new(props))?;
+        writer.close()?;
+        return persist_output(temporary, &opts.output);
+    };
+
+    // the rows are decoded to the Arrow types of the columns, which the writer lays out its way
+    let descriptor = SchemaDescriptor::new(definition.schema);
+    let arrow_schema = Arc::new(parquet_to_arrow_schema(&descriptor, None)?);
+    let batches: Batches = match input_format(&opts, input) {
+        InputFormat::Json => Box::new(
+            arrow::json::ReaderBuilder::new(arrow_schema.clone())
+                .with_batch_size(opts.writer.batch_size())
+                .build(BufReader::new(File::open(input)?))?,
+        ),
+        InputFormat::Csv => csv_batches(input, arrow_schema.clone(), opts.writer.batch_size())?,
+    };
+    let mut writer = ArrowWriter::try_new(temporary.as_file().try_clone()?, arrow_schema, Some(props))?;
+    for batch in batches {
+        writer.write(&batch?)?;
+    }
+    writer.close()?;
+
+    persist_output(temporary, &opts.output)
+}
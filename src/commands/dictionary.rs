@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+
+use clap::{Parser};
+use log::debug;
+use parquet::basic::Encoding;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, UnknownColumn};
+use crate::pages::{dictionary_values, read_page_headers};
+use crate::utils::{check_path_present, open_file};
+
+// prints the dictionary of a column in every row group and detects dictionary fallback
+#[derive(Parser, Debug)]
+pub struct DictionaryCommandArgs {
+    file: PathBuf,
+
+    #[arg(short, long, help = "the column to inspect, given by its dotted path")]
+    column: String,
+
+    #[arg(short, long, help = "print at most this many dictionary values per row group")]
+    limit: Option<usize>,
+}
+
+fn is_dictionary_encoding(encoding: Option<Encoding>) -> bool {
+    matches!(encoding, Some(Encoding::PLAIN_DICTIONARY) | Some(Encoding::RLE_DICTIONARY))
+}
+
+pub(crate) fn execute(opts: DictionaryCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file name to read is: {}", opts.file.display());
+    debug!("Column: {}", opts.column);
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file.to_path_buf()));
+    }
+
+    let file = open_file(&opts.file)?;
+    let parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
+    let metadata = parquet_reader.metadata();
+    let column_index = metadata
+        .file_metadata()
+        .schema_descr()
+        .columns()
+        .iter()
+        .position(|c| c.path().string() == opts.column)
+        .ok_or_else(|| UnknownColumn(opts.column.to_string()))?;
+
+    let mut fallbacks = vec![];
+    for (rg, row_group) in metadata.row_groups().iter().enumerate() {
+        let headers = read_page_headers(&file, row_group.column(column_index))?;
+
+        match headers.iter().find(|h| h.header.dictionary_page_header.is_some()) {
+            Some(dictionary) => println!(
+                "Row group {}: dictionary page @ {}, {}/{} bytes compressed/uncompressed, {} entries",
+                rg,
+                dictionary.offset,
+                dictionary.header.compressed_page_size,
+                dictionary.header.uncompressed_page_size,
+                dictionary.num_values().unwrap_or_default()
+            ),
+            None => println!("Row group {}: no dictionary page", rg),
+        }
+
+        let data_pages = headers.iter().filter(|h| h.is_data_page()).collect::<Vec<_>>();
+        let encoded = data_pages.iter().filter(|h| is_dictionary_encoding(h.encoding())).count();
+        println!(
+            "  data pages: {} dictionary encoded, {} with other encodings",
+            encoded,
+            data_pages.len() - encoded
+        );
+
+        // the writer falls back for good once the dictionary grows too large, so any page
+        // after a dictionary encoded one that is not dictionary encoded itself is a fallback
+        let first_encoded = data_pages.iter().position(|h| is_dictionary_encoding(h.encoding()));
+        let fallback = first_encoded.and_then(|first| {
+            data_pages
+                .iter()
+                .enumerate()
+                .skip(first)
+                .find(|(_, h)| !is_dictionary_encoding(h.encoding()))
+        });
+        if let Some((page, header)) = fallback {
+            println!(
+                "  fallback: data page {} @ {} is {} after {} dictionary encoded pages",
+                page,
+                header.offset,
+                header.encoding().map_or(String::from("UNKNOWN"), |e| e.to_string()),
+                page - first_encoded.unwrap_or_default()
+            );
+            fallbacks.push(rg);
+        }
+
+        let row_group_reader = parquet_reader.get_row_group(rg)?;
+        if let Some(mut values) = dictionary_values(row_group_reader.as_ref(), column_index)? {
+            let total = values.len();
+            if let Some(limit) = opts.limit {
+                values.truncate(limit);
+            }
+            let more = if values.len() < total {
+                format!(", ... {} more", total - values.len())
+            } else {
+                String::new()
+            };
+            println!("  values: [{}{}]", values.join(", "), more);
+        }
+    }
+
+    println!();
+    if fallbacks.is_empty() {
+        println!("No dictionary fallback in column {}", opts.column);
+    } else {
+        let row_groups = fallbacks.iter().map(|rg| rg.to_string()).collect::<Vec<_>>();
+        println!(
+            "Dictionary fallback in column {} in row groups: {}",
+            opts.column,
+            row_groups.join(", ")
+        );
+    }
+
+    Ok(())
+}
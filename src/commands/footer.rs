@@ -0,0 +1,337 @@
+use std::path::PathBuf;
+
+use clap::{Parser};
+use log::debug;
+use parquet::basic::{ConvertedType, Encoding, PageType, Repetition, Type as PhysicalType};
+use parquet::format;
+use serde::Serialize;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::FileNotFound;
+use crate::footer::{check_footer, codec_name, read_file_metadata, FooterCheck};
+use crate::pages::{bytes_text, statistics_value};
+use crate::utils::{check_path_present, open_file};
+
+// prints the whole footer of the parquet file as JSON
+#[derive(Parser, Debug)]
+pub struct FooterCommandArgs {
+    files: Vec<PathBuf>,
+
+    #[arg(short, long, help = "indent the JSON output")]
+    pretty: bool,
+}
+
+/// The footer of one file. Field names follow parquet.thrift, enums are printed by name and
+/// fields missing from the file are null.
+#[derive(Serialize, Debug)]
+struct Footer {
+    file: String,
+    /// Magic bytes and metadata length checks.
+    footer: FooterCheck,
+    /// What is wrong with the footer, empty for a valid file.
+    problems: Vec<String>,
+    /// The decoded file metadata, null when the footer is invalid or can not be decoded.
+    metadata: Option<FileMetaData>,
+}
+
+#[derive(Serialize, Debug)]
+struct FileMetaData {
+    version: i32,
+    num_rows: i64,
+    created_by: Option<String>,
+    key_value_metadata: Option<Vec<KeyValue>>,
+    /// The schema flattened depth first, groups are followed by their `num_children` fields.
+    schema: Vec<SchemaElement>,
+    row_groups: Vec<RowGroup>,
+    /// TYPE_DEFINED_ORDER per leaf column, when present.
+    column_orders: Option<Vec<String>>,
+    encrypted: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct KeyValue {
+    key: String,
+    value: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct SchemaElement {
+    name: String,
+    /// Physical type, null for groups.
+    #[serde(rename = "type")]
+    physical_type: Option<String>,
+    type_length: Option<i32>,
+    repetition_type: Option<String>,
+    num_children: Option<i32>,
+    converted_type: Option<String>,
+    scale: Option<i32>,
+    precision: Option<i32>,
+    field_id: Option<i32>,
+    /// Logical type with its parameters, such as `DECIMAL(10,2)` or `TIMESTAMP(MILLIS,true)`.
+    logical_type: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct RowGroup {
+    ordinal: Option<i16>,
+    num_rows: i64,
+    /// Uncompressed size of all the column data.
+    total_byte_size: i64,
+    total_compressed_size: Option<i64>,
+    file_offset: Option<i64>,
+    sorting_columns: Option<Vec<SortingColumn>>,
+    columns: Vec<ColumnChunk>,
+}
+
+#[derive(Serialize, Debug)]
+struct SortingColumn {
+    column_idx: i32,
+    descending: bool,
+    nulls_first: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct ColumnChunk {
+    /// Path of the leaf column, joined with dots.
+    path: String,
+    /// Set when the column data lives in another file.
+    file_path: Option<String>,
+    file_offset: i64,
+    #[serde(rename = "type")]
+    physical_type: Option<String>,
+    encodings: Vec<String>,
+    codec: Option<String>,
+    num_values: Option<i64>,
+    total_uncompressed_size: Option<i64>,
+    total_compressed_size: Option<i64>,
+    data_page_offset: Option<i64>,
+    index_page_offset: Option<i64>,
+    dictionary_page_offset: Option<i64>,
+    bloom_filter_offset: Option<i64>,
+    offset_index_offset: Option<i64>,
+    offset_index_length: Option<i32>,
+    column_index_offset: Option<i64>,
+    column_index_length: Option<i32>,
+    encoding_stats: Option<Vec<PageEncodingStats>>,
+    statistics: Option<Statistics>,
+    key_value_metadata: Option<Vec<KeyValue>>,
+}
+
+#[derive(Serialize, Debug)]
+struct PageEncodingStats {
+    page_type: String,
+    encoding: String,
+    count: i32,
+}
+
+/// Min and max values are decoded from their physical type, byte arrays are printed as text
+/// when they are valid UTF-8 and as hex otherwise.
+#[derive(Serialize, Debug)]
+struct Statistics {
+    min_value: Option<String>,
+    max_value: Option<String>,
+    null_count: Option<i64>,
+    distinct_count: Option<i64>,
+    /// Deprecated min, ordered as signed values whatever the logical type.
+    min: Option<String>,
+    /// Deprecated max, ordered as signed values whatever the logical type.
+    max: Option<String>,
+}
+
+// the name of a thrift enum value, from its counterpart in parquet::basic
+fn name<T: TryFrom<F> + std::fmt::Display, F: Copy + std::fmt::Debug>(value: F) -> String {
+    match T::try_from(value) {
+        Ok(value) => value.to_string(),
+        Err(_) => format!("UNKNOWN({:?})", value),
+    }
+}
+
+fn time_unit_name(unit: &format::TimeUnit) -> &'static str {
+    match unit {
+        format::TimeUnit::MILLIS(_) => "MILLIS",
+        format::TimeUnit::MICROS(_) => "MICROS",
+        format::TimeUnit::NANOS(_) => "NANOS",
+    }
+}
+
+fn logical_type_name(logical_type: &format::LogicalType) -> String {
+    match logical_type {
+        format::LogicalType::STRING(_) => String::from("STRING"),
+        format::LogicalType::MAP(_) => String::from("MAP"),
+        format::LogicalType::LIST(_) => String::from("LIST"),
+        format::LogicalType::ENUM(_) => String::from("ENUM"),
+        format::LogicalType::DECIMAL(t) => format!("DECIMAL({},{})", t.precision, t.scale),
+        format::LogicalType::DATE(_) => String::from("DATE"),
+        format::LogicalType::TIME(t) => {
+            format!("TIME({},{})", time_unit_name(&t.unit), t.is_adjusted_to_u_t_c)
+        }
+        format::LogicalType::TIMESTAMP(t) => {
+            format!("TIMESTAMP({},{})", time_unit_name(&t.unit), t.is_adjusted_to_u_t_c)
+        }
+        format::LogicalType::INTEGER(t) => format!("INTEGER({},{})", t.bit_width, t.is_signed),
+        format::LogicalType::UNKNOWN(_) => String::from("UNKNOWN"),
+        format::LogicalType::JSON(_) => String::from("JSON"),
+        format::LogicalType::BSON(_) => String::from("BSON"),
+        format::LogicalType::UUID(_) => String::from("UUID"),
+    }
+}
+
+fn key_values(key_values: &Option<Vec<format::KeyValue>>) -> Option<Vec<KeyValue>> {
+    key_values.as_ref().map(|kvs| {
+        kvs.iter()
+            .map(|kv| KeyValue { key: kv.key.clone(), value: kv.value.clone() })
+            .collect()
+    })
+}
+
+fn statistics(statistics: &format::Statistics, physical_type: Option<PhysicalType>) -> Statistics {
+    let value = |v: &Vec<u8>| match physical_type {
+        Some(physical_type) => statistics_value(v, physical_type),
+        None => bytes_text(v),
+    };
+    Statistics {
+        min_value: statistics.min_value.as_ref().map(value),
+        max_value: statistics.max_value.as_ref().map(value),
+        null_count: statistics.null_count,
+        distinct_count: statistics.distinct_count,
+        min: statistics.min.as_ref().map(value),
+        max: statistics.max.as_ref().map(value),
+    }
+}
+
+fn column_chunk(chunk: &format::ColumnChunk) -> ColumnChunk {
+    let meta = chunk.meta_data.as_ref();
+    let physical_type = meta.and_then(|m| PhysicalType::try_from(m.type_).ok());
+    ColumnChunk {
+        path: meta.map(|m| m.path_in_schema.join(".")).unwrap_or_default(),
+        file_path: chunk.file_path.clone(),
+        file_offset: chunk.file_offset,
+        physical_type: meta.map(|m| name::<PhysicalType, _>(m.type_)),
+        encodings: meta
+            .map(|m| m.encodings.iter().map(|e| name::<Encoding, _>(*e)).collect())
+            .unwrap_or_default(),
+        codec: meta.map(|m| codec_name(m.codec)),
+        num_values: meta.map(|m| m.num_values),
+        total_uncompressed_size: meta.map(|m| m.total_uncompressed_size),
+        total_compressed_size: meta.map(|m| m.total_compressed_size),
+        data_page_offset: meta.map(|m| m.data_page_offset),
+        index_page_offset: meta.and_then(|m| m.index_page_offset),
+        dictionary_page_offset: meta.and_then(|m| m.dictionary_page_offset),
+        bloom_filter_offset: meta.and_then(|m| m.bloom_filter_offset),
+        offset_index_offset: chunk.offset_index_offset,
+        offset_index_length: chunk.offset_index_length,
+        column_index_offset: chunk.column_index_offset,
+        column_index_length: chunk.column_index_length,
+        encoding_stats: meta.and_then(|m| m.encoding_stats.as_ref()).map(|stats| {
+            stats
+                .iter()
+                .map(|s| PageEncodingStats {
+                    page_type: name::<PageType, _>(s.page_type),
+                    encoding: name::<Encoding, _>(s.encoding),
+                    count: s.count,
+                })
+                .collect()
+        }),
+        statistics: meta
+            .and_then(|m| m.statistics.as_ref())
+            .map(|s| statistics(s, physical_type)),
+        key_value_metadata: meta.and_then(|m| key_values(&m.key_value_metadata)),
+    }
+}
+
+fn file_metadata(metadata: &format::FileMetaData) -> FileMetaData {
+    FileMetaData {
+        version: metadata.version,
+        num_rows: metadata.num_rows,
+        created_by: metadata.created_by.clone(),
+        key_value_metadata: key_values(&metadata.key_value_metadata),
+        schema: metadata
+            .schema
+            .iter()
+            .map(|element| SchemaElement {
+                name: element.name.clone(),
+                physical_type: element.type_.map(name::<PhysicalType, _>),
+                type_length: element.type_length,
+                repetition_type: element.repetition_type.map(name::<Repetition, _>),
+                num_children: element.num_children,
+                converted_type: element.converted_type.map(|t| name::<ConvertedType, _>(Some(t))),
+                scale: element.scale,
+                precision: element.precision,
+                field_id: element.field_id,
+                logical_type: element.logical_type.as_ref().map(logical_type_name),
+            })
+            .collect(),
+        row_groups: metadata
+            .row_groups
+            .iter()
+            .map(|row_group| RowGroup {
+                ordinal: row_group.ordinal,
+                num_rows: row_group.num_rows,
+                total_byte_size: row_group.total_byte_size,
+                total_compressed_size: row_group.total_compressed_size,
+                file_offset: row_group.file_offset,
+                sorting_columns: row_group.sorting_columns.as_ref().map(|columns| {
+                    columns
+                        .iter()
+                        .map(|c| SortingColumn {
+                            column_idx: c.column_idx,
+                            descending: c.descending,
+                            nulls_first: c.nulls_first,
+                        })
+                        .collect()
+                }),
+                columns: row_group.columns.iter().map(column_chunk).collect(),
+            })
+            .collect(),
+        column_orders: metadata.column_orders.as_ref().map(|orders| {
+            orders
+                .iter()
+                .map(|order| match order {
+                    format::ColumnOrder::TYPEORDER(_) => String::from("TYPE_DEFINED_ORDER"),
+                })
+                .collect()
+        }),
+        encrypted: metadata.encryption_algorithm.is_some(),
+    }
+}
+
+pub(crate) fn execute(opts: FooterCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to read are: {:?}", opts.files);
+
+    for file_name in &opts.files {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    for file_name in &opts.files {
+        let file = open_file(file_name)?;
+        let check = check_footer(&file)?;
+        let mut problems = check.problems();
+        let metadata = match check.is_valid() {
+            true => match read_file_metadata(&file, &check) {
+                Ok(metadata) => Some(file_metadata(&metadata)),
+                Err(e) => {
+                    problems.push(format!("the file metadata can not be decoded: {:?}", e));
+                    None
+                }
+            },
+            false => None,
+        };
+
+        let footer = Footer {
+            file: file_name.display().to_string(),
+            footer: check,
+            problems,
+            metadata,
+        };
+        let footer_json = if opts.pretty {
+            serde_json::to_string_pretty(&footer)?
+        } else {
+            serde_json::to_string(&footer)?
+        };
+        println!("{}", footer_json);
+    }
+
+    Ok(())
+}
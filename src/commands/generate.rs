@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use clap::Parser;
+use log::debug;
+use parquet::arrow::{parquet_to_arrow_schema, ArrowWriter};
+use parquet::schema::types::SchemaDescriptor;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileExists, FileNotFound, InvalidSchema};
+use crate::sampling::sample_rng;
+use crate::synthetic::{ColumnSetting, Generator, GeneratorConfig, Length, RowGenerator};
+use crate::utils::{check_path_present, persist_output, read_schema, temporary_output, WriterArgs};
+
+// writes a file of synthetic rows following a schema
+#[derive(Parser, Debug)]
+#[command(about = "write a parquet file of synthetic rows following a schema", long_about = None)]
+pub struct GenerateCommandArgs {
+    #[arg(
+        short,
+        long,
+        help = "a Parquet file or a schema file as `create` reads them"
+    )]
+    schema: PathBuf,
+
+    #[arg(short = 'n', long, default_value = "1000", help = "number of rows to generate")]
+    rows: usize,
+
+    #[arg(long, help = "seed of the random generator, to generate the same rows again")]
+    seed: Option<u64>,
+
+    #[arg(
+        long = "values",
+        value_name = "COLUMN=GENERATOR",
+        help = "values of a column: uniform(MIN,MAX), normal(MEAN,STDDEV), sequence(START[,STEP]), pattern(TEXT) with # for a digit, ? for a letter and * for either, or choice(A|B|...)"
+    )]
+    values: Vec<ColumnSetting<Generator>>,
+
+    #[arg(long, default_value = "0", help = "fraction of null values of the optional columns")]
+    null_rate: f64,
+
+    #[arg(long, value_name = "COLUMN=RATE", help = "fraction of null values of a column")]
+    nulls: Vec<ColumnSetting<f64>>,
+
+    #[arg(long, value_name = "COLUMN=COUNT", help = "number of distinct values of a column")]
+    distinct: Vec<ColumnSetting<usize>>,
+
+    #[arg(
+        long,
+        value_name = "COLUMN=LENGTH",
+        help = "length of the lists, maps or binary values of a column: N, uniform(MIN,MAX) or normal(MEAN,STDDEV)"
+    )]
+    length: Vec<ColumnSetting<Length>>,
+
+    #[clap(flatten)]
+    writer: WriterArgs,
+
+    output: PathBuf,
+}
+
+pub(crate) fn execute(opts: GenerateCommandArgs) -> Result<(), PQRSError> {
+    debug!("The schema file is: {}", opts.schema.display());
+    debug!("The file name to write to: {}", opts.output.display());
+    debug!("Rows to generate: {}", opts.rows);
+
+    if check_path_present(&opts.output) {
+        return Err(FileExists(opts.output.to_path_buf()));
+    }
+    if !check_path_present(&opts.schema) {
+        return Err(FileNotFound(opts.schema.to_path_buf()));
+    }
+
+    let definition = read_schema(&opts.schema)?;
+    if !definition.schema.is_group() || definition.schema.get_fields().is_empty() {
+        return Err(InvalidSchema(format!("{} defines no columns", opts.schema.display())));
+    }
+    let props = opts.writer.properties(definition.key_value_metadata)?;
+    let descriptor = SchemaDescriptor::new(definition.schema);
+    let arrow_schema = Arc::new(parquet_to_arrow_schema(&descriptor, None)?);
+
+    let config = GeneratorConfig {
+        rows: opts.rows,
+        null_rate: opts.null_rate,
+        values: opts.values,
+        nulls: opts.nulls,
+        distinct: opts.distinct,
+        lengths: opts.length,
+    };
+    let mut generator = RowGenerator::new(arrow_schema.clone(), &config, sample_rng(opts.seed))?;
+
+    // the rows are drawn a batch at a time, so files of any size are written in constant memory
+    let temporary = temporary_output(&opts.output)?;
+    let mut writer = ArrowWriter::try_new(temporary.as_file().try_clone()?, arrow_schema, Some(props))?;
+    let mut remaining = opts.rows;
+    while remaining > 0 {
+        let rows = remaining.min(opts.writer.batch_size());
+        writer.write(&generator.batch(rows)?)?;
+        remaining -= rows;
+    }
+    writer.close()?;
+
+    persist_output(temporary, &opts.output)
+}
@@ -0,0 +1,224 @@
+use std::path::PathBuf;
+
+use arrow::array::{Array, BinaryArray};
+use clap::Parser;
+use log::debug;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ProjectionMask;
+use serde::Serialize;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, InvalidGeoMetadata};
+use crate::geo::{covering_row_group_bbox, parse_wkb, read_geo_metadata, BoundingBox, GeoMetadata};
+use crate::utils::{check_path_present, open_file};
+
+// validates the GeoParquet metadata of a file and tells the bounding box of each row group
+#[derive(Parser, Debug)]
+pub struct GeoCommandArgs {
+    file: PathBuf,
+
+    #[arg(short, long, help = "decode the geometries even when covering columns have statistics")]
+    decode: bool,
+
+    #[arg(short, long, value_name = "XMIN,YMIN,XMAX,YMAX", help = "tell which row groups may intersect the box")]
+    bbox: Option<BoundingBox>,
+
+    #[arg(short, long, help = "print the metadata, bounding boxes and problems as JSON")]
+    json: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct RowGroupBbox {
+    row_group: usize,
+    num_rows: i64,
+    bbox: BoundingBox,
+    /// Whether the box comes from the statistics of the covering columns or the geometries.
+    source: &'static str,
+    /// The geometry types found, when the geometries were decoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    geometry_types: Option<Vec<String>>,
+    /// Whether the row group may intersect the filtered box.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    intersects: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+struct GeoReport {
+    file: String,
+    metadata: GeoMetadata,
+    primary_bbox: BoundingBox,
+    row_groups: Vec<RowGroupBbox>,
+    problems: Vec<String>,
+}
+
+// the bounding box and geometry types of the WKB values of a row group
+fn decode_row_group(
+    opts: &GeoCommandArgs,
+    root: usize,
+    row_group: usize,
+    problems: &mut Vec<String>,
+) -> Result<(BoundingBox, Vec<String>), PQRSError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(open_file(&opts.file)?)?;
+    let mask = ProjectionMask::roots(builder.parquet_schema(), [root]);
+    let reader = builder.with_projection(mask).with_row_groups(vec![row_group]).build()?;
+
+    let mut bbox = BoundingBox::empty();
+    let mut geometry_types = vec![];
+    let mut invalid = 0;
+    for batch in reader {
+        let batch = batch?;
+        let values = batch.column(0).as_any().downcast_ref::<BinaryArray>().ok_or_else(|| {
+            InvalidGeoMetadata("the primary column is declared WKB but does not hold binary values".to_string())
+        })?;
+        for wkb in values.iter().flatten() {
+            match parse_wkb(wkb) {
+                Ok(geometry) => {
+                    bbox.merge(&geometry.bbox());
+                    let geometry_type = geometry.type_name();
+                    if !geometry_types.contains(&geometry_type) {
+                        geometry_types.push(geometry_type);
+                    }
+                }
+                Err(e) => {
+                    if invalid == 0 {
+                        problems.push(format!("row group {} holds invalid WKB: {}", row_group, e));
+                    }
+                    invalid += 1;
+                }
+            }
+        }
+    }
+    if invalid > 1 {
+        problems.push(format!("row group {} holds {} invalid WKB values in all", row_group, invalid));
+    }
+    geometry_types.sort();
+    Ok((bbox, geometry_types))
+}
+
+fn print_report(report: &GeoReport, filter: Option<&BoundingBox>) {
+    let geo = &report.metadata;
+    println!("GeoParquet {}, primary column {}", geo.version, geo.primary_column);
+    for (name, column) in &geo.columns {
+        let mut details = vec![column.encoding.to_string()];
+        if !column.geometry_types.is_empty() {
+            details.push(format!("types {}", column.geometry_types.join(", ")));
+        }
+        details.push(format!("CRS {}", column.crs_name()));
+        if let Some(edges) = &column.edges {
+            details.push(format!("{} edges", edges));
+        }
+        if let Some(bbox) = &column.bbox {
+            let values = bbox.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+            details.push(format!("bbox [{}]", values.join(", ")));
+        }
+        if let Some(covering) = column.covering_bbox() {
+            let paths = [&covering.xmin, &covering.ymin, &covering.xmax, &covering.ymax];
+            let paths = paths.iter().map(|p| p.join(".")).collect::<Vec<_>>();
+            details.push(format!("covering {}", paths.join(", ")));
+        }
+        println!("Column {}: {}", name, details.join(", "));
+    }
+
+    println!();
+    println!("Bounding boxes of {}: {}", geo.primary_column, report.primary_bbox);
+    for row_group in &report.row_groups {
+        let types = match &row_group.geometry_types {
+            Some(types) if !types.is_empty() => format!(", types {}", types.join(", ")),
+            _ => String::new(),
+        };
+        let pruned = match row_group.intersects {
+            Some(false) => ", pruned",
+            _ => "",
+        };
+        println!(
+            "  row group {}: {} rows, {} from the {}{}{}",
+            row_group.row_group, row_group.num_rows, row_group.bbox, row_group.source, types, pruned
+        );
+    }
+    if let Some(filter) = filter {
+        let selected = report.row_groups.iter().filter(|r| r.intersects == Some(true)).count();
+        println!("Row groups intersecting {}: {} of {}", filter, selected, report.row_groups.len());
+    }
+
+    println!();
+    match report.problems.is_empty() {
+        true => println!("No problems"),
+        false => {
+            println!("{} problems:", report.problems.len());
+            for problem in &report.problems {
+                println!("  {}", problem);
+            }
+        }
+    }
+}
+
+pub(crate) fn execute(opts: GeoCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file name to read is: {}", opts.file.display());
+    debug!("Decode geometries: {}, bbox filter: {:?}", opts.decode, opts.bbox);
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file.to_path_buf()));
+    }
+    let builder = ParquetRecordBatchReaderBuilder::try_new(open_file(&opts.file)?)?;
+    let metadata = builder.metadata().clone();
+    let schema = metadata.file_metadata().schema_descr();
+    let geo = read_geo_metadata(metadata.file_metadata())?
+        .ok_or_else(|| InvalidGeoMetadata(format!("{} has no geo metadata", opts.file.display())))?;
+    let mut problems = geo.validate(schema);
+
+    let column = geo.columns.get(&geo.primary_column);
+    let root = schema.root_schema().get_fields().iter().position(|f| f.name() == geo.primary_column);
+    let mut row_groups = vec![];
+    let mut primary_bbox = BoundingBox::empty();
+    if let (Some(column), Some(root)) = (column, root) {
+        let declared = column.bbox.as_deref().and_then(BoundingBox::from_values);
+        for (i, row_group) in metadata.row_groups().iter().enumerate() {
+            let covering = column.covering_bbox().and_then(|c| covering_row_group_bbox(row_group, c));
+            let (bbox, source, geometry_types) = match covering {
+                Some(bbox) if !opts.decode => (bbox, "covering statistics", None),
+                _ if column.encoding == "WKB" => {
+                    let (bbox, types) = decode_row_group(&opts, root, i, &mut problems)?;
+                    (bbox, "geometries", Some(types))
+                }
+                // native encodings are not decoded
+                _ => continue,
+            };
+
+            if let (Some(declared), false) = (declared, bbox.is_empty()) {
+                if !declared.contains(&bbox) {
+                    problems.push(format!("row group {} spans {}, out of the declared bbox {}", i, bbox, declared));
+                }
+            }
+            for geometry_type in geometry_types.iter().flatten() {
+                if !column.geometry_types.is_empty() && !column.geometry_types.contains(geometry_type) {
+                    problems.push(format!(
+                        "row group {} holds {} geometries, which are not declared",
+                        i, geometry_type
+                    ));
+                }
+            }
+
+            primary_bbox.merge(&bbox);
+            row_groups.push(RowGroupBbox {
+                row_group: i,
+                num_rows: row_group.num_rows(),
+                bbox,
+                source,
+                geometry_types,
+                intersects: opts.bbox.map(|filter| bbox.intersects(&filter)),
+            });
+        }
+    }
+
+    let report = GeoReport { file: opts.file.display().to_string(), metadata: geo, primary_bbox, row_groups, problems };
+    if opts.json {
+        println!("{}", serde_json::to_string(&report)?);
+    } else {
+        print_report(&report, opts.bbox.as_ref());
+    }
+
+    match report.problems.len() {
+        0 => Ok(()),
+        n => Err(InvalidGeoMetadata(format!("{} problems", n))),
+    }
+}
@@ -0,0 +1,136 @@
+use std::ops::Range;
+use std::path::PathBuf;
+
+use clap::{Parser};
+use log::debug;
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::serialized_reader::ReadOptionsBuilder;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, InvalidRowList, RowOutOfRange};
+use crate::timestamp::TimestampArgs;
+use crate::utils::{check_path_present, Formats, has_offset_index, locate_row, open_file, print_row_ranges};
+
+#[derive(Parser, Debug)]
+#[command(about = "show the records at the given row numbers", long_about = None)]
+pub struct GetCommandArgs {
+    #[clap(short, long, conflicts_with = "json")]
+    csv: bool,
+    #[clap(long = "no-header", requires = "csv", conflicts_with = "json")]
+    csv_no_header: bool,
+    #[clap(short, long, conflicts_with = "csv")]
+    json: bool,
+    #[clap(short = 'L', long, help = "print the row group and pages of each record to stderr")]
+    location: bool,
+    #[command(flatten)]
+    timestamps: TimestampArgs,
+    file: PathBuf,
+    #[clap(help = "zero based row numbers and inclusive ranges, e.g. 10,20,1000-1010")]
+    rows: String,
+}
+
+// parses `10,20,1000-1010` into sorted and merged ranges
+fn parse_rows(rows: &str) -> Result<Vec<Range<usize>>, PQRSError> {
+    let mut ranges = vec![];
+    for part in rows.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let parse = |s: &str| s.trim().parse::<usize>().map_err(|_| InvalidRowList(part.to_string()));
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (parse(start)?, parse(end)?),
+            None => (parse(part)?, parse(part)?),
+        };
+        let range = start..end.checked_add(1).ok_or_else(|| InvalidRowList(part.to_string()))?;
+        if range.is_empty() {
+            return Err(InvalidRowList(part.to_string()));
+        }
+        ranges.push(range);
+    }
+
+    if ranges.is_empty() {
+        return Err(InvalidRowList(rows.to_string()));
+    }
+
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<Range<usize>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    Ok(merged)
+}
+
+fn print_locations(metadata: &ParquetMetaData, ranges: &[Range<usize>]) {
+    let columns = metadata.file_metadata().schema_descr().columns();
+    for row in ranges.iter().flat_map(|r| r.clone()) {
+        let (row_group, position) = match locate_row(metadata, row) {
+            Some(location) => location,
+            None => continue,
+        };
+
+        let pages = match metadata.offset_index() {
+            Some(offset_index) => offset_index
+                .get(row_group)
+                .into_iter()
+                .flatten()
+                .zip(columns)
+                .map(|(locations, column)| {
+                    let page = locations
+                        .partition_point(|l| l.first_row_index <= position as i64)
+                        .saturating_sub(1);
+                    let offset = locations.get(page).map_or("unknown offset".to_string(), |l| l.offset.to_string());
+                    format!("{} #{} @ {}", column.path().string(), page, offset)
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            None => String::from("unknown, the file has no offset index"),
+        };
+
+        eprintln!("row {}: row group {}, row {} in group, pages: {}", row, row_group, position, pages);
+    }
+}
+
+pub fn execute(opts: GetCommandArgs) -> Result<(), PQRSError> {
+    let timestamps = opts.timestamps.options()?;
+    let format = if opts.json {
+        Formats::Json
+    } else if opts.csv_no_header {
+        Formats::CsvNoHeader
+    } else if opts.csv {
+        Formats::Csv
+    } else {
+        Formats::Default
+    };
+
+    debug!("The file name to read is: {}", opts.file.display());
+    debug!("Rows to print: {}", opts.rows);
+    debug!("output format: {}", format);
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file));
+    }
+
+    let ranges = parse_rows(&opts.rows)?;
+
+    let mut parquet_reader = SerializedFileReader::new(open_file(&opts.file)?)?;
+    if opts.location && has_offset_index(parquet_reader.metadata()) {
+        let read_options = ReadOptionsBuilder::new().with_page_index().build();
+        parquet_reader = SerializedFileReader::new_with_options(open_file(&opts.file)?, read_options)?;
+    }
+
+    let total_rows = parquet_reader.metadata().file_metadata().num_rows() as usize;
+    if let Some(last) = ranges.last() {
+        if last.end > total_rows {
+            return Err(RowOutOfRange(last.end - 1, total_rows));
+        }
+    }
+
+    if opts.location {
+        print_locations(parquet_reader.metadata(), &ranges);
+    }
+
+    let file = open_file(&opts.file)?;
+    print_row_ranges(file, &ranges, format, &timestamps)
+}
@@ -5,7 +5,9 @@ use log::debug;
 
 use crate::errors::PQRSError;
 use crate::errors::PQRSError::FileNotFound;
-use crate::utils::{check_path_present, Formats, open_file, print_rows};
+use crate::geo::GeometryFormat;
+use crate::timestamp::TimestampArgs;
+use crate::utils::{check_path_present, Formats, open_file, print_rows, RowOptions};
 
 #[derive(Parser, Debug)]
 pub struct HeadCommandArgs {
@@ -15,10 +17,19 @@ pub struct HeadCommandArgs {
     json: bool,
     #[clap(short = 'n', long, default_value = "5")]
     records: usize,
+    #[command(flatten)]
+    timestamps: TimestampArgs,
+    #[clap(long, value_enum, help = "print the WKB geometries of GeoParquet files as WKT or GeoJSON")]
+    geometry: Option<GeometryFormat>,
     file: PathBuf,
 }
 
 pub fn execute(opts: HeadCommandArgs) -> Result<(), PQRSError> {
+    let options = RowOptions {
+        timestamps: opts.timestamps.options()?,
+        geometry: opts.geometry,
+        ..Default::default()
+    };
     let format = if opts.json {
         Formats::Json
     } else if opts.csv {
@@ -37,5 +48,5 @@ pub fn execute(opts: HeadCommandArgs) -> Result<(), PQRSError> {
     }
 
     let file = open_file(&opts.file)?;
-    print_rows(file, Some(opts.records), format, false)
+    print_rows(file, &opts.file, Some(opts.records), format, &options)
 }
\ No newline at end of file
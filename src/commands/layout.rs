@@ -0,0 +1,300 @@
+use std::fs::File;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use clap::{Parser};
+use log::debug;
+use parquet::file::reader::{ChunkReader, FileReader, SerializedFileReader};
+use parquet::format::{ColumnChunk, ColumnMetaData};
+use thrift::protocol::{TCompactInputProtocol, TSerializable};
+
+use crate::bloom::read_bloom_filter_header;
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::FileNotFound;
+use crate::footer::{check_footer, FOOTER_SIZE, MAGIC};
+use crate::pages::{column_byte_range, read_page_headers, PageHeaderInfo};
+use crate::utils::{check_path_present, get_pretty_size, open_file};
+
+// prints where the bytes of the parquet file go, in file order
+#[derive(Parser, Debug)]
+pub struct LayoutCommandArgs {
+    file: PathBuf,
+
+    #[arg(short, long, help = "also draw the layout as a bar")]
+    bar: bool,
+
+    #[arg(short, long, default_value = "80", requires = "bar", help = "width of the bar in characters")]
+    width: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum RegionKind {
+    Magic,
+    DictionaryPage,
+    DataPages,
+    ColumnMetadata,
+    ColumnIndex,
+    OffsetIndex,
+    BloomFilter,
+    Footer,
+}
+
+impl RegionKind {
+    const ALL: [RegionKind; 8] = [
+        RegionKind::Magic,
+        RegionKind::DictionaryPage,
+        RegionKind::DataPages,
+        RegionKind::ColumnMetadata,
+        RegionKind::ColumnIndex,
+        RegionKind::OffsetIndex,
+        RegionKind::BloomFilter,
+        RegionKind::Footer,
+    ];
+
+    fn symbol(&self) -> char {
+        match self {
+            RegionKind::Magic => 'M',
+            RegionKind::DictionaryPage => 'd',
+            RegionKind::DataPages => 'D',
+            RegionKind::ColumnMetadata => 'm',
+            RegionKind::ColumnIndex => 'C',
+            RegionKind::OffsetIndex => 'O',
+            RegionKind::BloomFilter => 'B',
+            RegionKind::Footer => 'F',
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            RegionKind::Magic => "magic bytes",
+            RegionKind::DictionaryPage => "dictionary pages",
+            RegionKind::DataPages => "data pages",
+            RegionKind::ColumnMetadata => "column metadata",
+            RegionKind::ColumnIndex => "column indexes",
+            RegionKind::OffsetIndex => "offset indexes",
+            RegionKind::BloomFilter => "bloom filters",
+            RegionKind::Footer => "footer",
+        }
+    }
+}
+
+// a byte range of the file, end excluded
+#[derive(Debug)]
+struct Region {
+    start: u64,
+    end: u64,
+    kind: RegionKind,
+    label: String,
+}
+
+fn overlap(region: &Region, start: u64, end: u64) -> u64 {
+    region.end.min(end).saturating_sub(region.start.max(start))
+}
+
+// the column metadata is usually too large to read in one go when holding long statistics
+const COLUMN_METADATA_SIZE_LIMIT: u64 = 64 * 1024;
+
+fn thrift_size<T: TSerializable>(bytes: &[u8]) -> Option<u64> {
+    let mut cursor = Cursor::new(bytes);
+    let mut protocol = TCompactInputProtocol::new(&mut cursor);
+    T::read_from_in_protocol(&mut protocol).ok()?;
+    Some(cursor.position())
+}
+
+// size of the copy of the column metadata some writers store right after the pages of a
+// column chunk, parquet-cpp stores the whole column chunk, none when there is no such copy
+fn column_metadata_size(file: &File, offset: u64, end: u64) -> Option<u64> {
+    let length = COLUMN_METADATA_SIZE_LIMIT.min(end.saturating_sub(offset));
+    let bytes = file.get_bytes(offset, length as usize).ok()?;
+    thrift_size::<ColumnMetaData>(&bytes).or_else(|| thrift_size::<ColumnChunk>(&bytes))
+}
+
+fn print_bar(regions: &[Region], file_size: u64, width: usize) {
+    let width = width.max(1) as u64;
+    let bar = (0..width)
+        .map(|i| {
+            let start = i * file_size / width;
+            let end = ((i + 1) * file_size / width).max(start + 1);
+            // the kind owning most bytes of this slice of the file
+            RegionKind::ALL
+                .iter()
+                .map(|kind| {
+                    let bytes = regions
+                        .iter()
+                        .filter(|r| r.kind == *kind)
+                        .map(|r| overlap(r, start, end))
+                        .sum::<u64>();
+                    (bytes, kind)
+                })
+                .filter(|(bytes, _)| *bytes > 0)
+                .max_by_key(|(bytes, _)| *bytes)
+                .map_or('.', |(_, kind)| kind.symbol())
+        })
+        .collect::<String>();
+
+    println!();
+    println!("[{}]", bar);
+    let legend = RegionKind::ALL
+        .iter()
+        .map(|kind| format!("{} {}", kind.symbol(), kind.name()))
+        .collect::<Vec<_>>();
+    println!("{}, . unused", legend.join(", "));
+}
+
+pub(crate) fn execute(opts: LayoutCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file name to read is: {}", opts.file.display());
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file.to_path_buf()));
+    }
+
+    let file = open_file(&opts.file)?;
+    let check = check_footer(&file)?;
+    let parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
+    let metadata = parquet_reader.metadata();
+    let file_size = check.file_size;
+
+    let mut regions = vec![Region {
+        start: 0,
+        end: MAGIC.len() as u64,
+        kind: RegionKind::Magic,
+        label: String::from("header magic"),
+    }];
+    for (rg, row_group) in metadata.row_groups().iter().enumerate() {
+        for column in row_group.columns() {
+            let name = format!("row group {}, column {}", rg, column.column_path().string());
+
+            let headers = read_page_headers(&file, column)?;
+            let end_of = |h: &PageHeaderInfo| {
+                h.offset + h.header_size as u64 + h.header.compressed_page_size as u64
+            };
+            for header in headers.iter().filter(|h| h.header.dictionary_page_header.is_some()) {
+                regions.push(Region {
+                    start: header.offset,
+                    end: end_of(header),
+                    kind: RegionKind::DictionaryPage,
+                    label: format!("{}: dictionary page", name),
+                });
+            }
+            let data_pages = headers.iter().filter(|h| h.is_data_page()).collect::<Vec<_>>();
+            if let (Some(first), Some(last)) = (data_pages.first(), data_pages.last()) {
+                regions.push(Region {
+                    start: first.offset,
+                    end: end_of(last),
+                    kind: RegionKind::DataPages,
+                    label: format!("{}: {} data pages", name, data_pages.len()),
+                });
+            }
+
+            // the footer points to the copy of the column metadata, when there is one
+            let (chunk_start, chunk_length) = column_byte_range(column)?;
+            let metadata_start = column.file_offset() as u64;
+            let metadata_end = check.metadata_offset.unwrap_or(file_size);
+            if metadata_start == chunk_start + chunk_length {
+                if let Some(size) = column_metadata_size(&file, metadata_start, metadata_end) {
+                    regions.push(Region {
+                        start: metadata_start,
+                        end: metadata_start + size,
+                        kind: RegionKind::ColumnMetadata,
+                        label: format!("{}: column metadata", name),
+                    });
+                }
+            }
+
+            if let (Some(offset), Some(length)) = (column.column_index_offset(), column.column_index_length()) {
+                regions.push(Region {
+                    start: offset as u64,
+                    end: offset as u64 + length as u64,
+                    kind: RegionKind::ColumnIndex,
+                    label: format!("{}: column index", name),
+                });
+            }
+            if let (Some(offset), Some(length)) = (column.offset_index_offset(), column.offset_index_length()) {
+                regions.push(Region {
+                    start: offset as u64,
+                    end: offset as u64 + length as u64,
+                    kind: RegionKind::OffsetIndex,
+                    label: format!("{}: offset index", name),
+                });
+            }
+            if let Some(offset) = column.bloom_filter_offset() {
+                let (header, header_size) = read_bloom_filter_header(&file, offset as u64)?;
+                regions.push(Region {
+                    start: offset as u64,
+                    end: offset as u64 + header_size as u64 + header.num_bytes as u64,
+                    kind: RegionKind::BloomFilter,
+                    label: format!("{}: bloom filter", name),
+                });
+            }
+        }
+    }
+    if let (Some(offset), Some(length)) = (check.metadata_offset, check.metadata_length) {
+        regions.push(Region {
+            start: offset,
+            end: offset + length as u64,
+            kind: RegionKind::Footer,
+            label: String::from("file metadata"),
+        });
+    }
+    regions.push(Region {
+        start: file_size - FOOTER_SIZE,
+        end: file_size,
+        kind: RegionKind::Footer,
+        label: String::from("metadata length and footer magic"),
+    });
+    regions.sort_by_key(|r| (r.start, r.end));
+
+    println!("Layout of {} ({} bytes):", opts.file.display(), file_size);
+    println!("{:>12} {:>12} {:>10}  region", "start", "end", "size");
+    let mut covered = 0;
+    let mut problems = 0;
+    for region in &regions {
+        if region.start > covered {
+            println!("{:>12} {:>12} {:>10}  unused", covered, region.start, region.start - covered);
+            problems += 1;
+        }
+        let note = if region.start < covered {
+            problems += 1;
+            format!(" (overlaps the previous {} bytes)", covered - region.start)
+        } else {
+            String::new()
+        };
+        println!(
+            "{:>12} {:>12} {:>10}  {}{}",
+            region.start,
+            region.end,
+            region.end - region.start,
+            region.label,
+            note
+        );
+        covered = covered.max(region.end);
+    }
+    if covered < file_size {
+        println!("{:>12} {:>12} {:>10}  unused", covered, file_size, file_size - covered);
+        problems += 1;
+    }
+
+    println!();
+    for kind in RegionKind::ALL {
+        let bytes = regions.iter().filter(|r| r.kind == kind).map(|r| r.end - r.start).sum::<u64>();
+        if bytes > 0 {
+            println!(
+                "{:<18} {:>12} ({:.1}%)",
+                kind.name(),
+                get_pretty_size(bytes as i64),
+                bytes as f64 * 100.0 / file_size as f64
+            );
+        }
+    }
+    if problems == 0 {
+        println!("No gaps nor overlaps");
+    } else {
+        println!("{} gaps or overlaps", problems);
+    }
+
+    if opts.bar {
+        print_bar(&regions, file_size, opts.width);
+    }
+
+    Ok(())
+}
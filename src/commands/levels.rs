@@ -0,0 +1,175 @@
+use std::path::PathBuf;
+
+use clap::{Parser};
+use log::debug;
+use parquet::basic::Repetition;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::schema::types::{ColumnDescriptor, Type};
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, UnknownColumn};
+use crate::pages::read_column_batch;
+use crate::utils::{check_path_present, open_file};
+
+// prints the repetition and definition levels of every value of a column
+#[derive(Parser, Debug)]
+pub struct LevelsCommandArgs {
+    file: PathBuf,
+
+    #[arg(short, long, help = "the leaf column to shred, given by its dotted path")]
+    column: String,
+
+    #[arg(short = 'n', long, help = "only print the levels of the first records")]
+    records: Option<usize>,
+}
+
+// a field on the path from the root to the leaf column
+#[derive(Debug)]
+struct PathNode {
+    name: String,
+    repetition: Repetition,
+    // definition level reached once this field is defined
+    def_level: i16,
+    // repetition level of this field, when it is repeated
+    rep_level: i16,
+}
+
+fn path_nodes(root: &Type, descr: &ColumnDescriptor) -> Vec<PathNode> {
+    let mut nodes = vec![];
+    let mut fields = root.get_fields();
+    let (mut def_level, mut rep_level) = (0, 0);
+    for part in descr.path().parts() {
+        let field = match fields.iter().find(|f| f.name() == part) {
+            Some(field) => field,
+            None => break,
+        };
+        let repetition = field.get_basic_info().repetition();
+        match repetition {
+            Repetition::REQUIRED => {}
+            Repetition::OPTIONAL => def_level += 1,
+            Repetition::REPEATED => {
+                def_level += 1;
+                rep_level += 1;
+            }
+        }
+        nodes.push(PathNode { name: part.to_string(), repetition, def_level, rep_level });
+        if field.is_group() {
+            fields = field.get_fields();
+        }
+    }
+    nodes
+}
+
+// where a leaf slot lands in its record, the list indexes are kept per repetition level
+fn record_path(nodes: &[PathNode], indexes: &[usize], def: i16) -> String {
+    let mut path = String::new();
+    for node in nodes {
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(&node.name);
+        if def < node.def_level {
+            // the first undefined field tells what is missing
+            path.push_str(match node.repetition {
+                Repetition::REPEATED => " = [] (empty)",
+                _ => " = null",
+            });
+            return path;
+        }
+        if node.repetition == Repetition::REPEATED {
+            path.push_str(&format!("[{}]", indexes[node.rep_level as usize]));
+        }
+    }
+    path
+}
+
+pub(crate) fn execute(opts: LevelsCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file name to read is: {}", opts.file.display());
+    debug!("Column: {}, records: {:?}", opts.column, opts.records);
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file.to_path_buf()));
+    }
+
+    let parquet_reader = SerializedFileReader::new(open_file(&opts.file)?)?;
+    let metadata = parquet_reader.metadata();
+    let schema = metadata.file_metadata().schema_descr();
+    let column_index = schema
+        .columns()
+        .iter()
+        .position(|c| c.path().string() == opts.column)
+        .ok_or_else(|| UnknownColumn(opts.column.to_string()))?;
+    let descr = schema.column(column_index);
+    let nodes = path_nodes(schema.root_schema(), &descr);
+
+    println!(
+        "Column {}: max repetition level {}, max definition level {}",
+        opts.column,
+        descr.max_rep_level(),
+        descr.max_def_level()
+    );
+    for node in &nodes {
+        println!(
+            "  {} {}: definition level {}{}",
+            node.repetition,
+            node.name,
+            node.def_level,
+            match node.repetition {
+                Repetition::REPEATED => format!(", repetition level {}", node.rep_level),
+                _ => String::new(),
+            }
+        );
+    }
+    println!();
+    println!("{:>6} {:>4} {:>4}  {:<24} path", "record", "rep", "def", "value");
+
+    let mut record = 0;
+    let mut indexes = vec![0; descr.max_rep_level() as usize + 1];
+    'row_groups: for rg in 0..metadata.num_row_groups() {
+        let row_group_reader = parquet_reader.get_row_group(rg)?;
+        let mut reader = row_group_reader.get_column_reader(column_index)?;
+        let mut first = true;
+        loop {
+            let batch = read_column_batch(&mut reader, 1024)?;
+            if batch.rep_levels.is_empty() {
+                break;
+            }
+
+            let mut values = batch.values.iter();
+            for (&rep, &def) in batch.rep_levels.iter().zip(&batch.def_levels) {
+                // a repetition level of 0 starts a new record, the first one needs no counting
+                if rep == 0 && !(rg == 0 && first) {
+                    record += 1;
+                }
+                first = false;
+                if opts.records.is_some_and(|n| record >= n) {
+                    break 'row_groups;
+                }
+
+                // lists deeper than the repeated level restart, the repeated one moves on
+                for (level, index) in indexes.iter_mut().enumerate().skip(1) {
+                    if level as i16 > rep {
+                        *index = 0;
+                    } else if level as i16 == rep {
+                        *index += 1;
+                    }
+                }
+
+                let value = match def == descr.max_def_level() {
+                    true => values.next().cloned().unwrap_or_default(),
+                    false => String::from("null"),
+                };
+                println!(
+                    "{:>6} {:>4} {:>4}  {:<24} {}",
+                    record,
+                    rep,
+                    def,
+                    value,
+                    record_path(&nodes, &indexes, def)
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,526 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+use log::debug;
+use parquet::basic::{ConvertedType, Encoding, PageType, Type as PhysicalType};
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use parquet::file::reader::{ChunkReader, FileReader, SerializedFileReader};
+use parquet::format::PageHeader;
+use parquet::schema::types::{ColumnDescriptor, Type};
+use serde::Serialize;
+use thrift::protocol::{TCompactInputProtocol, TSerializable};
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, LintFailed, UnknownLintRule};
+use crate::utils::{check_path_present, get_pretty_size, open_file};
+
+// a dictionary page header holds no statistics, this is plenty
+const DICTIONARY_PAGE_HEADER_SIZE_ESTIMATE: u64 = 256;
+// column chunks with less values are too small for their dictionary size to matter
+const MIN_DICTIONARY_VALUES: i64 = 1000;
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+struct Rule {
+    id: &'static str,
+    severity: Severity,
+    description: &'static str,
+}
+
+const RULES: [Rule; 9] = [
+    Rule {
+        id: "row-group-too-small",
+        severity: Severity::Warning,
+        description: "a row group other than the last one is smaller than --min-row-group-size",
+    },
+    Rule {
+        id: "row-group-too-large",
+        severity: Severity::Warning,
+        description: "a row group is larger than --max-row-group-size",
+    },
+    Rule {
+        id: "missing-statistics",
+        severity: Severity::Warning,
+        description: "column chunks have no min and max statistics to skip them by",
+    },
+    Rule {
+        id: "missing-page-index",
+        severity: Severity::Info,
+        description: "column chunks have no column index or no offset index to skip pages by",
+    },
+    Rule {
+        id: "uncompressed-column",
+        severity: Severity::Warning,
+        description: "column chunks are not compressed",
+    },
+    Rule {
+        id: "high-cardinality-dictionary",
+        severity: Severity::Warning,
+        description: "the dictionary of a column chunk holds more than --max-dictionary-ratio of its values, or overflowed",
+    },
+    Rule {
+        id: "int96-timestamp",
+        severity: Severity::Warning,
+        description: "a column stores deprecated INT96 timestamps",
+    },
+    Rule {
+        id: "converted-type-without-logical-type",
+        severity: Severity::Info,
+        description: "a field has a deprecated converted type but no logical type",
+    },
+    Rule {
+        id: "buggy-statistics-writer",
+        severity: Severity::Error,
+        description: "the writer version is known to write wrong statistics for some sort orders",
+    },
+];
+
+// flags suboptimal or risky choices in how parquet files were written
+#[derive(Parser, Debug)]
+pub struct LintCommandArgs {
+    #[arg(required_unless_present = "list_rules")]
+    files: Vec<PathBuf>,
+
+    #[arg(short, long, help = "print the findings as JSON, one object per file")]
+    json: bool,
+
+    #[arg(short, long, value_name = "RULE[:COLUMN]", help = "suppress a rule, for one column only when given")]
+    disable: Vec<String>,
+
+    #[arg(short, long, value_name = "RULE=SEVERITY", help = "change the severity of a rule")]
+    severity: Vec<String>,
+
+    #[arg(long, value_enum, default_value = "error", help = "fail when a finding is at least this severe")]
+    fail_on: Severity,
+
+    #[arg(long, default_value = "16777216", help = "smallest row group size in bytes")]
+    min_row_group_size: u64,
+
+    #[arg(long, default_value = "1073741824", help = "largest row group size in bytes")]
+    max_row_group_size: u64,
+
+    #[arg(long, default_value = "0.5", help = "largest share of the values of a column chunk its dictionary may hold")]
+    max_dictionary_ratio: f64,
+
+    #[arg(long, help = "list the rules and their default severity")]
+    list_rules: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct Finding {
+    rule: &'static str,
+    severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    row_groups: Vec<usize>,
+    message: String,
+}
+
+#[derive(Serialize, Debug)]
+struct FileFindings {
+    file: String,
+    findings: Vec<Finding>,
+}
+
+// how the rules apply to the files, from the command line
+struct LintConfig {
+    severities: HashMap<&'static str, Severity>,
+    // suppressed rules, with the columns they are suppressed for, none meaning all of them
+    disabled: Vec<(&'static str, Option<String>)>,
+    min_row_group_size: u64,
+    max_row_group_size: u64,
+    max_dictionary_ratio: f64,
+}
+
+fn rule_id(name: &str) -> Result<&'static str, PQRSError> {
+    RULES
+        .iter()
+        .find(|r| r.id == name)
+        .map(|r| r.id)
+        .ok_or_else(|| UnknownLintRule(name.to_string()))
+}
+
+impl LintConfig {
+    fn new(opts: &LintCommandArgs) -> Result<Self, PQRSError> {
+        let mut severities = RULES.iter().map(|r| (r.id, r.severity)).collect::<HashMap<_, _>>();
+        for setting in &opts.severity {
+            let (rule, severity) = setting
+                .split_once('=')
+                .ok_or_else(|| UnknownLintRule(setting.to_string()))?;
+            let severity = Severity::from_str(severity, true)
+                .map_err(|_| UnknownLintRule(setting.to_string()))?;
+            severities.insert(rule_id(rule)?, severity);
+        }
+
+        let disabled = opts
+            .disable
+            .iter()
+            .map(|setting| match setting.split_once(':') {
+                Some((rule, column)) => Ok((rule_id(rule)?, Some(column.to_string()))),
+                None => Ok((rule_id(setting)?, None)),
+            })
+            .collect::<Result<Vec<_>, PQRSError>>()?;
+
+        Ok(LintConfig {
+            severities,
+            disabled,
+            min_row_group_size: opts.min_row_group_size,
+            max_row_group_size: opts.max_row_group_size,
+            max_dictionary_ratio: opts.max_dictionary_ratio,
+        })
+    }
+
+    fn finding(
+        &self,
+        rule: &'static str,
+        column: Option<String>,
+        row_groups: Vec<usize>,
+        message: String,
+    ) -> Option<Finding> {
+        let disabled = self
+            .disabled
+            .iter()
+            .any(|(id, only)| *id == rule && (only.is_none() || *only == column));
+        match disabled {
+            true => None,
+            false => Some(Finding { rule, severity: self.severities[rule], column, row_groups, message }),
+        }
+    }
+}
+
+// the row groups of each column, in schema order, where a column chunk check holds
+fn columns_where(
+    metadata: &ParquetMetaData,
+    check: impl Fn(&ColumnChunkMetaData) -> bool,
+) -> Vec<(String, Vec<usize>)> {
+    let schema = metadata.file_metadata().schema_descr();
+    (0..schema.num_columns())
+        .map(|i| {
+            let row_groups = metadata
+                .row_groups()
+                .iter()
+                .enumerate()
+                .filter(|(_, row_group)| check(row_group.column(i)))
+                .map(|(rg, _)| rg)
+                .collect::<Vec<_>>();
+            (schema.column(i).path().string(), row_groups)
+        })
+        .filter(|(_, row_groups)| !row_groups.is_empty())
+        .collect()
+}
+
+fn row_group_findings(metadata: &ParquetMetaData, config: &LintConfig) -> Vec<Finding> {
+    let mut findings = vec![];
+    let num_row_groups = metadata.num_row_groups();
+    for (rg, row_group) in metadata.row_groups().iter().enumerate() {
+        let size = row_group.compressed_size() as u64;
+        // the last row group holds what is left
+        if size < config.min_row_group_size && rg + 1 < num_row_groups {
+            findings.extend(config.finding("row-group-too-small", None, vec![rg], format!(
+                "row group {} holds {} in {} rows, less than {}",
+                rg,
+                get_pretty_size(size as i64),
+                row_group.num_rows(),
+                get_pretty_size(config.min_row_group_size as i64)
+            )));
+        }
+        if size > config.max_row_group_size {
+            findings.extend(config.finding("row-group-too-large", None, vec![rg], format!(
+                "row group {} holds {} in {} rows, more than {}",
+                rg,
+                get_pretty_size(size as i64),
+                row_group.num_rows(),
+                get_pretty_size(config.max_row_group_size as i64)
+            )));
+        }
+    }
+    findings
+}
+
+fn column_chunk_findings(metadata: &ParquetMetaData, config: &LintConfig) -> Vec<Finding> {
+    let num_row_groups = metadata.num_row_groups();
+    let mut findings = vec![];
+    let mut add = |rule, found: Vec<(String, Vec<usize>)>, what: &str| {
+        for (column, row_groups) in found {
+            let message = format!(
+                "column {} {} in {} of {} row groups",
+                column,
+                what,
+                row_groups.len(),
+                num_row_groups
+            );
+            findings.extend(config.finding(rule, Some(column), row_groups, message));
+        }
+    };
+
+    add(
+        "missing-statistics",
+        columns_where(metadata, |c| !c.statistics().is_some_and(|s| s.has_min_max_set())),
+        "has no min and max statistics",
+    );
+    add(
+        "missing-page-index",
+        columns_where(metadata, |c| c.column_index_offset().is_none() || c.offset_index_offset().is_none()),
+        "has no column index or no offset index",
+    );
+    add(
+        "uncompressed-column",
+        columns_where(metadata, |c| c.compression() == parquet::basic::Compression::UNCOMPRESSED),
+        "is not compressed",
+    );
+    findings
+}
+
+// data pages which are not dictionary encoded next to a dictionary page mean it overflowed
+fn falls_back(column: &ColumnChunkMetaData) -> bool {
+    column.page_encoding_stats().is_some_and(|stats| {
+        stats.iter().any(|s| s.page_type == PageType::DICTIONARY_PAGE)
+            && stats.iter().any(|s| {
+                matches!(s.page_type, PageType::DATA_PAGE | PageType::DATA_PAGE_V2)
+                    && !matches!(s.encoding, Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY)
+            })
+    })
+}
+
+// the number of entries of the dictionary of a column chunk, from its page header
+fn dictionary_size(file: &File, column: &ColumnChunkMetaData) -> Option<i64> {
+    let offset = column.dictionary_page_offset()? as u64;
+    let length = DICTIONARY_PAGE_HEADER_SIZE_ESTIMATE.min(column.compressed_size() as u64);
+    let bytes = file.get_bytes(offset, length as usize).ok()?;
+    let mut protocol = TCompactInputProtocol::new(Cursor::new(bytes.as_ref()));
+    let header = PageHeader::read_from_in_protocol(&mut protocol).ok()?;
+    header.dictionary_page_header.map(|h| h.num_values as i64)
+}
+
+fn dictionary_findings(file: &File, metadata: &ParquetMetaData, config: &LintConfig) -> Vec<Finding> {
+    let schema = metadata.file_metadata().schema_descr();
+    let mut findings = vec![];
+    for i in 0..schema.num_columns() {
+        let (mut row_groups, mut fallbacks, mut large) = (vec![], 0, 0);
+        let (mut entries, mut values) = (0, 0);
+        for (rg, row_group) in metadata.row_groups().iter().enumerate() {
+            let column = row_group.column(i);
+            let fallback = falls_back(column);
+            let size = match column.num_values() < MIN_DICTIONARY_VALUES {
+                true => None,
+                false => dictionary_size(file, column)
+                    .filter(|size| *size as f64 > config.max_dictionary_ratio * column.num_values() as f64),
+            };
+            if fallback {
+                fallbacks += 1;
+            }
+            if let Some(size) = size {
+                large += 1;
+                entries += size;
+                values += column.num_values();
+            }
+            if fallback || size.is_some() {
+                row_groups.push(rg);
+            }
+        }
+        if row_groups.is_empty() {
+            continue;
+        }
+        // one finding per column, whichever of the two checks found it
+        let column = schema.column(i).path().string();
+        let mut reasons = vec![];
+        if large > 0 {
+            reasons.push(format!("has dictionaries of {} entries for {} values in {} row groups", entries, values, large));
+        }
+        if fallbacks > 0 {
+            reasons.push(format!(
+                "falls back from dictionary encoding in {} of {} row groups",
+                fallbacks,
+                metadata.num_row_groups()
+            ));
+        }
+        let message = format!("column {} {}", column, reasons.join(" and "));
+        findings.extend(config.finding("high-cardinality-dictionary", Some(column), row_groups, message));
+    }
+    findings
+}
+
+fn schema_findings(metadata: &ParquetMetaData, config: &LintConfig) -> Vec<Finding> {
+    let mut findings = vec![];
+    for descr in metadata.file_metadata().schema_descr().columns() {
+        if descr.physical_type() == PhysicalType::INT96 {
+            let column = descr.path().string();
+            let message = format!("column {} stores INT96 timestamps, use INT64 with a TIMESTAMP logical type", column);
+            findings.extend(config.finding("int96-timestamp", Some(column), vec![], message));
+        }
+    }
+
+    fn visit(field: &Type, path: &mut Vec<String>, config: &LintConfig, findings: &mut Vec<Finding>) {
+        let info = field.get_basic_info();
+        if info.converted_type() != ConvertedType::NONE && info.logical_type().is_none() {
+            let column = path.join(".");
+            let message = format!(
+                "field {} has the converted type {} but no logical type",
+                column,
+                info.converted_type()
+            );
+            findings.extend(config.finding("converted-type-without-logical-type", Some(column), vec![], message));
+        }
+        if field.is_group() {
+            for child in field.get_fields() {
+                path.push(child.name().to_string());
+                visit(child, path, config, findings);
+                path.pop();
+            }
+        }
+    }
+    let root = metadata.file_metadata().schema_descr().root_schema();
+    for field in root.get_fields() {
+        visit(field, &mut vec![field.name().to_string()], config, &mut findings);
+    }
+    findings
+}
+
+// the application and version of a created_by string such as "parquet-mr version 1.8.1 (build ...)"
+fn writer_version(created_by: &str) -> Option<(&str, Vec<u32>)> {
+    let mut words = created_by.split_whitespace();
+    let application = words.next()?;
+    if words.next()? != "version" {
+        return None;
+    }
+    let version = words
+        .next()?
+        .split(['.', '-'])
+        .take(3)
+        .map(|part| part.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    Some((application, version))
+}
+
+fn writer_findings(metadata: &ParquetMetaData, config: &LintConfig) -> Vec<Finding> {
+    let created_by = match metadata.file_metadata().created_by() {
+        Some(created_by) => created_by,
+        None => return vec![],
+    };
+    let binary = |descr: &ColumnDescriptor| {
+        matches!(descr.physical_type(), PhysicalType::BYTE_ARRAY | PhysicalType::FIXED_LEN_BYTE_ARRAY)
+    };
+    let unsigned = |descr: &ColumnDescriptor| {
+        binary(descr)
+            || matches!(
+                descr.converted_type(),
+                ConvertedType::UINT_8 | ConvertedType::UINT_16 | ConvertedType::UINT_32 | ConvertedType::UINT_64
+            )
+    };
+    let (affected, problem): (&dyn Fn(&ColumnDescriptor) -> bool, _) = match writer_version(created_by) {
+        Some(("parquet-mr", version)) if version < vec![1, 8, 0] => {
+            (&binary, "are wrong for binary columns (PARQUET-251)")
+        }
+        Some(("parquet-mr", version)) if version < vec![1, 10, 0] => {
+            (&unsigned, "compare unsigned values as signed ones (PARQUET-686)")
+        }
+        Some(("parquet-cpp", version)) if version < vec![1, 3, 0] => {
+            (&unsigned, "compare unsigned values as signed ones (PARQUET-686)")
+        }
+        _ => return vec![],
+    };
+
+    let schema = metadata.file_metadata().schema_descr();
+    let mut findings = vec![];
+    for (i, descr) in schema.columns().iter().enumerate() {
+        let row_groups = metadata
+            .row_groups()
+            .iter()
+            .enumerate()
+            .filter(|(_, row_group)| row_group.column(i).statistics().is_some_and(|s| s.has_min_max_set()))
+            .map(|(rg, _)| rg)
+            .collect::<Vec<_>>();
+        if !affected(descr) || row_groups.is_empty() {
+            continue;
+        }
+        let column = descr.path().string();
+        let message = format!(
+            "the min and max statistics of column {} written by {} {}",
+            column,
+            created_by,
+            problem
+        );
+        findings.extend(config.finding("buggy-statistics-writer", Some(column), row_groups, message));
+    }
+    findings
+}
+
+fn lint_file(file: File, config: &LintConfig) -> Result<Vec<Finding>, PQRSError> {
+    let parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
+    let metadata = parquet_reader.metadata();
+
+    let mut findings = row_group_findings(metadata, config);
+    findings.extend(column_chunk_findings(metadata, config));
+    findings.extend(dictionary_findings(&file, metadata, config));
+    findings.extend(schema_findings(metadata, config));
+    findings.extend(writer_findings(metadata, config));
+    // the most severe first, then in rule order
+    let order = |f: &Finding| RULES.iter().position(|r| r.id == f.rule);
+    findings.sort_by(|a, b| {
+        b.severity
+            .partial_cmp(&a.severity)
+            .unwrap()
+            .then(order(a).cmp(&order(b)))
+    });
+    Ok(findings)
+}
+
+pub(crate) fn execute(opts: LintCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to read are: {:?}", opts.files);
+
+    if opts.list_rules {
+        for rule in &RULES {
+            println!("{:<36} {:<8} {}", rule.id, rule.severity, rule.description);
+        }
+        return Ok(());
+    }
+
+    for file_name in &opts.files {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    let config = LintConfig::new(&opts)?;
+    let mut failing = 0;
+    for file_name in &opts.files {
+        let findings = lint_file(open_file(file_name)?, &config)?;
+        failing += findings.iter().filter(|f| f.severity >= opts.fail_on).count();
+
+        if opts.json {
+            let file_findings = FileFindings { file: file_name.display().to_string(), findings };
+            println!("{}", serde_json::to_string(&file_findings)?);
+            continue;
+        }
+        println!("{}: {} findings", file_name.display(), findings.len());
+        for finding in &findings {
+            println!("  {} [{}] {}", finding.severity, finding.rule, finding.message);
+        }
+    }
+
+    if failing > 0 {
+        return Err(LintFailed(failing, opts.fail_on.to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand};
+use log::debug;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::format::KeyValue;
+use tempfile::NamedTempFile;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileExists, FileNotFound, InvalidFooter, InvalidKeyValue, UnknownMetadataKey};
+use crate::footer::{check_footer, read_file_metadata, write_footer};
+use crate::utils::{check_path_present, get_schema_metadata, open_file};
+
+// reads or edits the key value metadata of a file, only its footer is rewritten
+#[derive(Parser, Debug)]
+pub struct MetaCommandArgs {
+    #[clap(subcommand)]
+    action: MetaAction,
+}
+
+#[derive(Subcommand, Debug)]
+enum MetaAction {
+    /// Print the key value metadata
+    Get(GetArgs),
+    /// Add entries or replace the value of existing ones
+    Set(SetArgs),
+    /// Remove entries
+    Delete(DeleteArgs),
+    /// Set the entries of a JSON object, whose values are strings or null
+    Import(ImportArgs),
+}
+
+#[derive(Args, Debug)]
+struct GetArgs {
+    file: PathBuf,
+
+    #[arg(help = "only print these keys")]
+    keys: Vec<String>,
+
+    #[arg(short, long, help = "print the entries as a JSON object")]
+    json: bool,
+}
+
+// where the edited footer is written
+#[derive(Args, Debug)]
+struct EditArgs {
+    file: PathBuf,
+
+    #[arg(short, long, help = "write the edited file there instead of editing it in place")]
+    output: Option<PathBuf>,
+
+    #[arg(short, long, help = "print the entries as a JSON object")]
+    json: bool,
+}
+
+#[derive(Args, Debug)]
+struct SetArgs {
+    #[clap(flatten)]
+    edit: EditArgs,
+
+    #[arg(required = true, help = "entries as key=value")]
+    entries: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+struct DeleteArgs {
+    #[clap(flatten)]
+    edit: EditArgs,
+
+    #[arg(required = true)]
+    keys: Vec<String>,
+
+    #[arg(long, help = "do not fail on keys which are not in the file")]
+    ignore_missing: bool,
+}
+
+#[derive(Args, Debug)]
+struct ImportArgs {
+    #[clap(flatten)]
+    edit: EditArgs,
+
+    #[arg(help = "the JSON file, such as the metadata printed by `schema --json`")]
+    json_file: PathBuf,
+
+    #[arg(long, help = "drop the existing entries first")]
+    replace: bool,
+}
+
+fn parse_entry(entry: &str) -> Result<(String, Option<String>), PQRSError> {
+    match entry.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), Some(value.to_string()))),
+        _ => Err(InvalidKeyValue(entry.to_string())),
+    }
+}
+
+// reads a JSON object of entries, or the metadata of the `schema --json` output
+fn read_entries(path: &Path) -> Result<Vec<(String, Option<String>)>, PQRSError> {
+    let json: serde_json::Value = serde_json::from_reader(open_file(path)?)?;
+    let object = match json.get("metadata").filter(|_| json.get("message").is_some()) {
+        Some(metadata) => metadata,
+        None => &json,
+    };
+    let object = object
+        .as_object()
+        .ok_or_else(|| InvalidKeyValue(format!("{} does not hold a JSON object", path.display())))?;
+    object
+        .iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::Null => Ok((key.to_string(), None)),
+            serde_json::Value::String(value) => Ok((key.to_string(), Some(value.to_string()))),
+            other => Err(InvalidKeyValue(format!("{}={}, values must be strings or null", key, other))),
+        })
+        .collect()
+}
+
+// sets the value of the first entry with the key and drops the other ones, or appends an entry
+fn set_entry(entries: &mut Vec<KeyValue>, key: String, value: Option<String>) {
+    let mut found = false;
+    entries.retain_mut(|kv| {
+        if kv.key != key {
+            return true;
+        }
+        if found {
+            return false;
+        }
+        found = true;
+        kv.value = value.clone();
+        true
+    });
+    if !found {
+        entries.push(KeyValue::new(key, value));
+    }
+}
+
+// copies the data of a file verbatim then writes its footer with the edited entries
+fn edit_entries<F>(opts: &EditArgs, edit: F) -> Result<(), PQRSError>
+where
+    F: FnOnce(&mut Vec<KeyValue>) -> Result<(), PQRSError>,
+{
+    debug!("The file name to edit is: {}", opts.file.display());
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file.to_path_buf()));
+    }
+    if let Some(output) = &opts.output {
+        if check_path_present(output) {
+            return Err(FileExists(output.to_path_buf()));
+        }
+    }
+
+    let mut file = open_file(&opts.file)?;
+    let check = check_footer(&file)?;
+    let mut metadata = read_file_metadata(&file, &check)?;
+    let data_length = check.metadata_offset.ok_or_else(|| InvalidFooter(check.problems().join(", ")))?;
+    let mut entries = metadata.key_value_metadata.take().unwrap_or_default();
+    edit(&mut entries)?;
+    metadata.key_value_metadata = (!entries.is_empty()).then_some(entries);
+
+    // the edited file is written next to its destination then renamed, a failure leaves the
+    // original untouched
+    let destination = opts.output.as_ref().unwrap_or(&opts.file);
+    let directory = match destination.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let temporary = NamedTempFile::new_in(directory)?;
+    let mut sink = BufWriter::new(temporary);
+    // reading the footer moved the position of the file
+    file.seek(SeekFrom::Start(0))?;
+    std::io::copy(&mut file.take(data_length), &mut sink)?;
+    let mut sink = write_footer(sink, &metadata)?;
+    sink.flush()?;
+    let temporary = sink.into_inner().map_err(|e| e.into_error())?;
+    if opts.output.is_none() {
+        temporary.as_file().set_permissions(File::open(&opts.file)?.metadata()?.permissions())?;
+    }
+    temporary.persist(destination).map_err(|e| e.error)?;
+
+    print_entries(destination, &[], opts.json)
+}
+
+// prints the entries the way the schema command collects them
+fn print_entries(path: &Path, keys: &[String], json: bool) -> Result<(), PQRSError> {
+    let reader = SerializedFileReader::new(open_file(path)?)?;
+    let mut entries = get_schema_metadata(reader.metadata())
+        .unwrap_or_default()
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+    for key in keys {
+        if !entries.contains_key(key) {
+            return Err(UnknownMetadataKey(key.to_string()));
+        }
+    }
+    if !keys.is_empty() {
+        entries.retain(|key, _| keys.contains(key));
+    }
+
+    if json {
+        println!("{}", serde_json::to_string(&entries)?);
+        return Ok(());
+    }
+    println!("Metadata of {}: {} entries", path.display(), entries.len());
+    for (key, value) in &entries {
+        match value {
+            Some(value) => println!("{}: {}", key, value),
+            None => println!("{}", key),
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn execute(opts: MetaCommandArgs) -> Result<(), PQRSError> {
+    match opts.action {
+        MetaAction::Get(opts) => {
+            debug!("The file name to read is: {}", opts.file.display());
+            if !check_path_present(&opts.file) {
+                return Err(FileNotFound(opts.file.to_path_buf()));
+            }
+            print_entries(&opts.file, &opts.keys, opts.json)
+        }
+        MetaAction::Set(opts) => {
+            let parsed = opts.entries.iter().map(|e| parse_entry(e)).collect::<Result<Vec<_>, _>>()?;
+            edit_entries(&opts.edit, |entries| {
+                for (key, value) in parsed {
+                    set_entry(entries, key, value);
+                }
+                Ok(())
+            })
+        }
+        MetaAction::Delete(opts) => edit_entries(&opts.edit, |entries| {
+            for key in &opts.keys {
+                if !opts.ignore_missing && !entries.iter().any(|kv| &kv.key == key) {
+                    return Err(UnknownMetadataKey(key.to_string()));
+                }
+                entries.retain(|kv| &kv.key != key);
+            }
+            Ok(())
+        }),
+        MetaAction::Import(opts) => {
+            if !check_path_present(&opts.json_file) {
+                return Err(FileNotFound(opts.json_file.to_path_buf()));
+            }
+            let imported = read_entries(&opts.json_file)?;
+            edit_entries(&opts.edit, |entries| {
+                if opts.replace {
+                    entries.clear();
+                }
+                for (key, value) in imported {
+                    set_entry(entries, key, value);
+                }
+                Ok(())
+            })
+        }
+    }
+}
@@ -1,7 +1,26 @@
+pub(crate) mod advise;
+pub(crate) mod bench;
+pub(crate) mod bloom;
 pub(crate) mod cat;
+pub(crate) mod create;
+pub(crate) mod dictionary;
+pub(crate) mod footer;
+pub(crate) mod generate;
+pub(crate) mod geo;
+pub(crate) mod get;
 pub(crate) mod head;
+pub(crate) mod layout;
+pub(crate) mod levels;
+pub(crate) mod lint;
 pub(crate) mod merge;
+pub(crate) mod meta;
+pub(crate) mod page_index;
+pub(crate) mod pages;
+pub(crate) mod recover;
 pub(crate) mod rowcount;
 pub(crate) mod sample;
 pub(crate) mod schema;
-pub(crate) mod size;
\ No newline at end of file
+pub(crate) mod size;
+pub(crate) mod slice;
+pub(crate) mod tail;
+pub(crate) mod verify;
\ No newline at end of file
@@ -0,0 +1,192 @@
+use std::fs::File;
+use std::path::PathBuf;
+
+use clap::{Parser};
+use log::debug;
+use parquet::data_type::AsBytes;
+use parquet::file::metadata::ColumnChunkMetaData;
+use parquet::file::page_index::index::{Index, PageIndex};
+use parquet::file::page_index::index_reader::{read_columns_indexes, read_pages_locations};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::format::{BoundaryOrder, PageLocation};
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, UnknownColumn};
+use crate::pages::bytes_text;
+use crate::utils::{check_path_present, open_file};
+
+// prints the column index and offset index of every column chunk in the parquet file
+#[derive(Parser, Debug)]
+pub struct PageIndexCommandArgs {
+    file: PathBuf,
+
+    #[arg(short, long, help = "only print the page index of this column, given by its dotted path")]
+    column: Option<String>,
+
+    #[arg(short = 'g', long = "row-group", help = "only print the page index of this row group")]
+    row_group: Option<usize>,
+}
+
+// min, max and null count of a page, min and max are both missing for pages of nulls only
+type PageBounds = (Option<String>, Option<String>, Option<i64>);
+
+fn native_bounds<T>(pages: &[PageIndex<T>], text: impl Fn(&T) -> String) -> Vec<PageBounds> {
+    pages
+        .iter()
+        .map(|page| (page.min.as_ref().map(&text), page.max.as_ref().map(&text), page.null_count))
+        .collect()
+}
+
+fn column_index_bounds(index: &Index) -> Option<(Vec<PageBounds>, BoundaryOrder)> {
+    let bounds = match index {
+        Index::NONE => return None,
+        Index::BOOLEAN(index) => native_bounds(&index.indexes, |v| v.to_string()),
+        Index::INT32(index) => native_bounds(&index.indexes, |v| v.to_string()),
+        Index::INT64(index) => native_bounds(&index.indexes, |v| v.to_string()),
+        Index::INT96(index) => native_bounds(&index.indexes, |v| format!("{:?}", v.data())),
+        Index::FLOAT(index) => native_bounds(&index.indexes, |v| v.to_string()),
+        Index::DOUBLE(index) => native_bounds(&index.indexes, |v| v.to_string()),
+        Index::BYTE_ARRAY(index) => native_bounds(&index.indexes, |v| bytes_text(v.as_bytes())),
+        Index::FIXED_LEN_BYTE_ARRAY(index) => {
+            native_bounds(&index.indexes, |v| bytes_text(v.as_bytes()))
+        }
+    };
+    Some((bounds, index.get_boundary_order().unwrap_or(BoundaryOrder::UNORDERED)))
+}
+
+fn boundary_order_name(order: BoundaryOrder) -> String {
+    match order {
+        BoundaryOrder::UNORDERED => String::from("UNORDERED"),
+        BoundaryOrder::ASCENDING => String::from("ASCENDING"),
+        BoundaryOrder::DESCENDING => String::from("DESCENDING"),
+        _ => format!("UNKNOWN({})", order.0),
+    }
+}
+
+// reads the page index of a single column chunk, so a chunk without one does not stop the others
+fn read_page_index(
+    file: &File,
+    column: &ColumnChunkMetaData,
+) -> Result<(Index, Option<Vec<PageLocation>>), PQRSError> {
+    let chunks = std::slice::from_ref(column);
+    let index = read_columns_indexes(file, chunks)?.pop().unwrap_or(Index::NONE);
+    let locations = match column.offset_index_offset() {
+        Some(_) => read_pages_locations(file, chunks)?.pop(),
+        None => None,
+    };
+    Ok((index, locations))
+}
+
+fn print_page(page: usize, location: Option<&PageLocation>, bounds: Option<&PageBounds>) {
+    let mut line = format!("  page {}:", page);
+    if let Some(location) = location {
+        line.push_str(&format!(
+            " offset {}, {} bytes, first row {}",
+            location.offset, location.compressed_page_size, location.first_row_index
+        ));
+    }
+    match bounds {
+        Some((None, None, null_count)) => {
+            line.push_str(&format!("{} null page", if location.is_some() { "," } else { "" }));
+            if let Some(null_count) = null_count {
+                line.push_str(&format!(", nulls {}", null_count));
+            }
+        }
+        Some((min, max, null_count)) => {
+            line.push_str(&format!(
+                "{} min {}, max {}, nulls {}",
+                if location.is_some() { "," } else { "" },
+                min.as_deref().unwrap_or("-"),
+                max.as_deref().unwrap_or("-"),
+                null_count.map_or(String::from("-"), |c| c.to_string())
+            ));
+        }
+        None => {}
+    }
+    println!("{}", line);
+}
+
+pub(crate) fn execute(opts: PageIndexCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file name to read is: {}", opts.file.display());
+    debug!("Column: {:?}, row group: {:?}", opts.column, opts.row_group);
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file.to_path_buf()));
+    }
+
+    let file = open_file(&opts.file)?;
+    let parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
+    let metadata = parquet_reader.metadata();
+    let schema = metadata.file_metadata().schema_descr();
+
+    let columns = match &opts.column {
+        Some(column) => {
+            let index = schema
+                .columns()
+                .iter()
+                .position(|c| &c.path().string() == column)
+                .ok_or_else(|| UnknownColumn(column.to_string()))?;
+            vec![index]
+        }
+        None => (0..schema.num_columns()).collect(),
+    };
+
+    // row groups where each column lacks a column index or an offset index
+    let mut missing_column_index = vec![vec![]; schema.num_columns()];
+    let mut missing_offset_index = vec![vec![]; schema.num_columns()];
+    for (rg, row_group) in metadata.row_groups().iter().enumerate() {
+        if opts.row_group.is_some_and(|g| g != rg) {
+            continue;
+        }
+
+        for &i in &columns {
+            let column = row_group.column(i);
+            let (index, locations) = read_page_index(&file, column)?;
+            let bounds = column_index_bounds(&index);
+
+            let mut title = format!("Row group {}, column {}:", rg, column.column_path().string());
+            match &bounds {
+                Some((_, order)) => {
+                    title.push_str(&format!(" boundary order {}", boundary_order_name(*order)))
+                }
+                None => {
+                    title.push_str(" no column index");
+                    missing_column_index[i].push(rg);
+                }
+            }
+            if locations.is_none() {
+                title.push_str(if bounds.is_some() { ", no offset index" } else { " nor offset index" });
+                missing_offset_index[i].push(rg);
+            }
+            println!("{}", title);
+
+            let pages = bounds.as_ref().map(|(p, _)| p.len()).unwrap_or_default();
+            let pages = pages.max(locations.as_ref().map(|l| l.len()).unwrap_or_default());
+            for page in 0..pages {
+                print_page(
+                    page,
+                    locations.as_ref().and_then(|l| l.get(page)),
+                    bounds.as_ref().and_then(|(b, _)| b.get(page)),
+                );
+            }
+        }
+    }
+
+    println!();
+    let mut complete = true;
+    for &i in &columns {
+        let path = schema.column(i).path().string();
+        for (missing, name) in [(&missing_column_index[i], "column"), (&missing_offset_index[i], "offset")] {
+            if !missing.is_empty() {
+                complete = false;
+                let row_groups = missing.iter().map(|rg| rg.to_string()).collect::<Vec<_>>();
+                println!("Column {} has no {} index in row groups: {}", path, name, row_groups.join(", "));
+            }
+        }
+    }
+    if complete {
+        println!("Every column has a column index and an offset index");
+    }
+
+    Ok(())
+}
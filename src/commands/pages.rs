@@ -0,0 +1,205 @@
+use std::path::PathBuf;
+
+use clap::{Parser};
+use log::debug;
+use parquet::basic::Encoding;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use serde::Serialize;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, UnknownColumn};
+use crate::pages::{data_page_values, dictionary_values, read_page_headers, PageStatistics};
+use crate::utils::{check_path_present, open_file};
+
+// prints every page of the column chunks in the parquet file
+#[derive(Parser, Debug)]
+pub struct PagesCommandArgs {
+    file: PathBuf,
+
+    #[arg(short, long, help = "only print the pages of this column, given by its dotted path")]
+    column: Option<String>,
+
+    #[arg(short = 'g', long = "row-group", help = "only print the pages of this row group")]
+    row_group: Option<usize>,
+
+    #[arg(short = 'V', long, help = "also decode and print the values of every page")]
+    values: bool,
+
+    #[arg(short, long)]
+    json: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct PageInfo {
+    row_group: usize,
+    column: String,
+    page: usize,
+    page_type: String,
+    offset: u64,
+    header_size: usize,
+    compressed_size: i32,
+    uncompressed_size: i32,
+    crc: Option<i32>,
+    num_values: Option<i32>,
+    encoding: Option<String>,
+    definition_level_encoding: Option<String>,
+    repetition_level_encoding: Option<String>,
+    num_nulls: Option<i32>,
+    num_rows: Option<i32>,
+    is_sorted: Option<bool>,
+    statistics: Option<PageStatistics>,
+    values: Option<Vec<String>>,
+}
+
+fn encoding_name(encoding: parquet::format::Encoding) -> String {
+    match Encoding::try_from(encoding) {
+        Ok(encoding) => encoding.to_string(),
+        Err(_) => format!("UNKNOWN({})", encoding.0),
+    }
+}
+
+fn print_page(page: &PageInfo) {
+    let mut line = format!(
+        "  page {}: {} @ {}, header {} bytes, {}/{} bytes compressed/uncompressed",
+        page.page, page.page_type, page.offset, page.header_size, page.compressed_size, page.uncompressed_size
+    );
+    if let Some(num_values) = page.num_values {
+        line.push_str(&format!(", {} values", num_values));
+    }
+    if let Some(num_nulls) = page.num_nulls {
+        line.push_str(&format!(", {} nulls", num_nulls));
+    }
+    if let Some(num_rows) = page.num_rows {
+        line.push_str(&format!(", {} rows", num_rows));
+    }
+    if let Some(encoding) = &page.encoding {
+        line.push_str(&format!(", encoding {}", encoding));
+    }
+    if let Some(encoding) = &page.definition_level_encoding {
+        line.push_str(&format!(", def levels {}", encoding));
+    }
+    if let Some(encoding) = &page.repetition_level_encoding {
+        line.push_str(&format!(", rep levels {}", encoding));
+    }
+    if let Some(is_sorted) = page.is_sorted {
+        line.push_str(&format!(", sorted {}", is_sorted));
+    }
+    if let Some(crc) = page.crc {
+        line.push_str(&format!(", crc {:08x}", crc));
+    }
+    println!("{}", line);
+
+    if let Some(statistics) = &page.statistics {
+        println!(
+            "    statistics: min {}, max {}, nulls {}, distinct {}",
+            statistics.min.as_deref().unwrap_or("-"),
+            statistics.max.as_deref().unwrap_or("-"),
+            statistics.null_count.map_or(String::from("-"), |c| c.to_string()),
+            statistics.distinct_count.map_or(String::from("-"), |c| c.to_string()),
+        );
+    }
+    if let Some(values) = &page.values {
+        println!("    values: [{}]", values.join(", "));
+    }
+}
+
+pub(crate) fn execute(opts: PagesCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file name to read is: {}", opts.file.display());
+    debug!("Column: {:?}, row group: {:?}", opts.column, opts.row_group);
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file.to_path_buf()));
+    }
+
+    let file = open_file(&opts.file)?;
+    let parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
+    let metadata = parquet_reader.metadata();
+    let schema = metadata.file_metadata().schema_descr();
+
+    let columns = match &opts.column {
+        Some(column) => {
+            let index = schema
+                .columns()
+                .iter()
+                .position(|c| &c.path().string() == column)
+                .ok_or_else(|| UnknownColumn(column.to_string()))?;
+            vec![index]
+        }
+        None => (0..schema.num_columns()).collect(),
+    };
+
+    for (rg, row_group) in metadata.row_groups().iter().enumerate() {
+        if opts.row_group.is_some_and(|g| g != rg) {
+            continue;
+        }
+        let row_group_reader = parquet_reader.get_row_group(rg)?;
+
+        for &i in &columns {
+            let column = row_group.column(i);
+            let descr = column.column_descr();
+            let headers = read_page_headers(&file, column)?;
+
+            let mut dictionary = None;
+            let mut data_values = vec![].into_iter();
+            if opts.values {
+                dictionary = dictionary_values(row_group_reader.as_ref(), i)?;
+                let pages = headers
+                    .iter()
+                    .filter(|h| h.is_data_page())
+                    .map(|h| h.num_values().unwrap_or_default() as usize)
+                    .collect::<Vec<_>>();
+                data_values = data_page_values(row_group_reader.as_ref(), i, &pages)?.into_iter();
+            }
+
+            if !opts.json {
+                println!(
+                    "Row group {}, column {} ({}, {}):",
+                    rg, column.column_path().string(), descr.physical_type(), column.compression()
+                );
+            }
+            for (p, info) in headers.iter().enumerate() {
+                let header = &info.header;
+                let v1 = header.data_page_header.as_ref();
+                let v2 = header.data_page_header_v2.as_ref();
+                let values = if info.is_data_page() {
+                    data_values.next()
+                } else if header.dictionary_page_header.is_some() {
+                    dictionary.take()
+                } else {
+                    None
+                };
+
+                let page = PageInfo {
+                    row_group: rg,
+                    column: column.column_path().string(),
+                    page: p,
+                    page_type: info
+                        .page_type()
+                        .map_or_else(|| format!("UNKNOWN({})", header.type_.0), |t| t.to_string()),
+                    offset: info.offset,
+                    header_size: info.header_size,
+                    compressed_size: header.compressed_page_size,
+                    uncompressed_size: header.uncompressed_page_size,
+                    crc: header.crc,
+                    num_values: info.num_values(),
+                    encoding: info.encoding().map(|e| e.to_string()),
+                    definition_level_encoding: v1.map(|h| encoding_name(h.definition_level_encoding)),
+                    repetition_level_encoding: v1.map(|h| encoding_name(h.repetition_level_encoding)),
+                    num_nulls: v2.map(|h| h.num_nulls),
+                    num_rows: v2.map(|h| h.num_rows),
+                    is_sorted: header.dictionary_page_header.as_ref().and_then(|h| h.is_sorted),
+                    statistics: info.statistics().map(|s| PageStatistics::new(s, descr)),
+                    values,
+                };
+
+                if opts.json {
+                    println!("{}", serde_json::to_string(&page)?);
+                } else {
+                    print_page(&page);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
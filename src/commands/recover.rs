@@ -0,0 +1,256 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use clap::{Parser};
+use log::debug;
+use parquet::format::{CompressionCodec, KeyValue};
+use parquet::file::reader::{FileReader, RowGroupReader, SerializedFileReader};
+use parquet::schema::parser::parse_message_type;
+use parquet::schema::types::SchemaDescriptor;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileExists, FileNotFound, InvalidSchema, UnknownCompression};
+use crate::footer::codec_name;
+use crate::pages::read_column_batch;
+use crate::recover::{assemble_row_groups, scan_file, write_row_groups, ScanItem, ScannedRowGroup};
+use crate::utils::{check_path_present, open_file};
+
+// codecs tried in turn when the file does not tell which one its pages use
+const GUESSED_CODECS: [CompressionCodec; 6] = [
+    CompressionCodec::SNAPPY,
+    CompressionCodec::ZSTD,
+    CompressionCodec::GZIP,
+    CompressionCodec::LZ4_RAW,
+    CompressionCodec::BROTLI,
+    CompressionCodec::LZ4,
+];
+
+// salvages the complete row groups of a file whose footer is missing or corrupt
+#[derive(Parser, Debug)]
+pub struct RecoverCommandArgs {
+    file: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        help = "a parquet file with the same schema, or a file holding the schema as a message type"
+    )]
+    schema: PathBuf,
+
+    #[arg(short, long, help = "the file to write the recovered row groups to")]
+    output: PathBuf,
+
+    #[arg(short, long, help = "the compression codec of the pages, guessed when not given")]
+    compression: Option<String>,
+}
+
+// what the schema source tells about the damaged file
+struct SchemaSource {
+    schema: SchemaDescriptor,
+    // the codec of each column in the first row group of a sibling file
+    codecs: Vec<Option<CompressionCodec>>,
+    key_value_metadata: Option<Vec<KeyValue>>,
+}
+
+fn read_schema_source(path: &Path) -> Result<SchemaSource, PQRSError> {
+    if let Ok(reader) = SerializedFileReader::new(open_file(path)?) {
+        let metadata = reader.metadata();
+        let schema = metadata.file_metadata().schema_descr();
+        let codecs = match metadata.row_groups().first() {
+            Some(row_group) => row_group.columns().iter().map(|c| Some(c.compression().into())).collect(),
+            None => vec![None; schema.num_columns()],
+        };
+        return Ok(SchemaSource {
+            schema: SchemaDescriptor::new(schema.root_schema_ptr()),
+            codecs,
+            key_value_metadata: metadata.file_metadata().key_value_metadata().cloned(),
+        });
+    }
+
+    // the message type as printed by the schema command, or its JSON output
+    let text = std::fs::read_to_string(path)
+        .map_err(|_| InvalidSchema(format!("{} is neither a parquet file nor text", path.display())))?;
+    let message = match serde_json::from_str::<serde_json::Value>(&text) {
+        Ok(json) => json["message"].as_str().map(str::to_string).unwrap_or(text),
+        Err(_) => text,
+    };
+    let schema = parse_message_type(&message).map_err(|e| InvalidSchema(e.to_string()))?;
+    let schema = SchemaDescriptor::new(Arc::new(schema));
+    Ok(SchemaSource { codecs: vec![None; schema.num_columns()], schema, key_value_metadata: None })
+}
+
+fn parse_codec(name: &str) -> Result<CompressionCodec, PQRSError> {
+    std::iter::once(CompressionCodec::UNCOMPRESSED)
+        .chain(GUESSED_CODECS)
+        .find(|codec| codec_name(*codec).eq_ignore_ascii_case(name))
+        .ok_or_else(|| UnknownCompression(name.to_string()))
+}
+
+// reads a column chunk back, returning its rows or why it can not be read
+fn count_rows(row_group_reader: &dyn RowGroupReader, index: usize, num_values: i64) -> Result<i64, String> {
+    let mut reader = row_group_reader.get_column_reader(index).map_err(|e| e.to_string())?;
+    let (mut values, mut rows) = (0, 0);
+    loop {
+        let batch = read_column_batch(&mut reader, 4096).map_err(|e| format!("{:?}", e))?;
+        if batch.rep_levels.is_empty() {
+            break;
+        }
+        values += batch.rep_levels.len() as i64;
+        rows += batch.rep_levels.iter().filter(|&&rep| rep == 0).count() as i64;
+    }
+    match values == num_values {
+        true => Ok(rows),
+        false => Err(format!("{} values were decoded instead of {}", values, num_values)),
+    }
+}
+
+// writes a row group alone to a temporary file, then reads each of its column chunks back
+fn decode_row_group(
+    bytes: &[u8],
+    schema: &SchemaDescriptor,
+    row_group: &ScannedRowGroup,
+) -> Result<Vec<Result<i64, String>>, PQRSError> {
+    let file = write_row_groups(tempfile::tempfile()?, bytes, schema, std::slice::from_ref(row_group), None)?;
+    let reader = SerializedFileReader::new(file)?;
+    let row_group_reader = reader.get_row_group(0)?;
+    Ok(row_group
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| count_rows(row_group_reader.as_ref(), i, chunk.num_values()))
+        .collect())
+}
+
+// finds a codec for each column chunk the row group can be read with, and its rows. The codec
+// which worked for a column is tried first in the next row groups.
+fn resolve_row_group(
+    bytes: &[u8],
+    schema: &SchemaDescriptor,
+    row_group: &mut ScannedRowGroup,
+    preferred: &mut [Option<CompressionCodec>],
+    notes: &mut Vec<String>,
+) -> Result<bool, PQRSError> {
+    let candidates = row_group
+        .chunks
+        .iter()
+        .zip(preferred.iter())
+        .map(|(chunk, preferred)| {
+            if let Some(codec) = chunk.codec {
+                return vec![codec];
+            }
+            let mut codecs = Vec::from_iter(*preferred);
+            let uncompressed = chunk
+                .pages
+                .iter()
+                .all(|p| p.header.compressed_page_size == p.header.uncompressed_page_size);
+            if uncompressed && !codecs.contains(&CompressionCodec::UNCOMPRESSED) {
+                codecs.push(CompressionCodec::UNCOMPRESSED);
+            }
+            codecs.extend(GUESSED_CODECS.iter().filter(|c| !codecs.contains(c)).collect::<Vec<_>>());
+            codecs
+        })
+        .collect::<Vec<_>>();
+
+    let mut choices = vec![0; candidates.len()];
+    loop {
+        for ((chunk, codecs), &choice) in row_group.chunks.iter_mut().zip(&candidates).zip(&choices) {
+            chunk.codec = Some(codecs[choice]);
+        }
+        let results = decode_row_group(bytes, schema, row_group)?;
+
+        let mut retry = false;
+        for (i, result) in results.iter().enumerate() {
+            if let Err(e) = result {
+                if choices[i] + 1 == candidates[i].len() {
+                    notes.push(format!(
+                        "dropped the row group at offset {}, column {} can not be read: {}",
+                        row_group.offset(),
+                        schema.column(i).path().string(),
+                        e
+                    ));
+                    return Ok(false);
+                }
+                choices[i] += 1;
+                retry = true;
+            }
+        }
+        if retry {
+            continue;
+        }
+
+        let rows = results.into_iter().collect::<Result<Vec<_>, _>>().unwrap_or_default();
+        if rows.windows(2).any(|w| w[0] != w[1]) {
+            notes.push(format!(
+                "dropped the row group at offset {}, its column chunks hold {:?} rows",
+                row_group.offset(),
+                rows
+            ));
+            return Ok(false);
+        }
+        for (preferred, chunk) in preferred.iter_mut().zip(&row_group.chunks) {
+            *preferred = chunk.codec;
+        }
+        row_group.num_rows = rows.first().copied();
+        return Ok(true);
+    }
+}
+
+pub(crate) fn execute(opts: RecoverCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file name to recover is: {}", opts.file.display());
+    debug!("The schema is read from: {}", opts.schema.display());
+    debug!("The file name to write to: {}", opts.output.display());
+
+    if check_path_present(&opts.output) {
+        return Err(FileExists(opts.output.to_path_buf()));
+    }
+    for file_name in [&opts.file, &opts.schema] {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    let source = read_schema_source(&opts.schema)?;
+    let mut preferred = match &opts.compression {
+        Some(name) => vec![Some(parse_codec(name)?); source.schema.num_columns()],
+        None => source.codecs,
+    };
+    let schema = source.schema;
+
+    let bytes = std::fs::read(&opts.file)?;
+    let items = scan_file(&bytes, &schema);
+    let pages = items.iter().filter(|item| matches!(item, ScanItem::Page(_))).count();
+    let (row_groups, mut notes) = assemble_row_groups(items, &schema);
+    println!(
+        "Scanned {} bytes of {}: {} pages in {} row groups",
+        bytes.len(),
+        opts.file.display(),
+        pages,
+        row_groups.len()
+    );
+
+    let mut recovered = vec![];
+    for mut row_group in row_groups {
+        if resolve_row_group(&bytes, &schema, &mut row_group, &mut preferred, &mut notes)? {
+            recovered.push(row_group);
+        }
+    }
+    for note in &notes {
+        println!("  {}", note);
+    }
+
+    let file = BufWriter::new(File::create(&opts.output)?);
+    let mut file = write_row_groups(file, &bytes, &schema, &recovered, source.key_value_metadata)?;
+    file.flush()?;
+
+    let rows = recovered.iter().map(|r| r.num_rows.unwrap_or_default()).sum::<i64>();
+    println!(
+        "Recovered {} rows in {} row groups into {}",
+        rows,
+        recovered.len(),
+        opts.output.display()
+    );
+
+    Ok(())
+}
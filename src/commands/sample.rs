@@ -2,40 +2,119 @@ use std::path::PathBuf;
 
 use clap::{Parser};
 use log::debug;
+use parquet::file::reader::{FileReader, SerializedFileReader};
 
 use crate::errors::PQRSError;
-use crate::errors::PQRSError::FileNotFound;
-use crate::utils::{check_path_present, Formats, open_file, print_rows_random};
+use crate::errors::PQRSError::{FileExists, FileNotFound, InvalidSampleFraction};
+use crate::sampling::{Allocation, sample_rng, sample_rows, sample_strata, SampleSize};
+use crate::timestamp::TimestampArgs;
+use crate::utils::{check_path_present, Formats, open_file, print_row_ranges, write_row_ranges};
 
 // prints a random sample of records from the parquet file
 #[derive(Parser, Debug)]
 pub struct SampleCommandArgs {
     file: PathBuf,
 
-    #[arg(short = 'n', long)]
-    records: usize,
+    #[arg(short = 'n', long, required_unless_present_any = ["fraction", "per_stratum"], conflicts_with = "fraction")]
+    records: Option<usize>,
+
+    #[arg(long, help = "keep each record with this probability, between 0 and 1")]
+    fraction: Option<f64>,
+
+    #[arg(long, help = "seed of the random generator, to get the same sample again")]
+    seed: Option<u64>,
+
+    #[arg(long = "row-groups", help = "sample whole row groups first so only those are decoded")]
+    by_row_group: bool,
+
+    #[arg(long = "stratify-by", value_name = "COLUMN", conflicts_with_all = ["fraction", "by_row_group"], help = "sample each distinct value of this top level column separately, in proportion to its size with --records")]
+    stratify_by: Option<String>,
+
+    #[arg(long = "per-stratum", requires = "stratify_by", conflicts_with = "records", help = "number of records to sample from each distinct value")]
+    per_stratum: Option<usize>,
+
+    #[arg(long = "max-strata", default_value = "10000", requires = "stratify_by", help = "maximum number of distinct values to stratify by")]
+    max_strata: usize,
+
+    #[arg(short, long, conflicts_with = "json")]
+    csv: bool,
+
+    #[arg(long = "no-header", requires = "csv", conflicts_with = "json")]
+    csv_no_header: bool,
 
     #[arg(short, long)]
     json: bool,
+
+    #[arg(short, long, conflicts_with_all = ["csv", "json"], help = "write the sample to a new parquet file")]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    timestamps: TimestampArgs,
 }
 
 pub fn execute(opts: SampleCommandArgs) -> Result<(), PQRSError> {
+    let timestamps = opts.timestamps.options()?;
     let format = if opts.json {
         Formats::Json
+    } else if opts.csv_no_header {
+        Formats::CsvNoHeader
+    } else if opts.csv {
+        Formats::Csv
     } else {
         Formats::Default
     };
 
+    let size = match (opts.records.or(opts.per_stratum), opts.fraction) {
+        (_, Some(fraction)) if !(fraction > 0.0 && fraction <= 1.0) => {
+            return Err(InvalidSampleFraction(fraction));
+        }
+        (_, Some(fraction)) => SampleSize::Fraction(fraction),
+        (Some(records), None) => SampleSize::Records(records),
+        (None, None) => unreachable!("clap requires records or fraction"),
+    };
+
     debug!("The file name to read is :{}", opts.file.display());
-    debug!("Number of records to print: {}", opts.records);
+    debug!("Sample size: {:?}, by row group: {}", size, opts.by_row_group);
+    debug!("Stratify by: {:?}", opts.stratify_by);
     debug!("Output format :{}", format);
 
     if !check_path_present(&opts.file) {
         return Err(FileNotFound(opts.file.to_path_buf()));
     }
 
+    if let Some(output) = &opts.output {
+        if check_path_present(output) {
+            return Err(FileExists(output.to_path_buf()));
+        }
+    }
+
+    let mut rng = sample_rng(opts.seed);
+    let ranges = match &opts.stratify_by {
+        Some(column) => {
+            let allocation = match opts.per_stratum {
+                Some(records) => Allocation::PerStratum(records),
+                None => Allocation::Proportional(opts.records.unwrap_or_default()),
+            };
+            let file = open_file(&opts.file)?;
+            let (ranges, strata) = sample_strata(file, column, allocation, opts.max_strata, &timestamps, &mut rng)?;
+
+            eprintln!("Strata of column {}:", column);
+            for stratum in &strata {
+                eprintln!("  {}: {} rows, {} sampled", stratum.value, stratum.rows, stratum.sampled);
+            }
+            ranges
+        }
+        None => {
+            let parquet_reader = SerializedFileReader::new(open_file(&opts.file)?)?;
+            sample_rows(parquet_reader.metadata(), size, opts.by_row_group, &mut rng)
+        }
+    };
+
     let file = open_file(&opts.file)?;
-    print_rows_random(file, opts.records, format)?;
+    match &opts.output {
+        Some(output) => write_row_ranges(file, &ranges, output)?,
+        None => print_row_ranges(file, &ranges, format, &timestamps)?,
+    }
 
     Ok(())
 }
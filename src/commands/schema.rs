@@ -2,16 +2,20 @@ use std::collections::HashMap;
 use std::io::BufWriter;
 use std::path::PathBuf;
 
-use clap::{Parser};
+use clap::{Parser, ValueEnum};
 use log::debug;
 use parquet::file::metadata::ParquetMetaData;
 use parquet::file::reader::{FileReader, SerializedFileReader};
 use parquet::schema::printer::{print_file_metadata, print_parquet_metadata, print_schema};
 use serde::{Deserialize, Serialize};
 
+use crate::ddl::{create_table, Dialect};
+use crate::embedded::{decode_embedded_schemas, print_embedded_schemas, EmbeddedSchemas};
 use crate::errors::PQRSError;
 use crate::errors::PQRSError::FileNotFound;
-use crate::utils::{check_path_present, open_file};
+use crate::export::{avro_schema, json_schema, proto_schema};
+use crate::fields::schema_fields;
+use crate::utils::{check_path_present, get_schema_metadata, open_file};
 
 #[derive(Parser, Debug)]
 pub struct SchemaCommandArgs {
@@ -20,6 +24,26 @@ pub struct SchemaCommandArgs {
     detailed: bool,
     #[arg(short, long, conflicts_with = "detailed")]
     json: bool,
+    #[arg(short, long, value_enum, default_value = "text", conflicts_with_all = ["detailed", "json"])]
+    format: SchemaFormat,
+    #[arg(long, value_enum, default_value = "postgres", help = "dialect of the ddl format")]
+    dialect: Dialect,
+    #[arg(long, help = "name of the table, record or message, the file name without its extension by default")]
+    table: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+enum SchemaFormat {
+    /// The Parquet message type and file metadata
+    Text,
+    /// A CREATE TABLE statement, lossy mappings are told in comments
+    Ddl,
+    /// An Avro record schema
+    Avro,
+    /// A JSON Schema of the rows printed by `cat --json`
+    JsonSchema,
+    /// A proto3 message definition
+    Proto,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -30,24 +54,14 @@ pub struct ParquetSchema {
     metadata: Option<HashMap<String, Option<String>>>,
     columns: Vec<HashMap<String, String>>,
     message: String,
-}
-
-fn get_schema_metadata(metadata: &ParquetMetaData) -> Option<HashMap<String, Option<String>>> {
-    if let Some(metadata) = metadata.file_metadata().key_value_metadata() {
-        let mut fields: HashMap<String, Option<String>> = HashMap::new();
-        for kv in metadata.iter() {
-            fields.insert(kv.key.to_string(), kv.value.to_owned());
-        }
-        Some(fields)
-    } else {
-        None
-    }
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    embedded_schemas: Option<EmbeddedSchemas>,
 }
 
 fn get_column_information(metadata: &ParquetMetaData) -> Vec<HashMap<String, String>> {
     let schema = metadata.file_metadata().schema_descr();
     let mut columns = Vec::new();
-    for (_i, col) in schema.columns().iter().enumerate() {
+    for col in schema.columns().iter() {
         let mut column_info: HashMap<String, String> = HashMap::new();
         column_info.insert(String::from("name"), String::from(col.name()));
         column_info.insert(String::from("path"), col.path().string());
@@ -80,7 +94,29 @@ pub fn execute(opts: SchemaCommandArgs) -> Result<(), PQRSError> {
         match SerializedFileReader::new(file) {
             Ok(parquet_reader) => {
                 let metadata = parquet_reader.metadata();
-                if opts.json {
+                let embedded = decode_embedded_schemas(metadata.file_metadata());
+                if opts.format != SchemaFormat::Text {
+                    let name = match &opts.table {
+                        Some(table) => table.to_string(),
+                        None => file_name.file_stem().unwrap_or_default().to_string_lossy().into_owned(),
+                    };
+                    let fields = schema_fields(metadata.file_metadata().schema_descr());
+                    match opts.format {
+                        SchemaFormat::Ddl => {
+                            let (statement, warnings) = create_table(opts.dialect, &name, &fields);
+                            for warning in warnings {
+                                println!("-- warning: {}", warning);
+                            }
+                            println!("{}", statement);
+                        }
+                        SchemaFormat::Avro => println!("{}", serde_json::to_string_pretty(&avro_schema(&name, &fields))?),
+                        SchemaFormat::JsonSchema => {
+                            println!("{}", serde_json::to_string_pretty(&json_schema(&name, &fields))?)
+                        }
+                        SchemaFormat::Proto => print!("{}", proto_schema(&name, &fields)),
+                        SchemaFormat::Text => unreachable!(),
+                    }
+                } else if opts.json {
                     let schema = ParquetSchema {
                         version: metadata.file_metadata().version(),
                         num_rows: metadata.file_metadata().num_rows(),
@@ -91,6 +127,7 @@ pub fn execute(opts: SchemaCommandArgs) -> Result<(), PQRSError> {
                         metadata: get_schema_metadata(metadata),
                         columns: get_column_information(metadata),
                         message: get_message(metadata)?,
+                        embedded_schemas: (!embedded.is_empty()).then_some(embedded),
                     };
                     let schema_json = serde_json::to_string(&schema)?;
                     println!("{}", schema_json);
@@ -102,6 +139,10 @@ pub fn execute(opts: SchemaCommandArgs) -> Result<(), PQRSError> {
                     } else {
                         print_file_metadata(&mut std::io::stdout(), metadata.file_metadata())
                     }
+                    if !embedded.is_empty() {
+                        println!();
+                        print_embedded_schemas(&embedded);
+                    }
                 }
             }
             Err(e) => { return Err(PQRSError::ParquetError(e)); }
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use clap::{Parser};
+use log::debug;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::FileNotFound;
+use crate::timestamp::TimestampArgs;
+use crate::utils::{check_path_present, Formats, open_file, print_rows_range};
+
+#[derive(Parser, Debug)]
+#[command(about = "show a range of records of the given file", long_about = None)]
+pub struct SliceCommandArgs {
+    #[clap(short, long, conflicts_with = "json")]
+    csv: bool,
+    #[clap(long = "no-header", requires = "csv", conflicts_with = "json")]
+    csv_no_header: bool,
+    #[clap(short, long, conflicts_with = "csv")]
+    json: bool,
+    #[clap(short, long, default_value = "0", help = "index of the first record to print")]
+    offset: usize,
+    #[clap(short, long, help = "number of records to print, all remaining records if not given")]
+    limit: Option<usize>,
+    #[command(flatten)]
+    timestamps: TimestampArgs,
+    file: PathBuf,
+}
+
+pub fn execute(opts: SliceCommandArgs) -> Result<(), PQRSError> {
+    let timestamps = opts.timestamps.options()?;
+    let format = if opts.json {
+        Formats::Json
+    } else if opts.csv_no_header {
+        Formats::CsvNoHeader
+    } else if opts.csv {
+        Formats::Csv
+    } else {
+        Formats::Default
+    };
+
+    debug!("The file name to read is: {}", opts.file.display());
+    debug!("Records to print: offset {}, limit {:?}", opts.offset, opts.limit);
+    debug!("output format: {}", format);
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file));
+    }
+
+    let file = open_file(&opts.file)?;
+    print_rows_range(file, opts.offset, opts.limit, format, &timestamps)
+}
@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use clap::{Parser};
+use log::debug;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::FileNotFound;
+use crate::timestamp::TimestampArgs;
+use crate::utils::{check_path_present, Formats, get_row_count, open_file, print_rows_range};
+
+#[derive(Parser, Debug)]
+#[command(about = "show the last records of the given file", long_about = None)]
+pub struct TailCommandArgs {
+    #[clap(short, long, conflicts_with = "json")]
+    csv: bool,
+    #[clap(long = "no-header", requires = "csv", conflicts_with = "json")]
+    csv_no_header: bool,
+    #[clap(short, long, conflicts_with = "csv")]
+    json: bool,
+    #[clap(short = 'n', long, default_value = "5")]
+    records: usize,
+    #[command(flatten)]
+    timestamps: TimestampArgs,
+    file: PathBuf,
+}
+
+pub fn execute(opts: TailCommandArgs) -> Result<(), PQRSError> {
+    let timestamps = opts.timestamps.options()?;
+    let format = if opts.json {
+        Formats::Json
+    } else if opts.csv_no_header {
+        Formats::CsvNoHeader
+    } else if opts.csv {
+        Formats::Csv
+    } else {
+        Formats::Default
+    };
+
+    debug!("The file name to read is: {}", opts.file.display());
+    debug!("Number of records to print is: {}", opts.records);
+    debug!("output format: {}", format);
+
+    if !check_path_present(&opts.file) {
+        return Err(FileNotFound(opts.file));
+    }
+
+    let total_rows = get_row_count(open_file(&opts.file)?)? as usize;
+    let offset = total_rows.saturating_sub(opts.records);
+
+    let file = open_file(&opts.file)?;
+    print_rows_range(file, offset, Some(opts.records), format, &timestamps)
+}
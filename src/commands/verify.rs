@@ -0,0 +1,292 @@
+use std::fmt;
+use std::fs::File;
+use std::path::PathBuf;
+
+use clap::{Parser};
+use log::debug;
+use parquet::file::metadata::{ColumnChunkMetaData, RowGroupMetaData};
+use parquet::file::reader::{ChunkReader, FileReader, RowGroupReader, SerializedFileReader};
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, VerificationFailed};
+use crate::footer::{check_footer, read_file_metadata, MAGIC};
+use crate::pages::{column_byte_range, read_column_batch, read_page_headers, PageHeaderInfo};
+use crate::utils::{check_path_present, open_file};
+
+// checks the integrity of parquet files, down to their decoded values
+#[derive(Parser, Debug)]
+pub struct VerifyCommandArgs {
+    files: Vec<PathBuf>,
+}
+
+// something wrong in a file, with where it was found
+#[derive(Debug)]
+struct Problem {
+    row_group: Option<usize>,
+    column: Option<String>,
+    offset: Option<u64>,
+    message: String,
+}
+
+impl Problem {
+    fn file(message: String) -> Self {
+        Problem { row_group: None, column: None, offset: None, message }
+    }
+
+    fn chunk(row_group: usize, column: &ColumnChunkMetaData, offset: u64, message: String) -> Self {
+        Problem {
+            row_group: Some(row_group),
+            column: Some(column.column_path().string()),
+            offset: Some(offset),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut location = vec![];
+        if let Some(row_group) = self.row_group {
+            location.push(format!("row group {}", row_group));
+        }
+        if let Some(column) = &self.column {
+            location.push(format!("column {}", column));
+        }
+        if let Some(offset) = self.offset {
+            location.push(format!("offset {}", offset));
+        }
+        match location.is_empty() {
+            true => write!(f, "{}", self.message),
+            false => write!(f, "{}: {}", location.join(", "), self.message),
+        }
+    }
+}
+
+// checks the page headers and checksums of a column chunk, returns the headers when the
+// pages can be read further
+fn verify_page_headers(
+    file: &File,
+    rg: usize,
+    column: &ColumnChunkMetaData,
+    (start, end): (u64, u64),
+    problems: &mut Vec<Problem>,
+) -> Option<Vec<PageHeaderInfo>> {
+    let headers = match read_page_headers(file, column) {
+        Ok(headers) => headers,
+        Err(e) => {
+            let message = format!("the page headers can not be decoded: {:?}", e);
+            problems.push(Problem::chunk(rg, column, start, message));
+            return None;
+        }
+    };
+
+    let mut readable = true;
+    let mut num_values = 0;
+    for header in &headers {
+        let data_start = header.offset + header.header_size as u64;
+        let Ok(page_size) = u64::try_from(header.header.compressed_page_size) else {
+            let message = format!("the page has a negative size {}", header.header.compressed_page_size);
+            problems.push(Problem::chunk(rg, column, header.offset, message));
+            readable = false;
+            continue;
+        };
+        let page_end = data_start.checked_add(page_size);
+        if page_end.is_none_or(|page_end| page_end > end) {
+            let message = match page_end {
+                Some(page_end) => format!(
+                    "the page ends at {}, after the end of the column chunk at {}",
+                    page_end, end
+                ),
+                None => format!("the page size {} overflows the file offsets", page_size),
+            };
+            problems.push(Problem::chunk(rg, column, header.offset, message));
+            readable = false;
+            continue;
+        }
+        if header.is_data_page() {
+            num_values += header.num_values().unwrap_or_default() as i64;
+        }
+        if let Some(crc) = header.header.crc {
+            let bytes = match file.get_bytes(data_start, page_size as usize) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    problems.push(Problem::chunk(rg, column, header.offset, e.to_string()));
+                    readable = false;
+                    continue;
+                }
+            };
+            let checksum = crc32fast::hash(&bytes) as i32;
+            if checksum != crc {
+                let message = format!(
+                    "the page checksum is {:08x}, the header says {:08x}",
+                    checksum, crc
+                );
+                problems.push(Problem::chunk(rg, column, header.offset, message));
+            }
+        }
+    }
+    if readable && num_values != column.num_values() {
+        let message = format!(
+            "the data page headers hold {} values, the column chunk {}",
+            num_values,
+            column.num_values()
+        );
+        problems.push(Problem::chunk(rg, column, start, message));
+    }
+
+    readable.then_some(headers)
+}
+
+// decompresses every page of a column chunk then decodes its values, counting them
+fn verify_values(
+    row_group_reader: &dyn RowGroupReader,
+    row_group: &RowGroupMetaData,
+    rg: usize,
+    index: usize,
+    start: u64,
+    headers: &[PageHeaderInfo],
+    problems: &mut Vec<Problem>,
+) -> Result<(), PQRSError> {
+    let column = row_group.column(index);
+
+    let mut page_reader = row_group_reader.get_column_page_reader(index)?;
+    let mut pages = 0;
+    loop {
+        match page_reader.get_next_page() {
+            Ok(Some(_)) => pages += 1,
+            Ok(None) => break,
+            Err(e) => {
+                let offset = headers.get(pages).map_or(start, |h| h.offset);
+                let message = format!("the page can not be decompressed: {}", e);
+                problems.push(Problem::chunk(rg, column, offset, message));
+                return Ok(());
+            }
+        }
+    }
+
+    let mut reader = row_group_reader.get_column_reader(index)?;
+    let (mut num_values, mut num_rows) = (0, 0);
+    loop {
+        let batch = match read_column_batch(&mut reader, 4096) {
+            Ok(batch) => batch,
+            Err(e) => {
+                let message = format!("the values can not be decoded: {:?}", e);
+                problems.push(Problem::chunk(rg, column, start, message));
+                return Ok(());
+            }
+        };
+        if batch.rep_levels.is_empty() {
+            break;
+        }
+        num_values += batch.rep_levels.len() as i64;
+        num_rows += batch.rep_levels.iter().filter(|&&rep| rep == 0).count() as i64;
+    }
+
+    if num_values != column.num_values() {
+        let message = format!(
+            "{} values were decoded, the column chunk holds {}",
+            num_values,
+            column.num_values()
+        );
+        problems.push(Problem::chunk(rg, column, start, message));
+    }
+    if num_rows != row_group.num_rows() {
+        let message = format!(
+            "{} rows were decoded, the row group holds {}",
+            num_rows,
+            row_group.num_rows()
+        );
+        problems.push(Problem::chunk(rg, column, start, message));
+    }
+    Ok(())
+}
+
+fn verify_file(file: File) -> Result<Vec<Problem>, PQRSError> {
+    let check = check_footer(&file)?;
+    let mut problems = check.problems().into_iter().map(Problem::file).collect::<Vec<_>>();
+    if !problems.is_empty() {
+        return Ok(problems);
+    }
+    if let Err(e) = read_file_metadata(&file, &check) {
+        problems.push(Problem::file(format!("the file metadata can not be decoded: {:?}", e)));
+        return Ok(problems);
+    }
+    let parquet_reader = match SerializedFileReader::new(file.try_clone()?) {
+        Ok(reader) => reader,
+        Err(e) => {
+            problems.push(Problem::file(format!("the file metadata is not valid: {}", e)));
+            return Ok(problems);
+        }
+    };
+
+    let metadata = parquet_reader.metadata();
+    // column chunks lie between the header magic and the file metadata
+    let data_start = MAGIC.len() as u64;
+    let data_end = check.metadata_offset.unwrap_or(check.file_size);
+    let mut num_rows = 0;
+    for (rg, row_group) in metadata.row_groups().iter().enumerate() {
+        num_rows += row_group.num_rows();
+        let row_group_reader = parquet_reader.get_row_group(rg)?;
+        for (index, column) in row_group.columns().iter().enumerate() {
+            let (start, end) = match column_byte_range(column) {
+                Ok((start, length)) => (start, start + length),
+                Err(e) => {
+                    // the offsets themselves are broken, there is no offset to point at
+                    let message = format!("the column chunk can not be located: {:?}", e);
+                    problems.push(Problem { offset: None, ..Problem::chunk(rg, column, 0, message) });
+                    continue;
+                }
+            };
+            if start < data_start || end > data_end {
+                let message = format!(
+                    "the column chunk spans bytes {} to {}, out of the data between {} and {}",
+                    start, end, data_start, data_end
+                );
+                problems.push(Problem::chunk(rg, column, start, message));
+                continue;
+            }
+
+            if let Some(headers) = verify_page_headers(&file, rg, column, (start, end), &mut problems) {
+                verify_values(row_group_reader.as_ref(), row_group, rg, index, start, &headers, &mut problems)?;
+            }
+        }
+    }
+    if num_rows != metadata.file_metadata().num_rows() {
+        problems.push(Problem::file(format!(
+            "the row groups hold {} rows, the file metadata {}",
+            num_rows,
+            metadata.file_metadata().num_rows()
+        )));
+    }
+
+    Ok(problems)
+}
+
+pub(crate) fn execute(opts: VerifyCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to read are: {:?}", opts.files);
+
+    for file_name in &opts.files {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    let mut failed = 0;
+    for file_name in &opts.files {
+        let problems = verify_file(open_file(file_name)?)?;
+        if problems.is_empty() {
+            println!("{}: OK", file_name.display());
+            continue;
+        }
+        failed += 1;
+        println!("{}: {} problems", file_name.display(), problems.len());
+        for problem in &problems {
+            println!("  {}", problem);
+        }
+    }
+
+    if failed > 0 {
+        return Err(VerificationFailed(failed));
+    }
+    Ok(())
+}
@@ -0,0 +1,306 @@
+use clap::ValueEnum;
+
+use crate::fields::{FieldDef, FieldType, Unit};
+
+/// The SQL dialects CREATE TABLE statements are written for.
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub enum Dialect {
+    Postgres,
+    Duckdb,
+    Sqlite,
+    Hive,
+    Spark,
+    Bigquery,
+}
+
+// words which are reserved in at least one dialect and likely as column names
+const RESERVED: &[&str] = &[
+    "all", "and", "array", "as", "between", "by", "case", "cast", "check", "column", "create", "cross", "date",
+    "default", "desc", "distinct", "else", "end", "exists", "false", "from", "full", "group", "having", "in",
+    "inner", "interval", "into", "is", "join", "left", "like", "limit", "map", "not", "null", "on", "or", "order",
+    "outer", "partition", "range", "right", "rows", "select", "struct", "table", "then", "time", "timestamp", "to",
+    "true", "union", "user", "using", "values", "when", "where", "window", "with",
+];
+
+impl Dialect {
+    /// The identifier, quoted unless it is a lower case word which is not reserved.
+    pub fn quote(&self, name: &str) -> String {
+        let mut chars = name.chars();
+        let simple = chars.next().is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+            && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            && !RESERVED.contains(&name);
+        match (simple, self) {
+            (true, _) => name.to_string(),
+            (false, Dialect::Hive | Dialect::Spark | Dialect::Bigquery) => format!("`{}`", name.replace('`', "``")),
+            (false, _) => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+}
+
+// maps the fields to the types of a dialect and tells what does not map exactly
+struct Mapper {
+    dialect: Dialect,
+    warnings: Vec<String>,
+}
+
+impl Mapper {
+    fn warn(&mut self, path: &str, message: String) {
+        self.warnings.push(format!("{}: {}", path, message));
+    }
+
+    fn integer(&mut self, path: &str, bits: u8, signed: bool) -> String {
+        let name = match (self.dialect, bits, signed) {
+            (Dialect::Sqlite, 64, false) => {
+                self.warn(path, "unsigned 64 bit integers above 2^63 - 1 overflow INTEGER".to_string());
+                "INTEGER"
+            }
+            (Dialect::Sqlite, _, _) => "INTEGER",
+            (Dialect::Bigquery, 64, false) => {
+                self.warn(path, "unsigned 64 bit integers above 2^63 - 1 overflow INT64".to_string());
+                "INT64"
+            }
+            (Dialect::Bigquery, _, _) => "INT64",
+            (Dialect::Duckdb, 8, true) => "TINYINT",
+            (Dialect::Duckdb, 16, true) => "SMALLINT",
+            (Dialect::Duckdb, 32, true) => "INTEGER",
+            (Dialect::Duckdb, _, true) => "BIGINT",
+            (Dialect::Duckdb, 8, false) => "UTINYINT",
+            (Dialect::Duckdb, 16, false) => "USMALLINT",
+            (Dialect::Duckdb, 32, false) => "UINTEGER",
+            (Dialect::Duckdb, _, false) => "UBIGINT",
+            // unsigned integers take the next wider signed type
+            (Dialect::Postgres, 8 | 16, true) | (Dialect::Postgres, 8, false) => "SMALLINT",
+            (Dialect::Postgres, 32, true) | (Dialect::Postgres, 16, false) => "INTEGER",
+            (Dialect::Postgres, 64, true) | (Dialect::Postgres, 32, false) => "BIGINT",
+            (Dialect::Postgres, _, _) => "NUMERIC(20, 0)",
+            (_, 8, true) => "TINYINT",
+            (_, 16, true) | (_, 8, false) => "SMALLINT",
+            (_, 32, true) | (_, 16, false) => "INT",
+            (_, 64, true) | (_, 32, false) => "BIGINT",
+            (_, _, _) => "DECIMAL(20, 0)",
+        };
+        name.to_string()
+    }
+
+    fn decimal(&mut self, path: &str, precision: i32, scale: i32) -> String {
+        match self.dialect {
+            Dialect::Postgres => format!("NUMERIC({}, {})", precision, scale),
+            Dialect::Sqlite => {
+                if precision > 15 {
+                    let message = format!("DECIMAL({}, {}) is stored as a floating point NUMERIC", precision, scale);
+                    self.warn(path, message);
+                }
+                "NUMERIC".to_string()
+            }
+            Dialect::Bigquery if scale <= 9 && precision <= scale + 29 => format!("NUMERIC({}, {})", precision, scale),
+            Dialect::Bigquery if scale <= 38 && precision <= scale + 38 => {
+                format!("BIGNUMERIC({}, {})", precision, scale)
+            }
+            Dialect::Bigquery => {
+                self.warn(path, format!("DECIMAL({}, {}) does not fit BIGNUMERIC, mapped to STRING", precision, scale));
+                "STRING".to_string()
+            }
+            _ if precision <= 38 => format!("DECIMAL({}, {})", precision, scale),
+            _ => {
+                let message = format!("DECIMAL({}, {}) is above the precision of 38, mapped to DOUBLE", precision, scale);
+                self.warn(path, message);
+                "DOUBLE".to_string()
+            }
+        }
+    }
+
+    fn time(&mut self, path: &str, unit: Unit) -> String {
+        match self.dialect {
+            Dialect::Sqlite => "TEXT".to_string(),
+            // no time of day type, the values are read as integers
+            Dialect::Hive | Dialect::Spark => {
+                self.warn(path, format!("there is no TIME type, mapped to the {} since midnight", unit.name()));
+                match unit {
+                    Unit::Millis => "INT".to_string(),
+                    _ => "BIGINT".to_string(),
+                }
+            }
+            _ => {
+                if unit == Unit::Nanos {
+                    self.warn(path, "TIME holds microseconds, the nanoseconds are truncated".to_string());
+                }
+                "TIME".to_string()
+            }
+        }
+    }
+
+    fn timestamp(&mut self, path: &str, unit: Unit, utc: bool) -> String {
+        let name = match (self.dialect, unit, utc) {
+            (Dialect::Sqlite, _, _) => "TEXT",
+            (Dialect::Postgres, _, true) => "TIMESTAMPTZ",
+            (Dialect::Postgres, _, false) => "TIMESTAMP",
+            (Dialect::Duckdb, _, true) => "TIMESTAMPTZ",
+            (Dialect::Duckdb, Unit::Millis, false) => "TIMESTAMP_MS",
+            (Dialect::Duckdb, Unit::Micros, false) => "TIMESTAMP",
+            (Dialect::Duckdb, Unit::Nanos, false) => return "TIMESTAMP_NS".to_string(),
+            (Dialect::Hive, _, true) => {
+                let message = "TIMESTAMP has no time zone, the UTC instants are read as local date times";
+                self.warn(path, message.to_string());
+                return "TIMESTAMP".to_string();
+            }
+            (Dialect::Hive, _, false) => return "TIMESTAMP".to_string(),
+            (Dialect::Spark, _, true) => "TIMESTAMP",
+            (Dialect::Spark, _, false) => "TIMESTAMP_NTZ",
+            (Dialect::Bigquery, _, true) => "TIMESTAMP",
+            (Dialect::Bigquery, _, false) => "DATETIME",
+        };
+        if unit == Unit::Nanos && self.dialect != Dialect::Sqlite {
+            self.warn(path, format!("{} holds microseconds, the nanoseconds are truncated", name));
+        }
+        name.to_string()
+    }
+
+    // the type stored as JSON text by the dialects without nested types
+    fn json(&mut self, path: &str, what: &str) -> String {
+        let name = match self.dialect {
+            Dialect::Postgres => "JSONB",
+            Dialect::Sqlite => "TEXT",
+            _ => "JSON",
+        };
+        self.warn(path, format!("{} are not supported, mapped to {}", what, name));
+        name.to_string()
+    }
+
+    fn struct_fields(&mut self, path: &str, fields: &[FieldDef]) -> Vec<String> {
+        fields
+            .iter()
+            .map(|f| {
+                let name = self.dialect.quote(&f.name);
+                let sql_type = self.sql_type(&format!("{}.{}", path, f.name), &f.field_type);
+                match self.dialect {
+                    Dialect::Hive => format!("{}:{}", name, sql_type),
+                    Dialect::Spark => format!("{}: {}", name, sql_type),
+                    _ => format!("{} {}", name, sql_type),
+                }
+            })
+            .collect()
+    }
+
+    fn sql_type(&mut self, path: &str, field_type: &FieldType) -> String {
+        let dialect = self.dialect;
+        let name = match (field_type, dialect) {
+            (FieldType::Boolean, Dialect::Sqlite) => "INTEGER",
+            (FieldType::Boolean, Dialect::Bigquery) => "BOOL",
+            (FieldType::Boolean, _) => "BOOLEAN",
+            (FieldType::Integer { bits, signed }, _) => return self.integer(path, *bits, *signed),
+            (FieldType::Float, Dialect::Postgres) => "REAL",
+            (FieldType::Float | FieldType::Double, Dialect::Sqlite) => "REAL",
+            (FieldType::Float | FieldType::Double, Dialect::Bigquery) => "FLOAT64",
+            (FieldType::Float, _) => "FLOAT",
+            (FieldType::Double, Dialect::Postgres) => "DOUBLE PRECISION",
+            (FieldType::Double, _) => "DOUBLE",
+            (FieldType::Decimal { precision, scale }, _) => return self.decimal(path, *precision, *scale),
+            (FieldType::String | FieldType::Enum, Dialect::Postgres | Dialect::Sqlite) => "TEXT",
+            (FieldType::String | FieldType::Enum, Dialect::Duckdb) => "VARCHAR",
+            (FieldType::String | FieldType::Enum, _) => "STRING",
+            (FieldType::Json, Dialect::Postgres) => "JSONB",
+            (FieldType::Json, Dialect::Duckdb | Dialect::Bigquery) => "JSON",
+            (FieldType::Json, Dialect::Sqlite) => "TEXT",
+            (FieldType::Json, _) => "STRING",
+            (FieldType::Uuid, Dialect::Postgres | Dialect::Duckdb) => "UUID",
+            (FieldType::Bson | FieldType::Uuid | FieldType::Binary | FieldType::FixedBinary(_), dialect) => {
+                match dialect {
+                    Dialect::Postgres => "BYTEA",
+                    Dialect::Duckdb | Dialect::Sqlite => "BLOB",
+                    Dialect::Hive | Dialect::Spark => "BINARY",
+                    Dialect::Bigquery => "BYTES",
+                }
+            }
+            (FieldType::Date, Dialect::Sqlite) => "TEXT",
+            (FieldType::Date, _) => "DATE",
+            (FieldType::Time { unit, .. }, _) => return self.time(path, *unit),
+            (FieldType::Timestamp { unit, utc }, _) => return self.timestamp(path, *unit, *utc),
+            (FieldType::Interval, Dialect::Postgres | Dialect::Duckdb) => "INTERVAL",
+            (FieldType::Interval, _) => {
+                let binary = match dialect {
+                    Dialect::Sqlite => "BLOB",
+                    Dialect::Bigquery => "BYTES",
+                    _ => "BINARY",
+                };
+                self.warn(path, format!("INTERVAL is not supported, mapped to {} of 12 bytes", binary));
+                binary
+            }
+
+            (FieldType::Struct(_), Dialect::Postgres | Dialect::Sqlite) => return self.json(path, "structs"),
+            (FieldType::Struct(fields), _) => {
+                let fields = self.struct_fields(path, fields).join(", ");
+                return match dialect {
+                    Dialect::Duckdb => format!("STRUCT({})", fields),
+                    _ => format!("STRUCT<{}>", fields),
+                };
+            }
+            (FieldType::List(_), Dialect::Sqlite) => return self.json(path, "lists"),
+            (FieldType::List(element), Dialect::Postgres) if element.field_type.is_nested() => {
+                return self.json(path, "lists of nested values")
+            }
+            // maps are lists of structs in BigQuery, which has no arrays of arrays
+            (FieldType::List(element), Dialect::Bigquery)
+                if matches!(element.field_type, FieldType::List(_) | FieldType::Map(_, _)) =>
+            {
+                return self.json(path, "lists of lists or maps")
+            }
+            (FieldType::List(element), _) => {
+                let element = self.sql_type(&format!("{}.{}", path, element.name), &element.field_type);
+                return match dialect {
+                    Dialect::Postgres | Dialect::Duckdb => format!("{}[]", element),
+                    _ => format!("ARRAY<{}>", element),
+                };
+            }
+            (FieldType::Map(_, _), Dialect::Postgres | Dialect::Sqlite) => return self.json(path, "maps"),
+            (FieldType::Map(key, value), _) => {
+                let key_type = self.sql_type(&format!("{}.{}", path, key.name), &key.field_type);
+                let value_type = self.sql_type(&format!("{}.{}", path, value.name), &value.field_type);
+                return match dialect {
+                    Dialect::Duckdb => format!("MAP({}, {})", key_type, value_type),
+                    Dialect::Bigquery => {
+                        self.warn(path, "maps are not supported, mapped to a list of key value structs".to_string());
+                        format!("ARRAY<STRUCT<key {}, value {}>>", key_type, value_type)
+                    }
+                    _ => format!("MAP<{}, {}>", key_type, value_type),
+                };
+            }
+        };
+        name.to_string()
+    }
+}
+
+/// The CREATE TABLE statement of the fields, and the warnings about the lossy mappings.
+pub fn create_table(dialect: Dialect, table: &str, fields: &[FieldDef]) -> (String, Vec<String>) {
+    let mut mapper = Mapper { dialect, warnings: vec![] };
+    let mut columns = vec![];
+    for field in fields {
+        let sql_type = mapper.sql_type(&field.name, &field.field_type);
+        let is_array = dialect == Dialect::Bigquery && sql_type.starts_with("ARRAY<");
+        let mut column = format!("  {} {}", dialect.quote(&field.name), sql_type);
+        match (field.nullable, dialect) {
+            // Hive only has informational constraints
+            (_, Dialect::Hive) => {}
+            // BigQuery arrays are never null
+            (true, _) if is_array => {
+                mapper.warn(&field.name, "arrays can not be null, null lists are loaded as empty arrays".to_string());
+            }
+            (false, _) if is_array => {}
+            (false, _) => column.push_str(" NOT NULL"),
+            (true, _) => {}
+        }
+        columns.push(column);
+    }
+
+    let create = match dialect {
+        Dialect::Hive => "CREATE EXTERNAL TABLE",
+        _ => "CREATE TABLE",
+    };
+    let mut statement = format!("{} {} (\n{}\n)", create, dialect.quote(table), columns.join(",\n"));
+    match dialect {
+        Dialect::Hive => statement.push_str("\nSTORED AS PARQUET"),
+        Dialect::Spark => statement.push_str("\nUSING parquet"),
+        _ => {}
+    }
+    statement.push(';');
+    (statement, mapper.warnings)
+}
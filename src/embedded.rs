@@ -0,0 +1,467 @@
+use std::collections::{BTreeMap, HashMap};
+
+use arrow::datatypes::{DataType, Field, Schema};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use parquet::arrow::parquet_to_arrow_schema;
+use parquet::file::metadata::FileMetaData;
+use serde::{Deserialize, Serialize};
+
+/// The key of the Arrow schema written by the Arrow writers, as base64 encoded IPC.
+pub const ARROW_SCHEMA_KEY: &str = "ARROW:schema";
+/// The key of the JSON metadata pandas writes through pyarrow.
+pub const PANDAS_KEY: &str = "pandas";
+/// The key of the Spark StructType, as JSON.
+pub const SPARK_KEY: &str = "org.apache.spark.sql.parquet.row.metadata";
+
+const EXTENSION_NAME_KEY: &str = "ARROW:extension:name";
+const EXTENSION_METADATA_KEY: &str = "ARROW:extension:metadata";
+
+#[derive(Serialize, Debug)]
+pub struct ArrowField {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extension: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extension_metadata: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ArrowField>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ArrowSchema {
+    pub fields: Vec<ArrowField>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PandasColumn {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub field_name: Option<String>,
+    #[serde(default)]
+    pub pandas_type: String,
+    #[serde(default)]
+    pub numpy_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PandasMetadata {
+    /// Names of the columns holding the index, or the description of range indexes.
+    pub index_columns: Vec<String>,
+    pub columns: Vec<PandasColumn>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pandas_version: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SparkField {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SparkSchema {
+    pub fields: Vec<SparkField>,
+}
+
+/// The schemas other writers store in the key value metadata, and how they disagree with the
+/// parquet schema.
+#[derive(Serialize, Debug, Default)]
+pub struct EmbeddedSchemas {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arrow: Option<ArrowSchema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pandas: Option<PandasMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spark: Option<SparkSchema>,
+    /// Known keys whose value can not be decoded.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+    pub disagreements: Vec<String>,
+}
+
+impl EmbeddedSchemas {
+    pub fn is_empty(&self) -> bool {
+        self.arrow.is_none() && self.pandas.is_none() && self.spark.is_none() && self.errors.is_empty()
+    }
+}
+
+fn decode_arrow_schema(encoded: &str) -> Result<Schema, String> {
+    let bytes = BASE64_STANDARD.decode(encoded).map_err(|e| e.to_string())?;
+    // the IPC message may be prefixed by a continuation marker and its length
+    let message = match bytes.len() > 8 && bytes[..4] == [255u8; 4] {
+        true => &bytes[8..],
+        false => &bytes[..],
+    };
+    let message = arrow::ipc::root_as_message(message).map_err(|e| e.to_string())?;
+    let schema = message.header_as_schema().ok_or("the IPC message is not a schema")?;
+    Ok(arrow::ipc::convert::fb_to_schema(schema))
+}
+
+fn arrow_field(field: &Field) -> ArrowField {
+    let children = match field.data_type() {
+        DataType::Struct(fields) => fields.iter().map(|f| arrow_field(f)).collect(),
+        DataType::List(child)
+        | DataType::LargeList(child)
+        | DataType::FixedSizeList(child, _)
+        | DataType::Map(child, _) => vec![arrow_field(child)],
+        _ => vec![],
+    };
+    ArrowField {
+        name: field.name().to_string(),
+        data_type: field.data_type().to_string(),
+        nullable: field.is_nullable(),
+        timezone: timezone(field.data_type()).map(str::to_string),
+        extension: field.metadata().get(EXTENSION_NAME_KEY).cloned(),
+        extension_metadata: field.metadata().get(EXTENSION_METADATA_KEY).cloned(),
+        children,
+    }
+}
+
+fn timezone(data_type: &DataType) -> Option<&str> {
+    match data_type {
+        DataType::Timestamp(_, timezone) => timezone.as_deref(),
+        DataType::Dictionary(_, value) => timezone(value),
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+struct RawPandasMetadata {
+    #[serde(default)]
+    index_columns: Vec<serde_json::Value>,
+    #[serde(default)]
+    columns: Vec<PandasColumn>,
+    #[serde(default)]
+    creator: Option<serde_json::Value>,
+    #[serde(default)]
+    pandas_version: Option<String>,
+}
+
+fn decode_pandas_metadata(json: &str) -> Result<PandasMetadata, String> {
+    let raw: RawPandasMetadata = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let index_columns = raw
+        .index_columns
+        .iter()
+        .map(|index| match index {
+            serde_json::Value::String(name) => name.to_string(),
+            // range indexes are not stored as columns
+            range => format!(
+                "range({}, {}, {})",
+                range["start"], range["stop"], range["step"]
+            ),
+        })
+        .collect();
+    let creator = raw.creator.map(|creator| match (creator["library"].as_str(), creator["version"].as_str()) {
+        (Some(library), Some(version)) => format!("{} {}", library, version),
+        _ => creator.to_string(),
+    });
+    Ok(PandasMetadata { index_columns, columns: raw.columns, creator, pandas_version: raw.pandas_version })
+}
+
+// renders a Spark data type the way Spark prints its simple strings
+fn spark_type(data_type: &serde_json::Value) -> String {
+    if let Some(name) = data_type.as_str() {
+        return name.to_string();
+    }
+    match data_type["type"].as_str() {
+        Some("struct") => format!(
+            "struct<{}>",
+            data_type["fields"]
+                .as_array()
+                .map(|fields| {
+                    fields
+                        .iter()
+                        .map(|f| format!("{}:{}", f["name"].as_str().unwrap_or_default(), spark_type(&f["type"])))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .unwrap_or_default()
+        ),
+        Some("array") => format!("array<{}>", spark_type(&data_type["elementType"])),
+        Some("map") => format!(
+            "map<{},{}>",
+            spark_type(&data_type["keyType"]),
+            spark_type(&data_type["valueType"])
+        ),
+        Some("udt") => data_type["class"].as_str().unwrap_or("udt").to_string(),
+        _ => data_type.to_string(),
+    }
+}
+
+fn decode_spark_schema(json: &str) -> Result<SparkSchema, String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let fields = value["fields"].as_array().ok_or("the value is not a Spark StructType")?;
+    let fields = fields
+        .iter()
+        .map(|field| SparkField {
+            name: field["name"].as_str().unwrap_or_default().to_string(),
+            data_type: spark_type(&field["type"]),
+            nullable: field["nullable"].as_bool().unwrap_or(true),
+            metadata: field["metadata"].as_object().cloned().unwrap_or_default(),
+        })
+        .collect();
+    Ok(SparkSchema { fields })
+}
+
+// broad kinds of types, telling apart values which can not be converted to each other
+fn arrow_kind(data_type: &DataType) -> &'static str {
+    match data_type {
+        DataType::Boolean => "boolean",
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => "integer",
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => "floating point",
+        DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => "decimal",
+        DataType::Utf8 | DataType::LargeUtf8 => "string",
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => "binary",
+        DataType::Date32 | DataType::Date64 => "date",
+        DataType::Time32(_) | DataType::Time64(_) => "time",
+        DataType::Timestamp(_, _) => "timestamp",
+        DataType::Duration(_) | DataType::Interval(_) => "interval",
+        DataType::List(_) | DataType::LargeList(_) | DataType::FixedSizeList(_, _) => "list",
+        DataType::Struct(_) => "struct",
+        DataType::Map(_, _) => "map",
+        DataType::Dictionary(_, value) => arrow_kind(value),
+        _ => "other",
+    }
+}
+
+fn spark_kind(data_type: &str) -> &'static str {
+    match data_type.split(['(', '<']).next().unwrap_or_default() {
+        "boolean" => "boolean",
+        "byte" | "short" | "integer" | "long" => "integer",
+        "float" | "double" => "floating point",
+        "decimal" => "decimal",
+        "string" | "varchar" | "char" => "string",
+        "binary" => "binary",
+        "date" => "date",
+        "timestamp" | "timestamp_ntz" => "timestamp",
+        "array" => "list",
+        "struct" => "struct",
+        "map" => "map",
+        _ => "other",
+    }
+}
+
+// compares the fields of an embedded schema to the top level parquet fields, as Arrow fields
+fn compare_fields(
+    source: &str,
+    fields: &[(String, &'static str, bool)],
+    parquet: &HashMap<&str, &Field>,
+    disagreements: &mut Vec<String>,
+) {
+    for (name, kind, nullable) in fields {
+        let field = match parquet.get(name.as_str()) {
+            Some(field) => field,
+            None => {
+                disagreements.push(format!("{} has field {}, the parquet schema does not", source, name));
+                continue;
+            }
+        };
+        let parquet_kind = arrow_kind(field.data_type());
+        if *kind != "other" && parquet_kind != "other" && *kind != parquet_kind {
+            disagreements.push(format!(
+                "field {} is a {} in {}, a {} in the parquet schema",
+                name, kind, source, parquet_kind
+            ));
+        }
+        if *nullable != field.is_nullable() {
+            disagreements.push(format!(
+                "field {} is {} in {}, {} in the parquet schema",
+                name,
+                if *nullable { "nullable" } else { "not nullable" },
+                source,
+                if field.is_nullable() { "optional" } else { "required" }
+            ));
+        }
+    }
+    for name in parquet.keys() {
+        if !fields.iter().any(|(n, _, _)| n == name) {
+            disagreements.push(format!("the parquet schema has field {}, {} does not", name, source));
+        }
+    }
+}
+
+fn compare_schemas(embedded: &mut EmbeddedSchemas, arrow: Option<&Schema>, metadata: &FileMetaData) {
+    // the parquet schema as Arrow converts it without hints
+    let parquet_schema = match parquet_to_arrow_schema(metadata.schema_descr(), None) {
+        Ok(schema) => schema,
+        Err(e) => {
+            let message = format!("the parquet schema can not be converted to compare it: {}", e);
+            embedded.disagreements.push(message);
+            return;
+        }
+    };
+    let parquet_fields = parquet_schema
+        .fields()
+        .iter()
+        .map(|f| (f.name().as_str(), f.as_ref()))
+        .collect::<HashMap<_, _>>();
+    let mut disagreements = vec![];
+
+    if let Some(arrow) = arrow {
+        let fields = arrow
+            .fields()
+            .iter()
+            .map(|f| (f.name().to_string(), arrow_kind(f.data_type()), f.is_nullable()))
+            .collect::<Vec<_>>();
+        compare_fields(ARROW_SCHEMA_KEY, &fields, &parquet_fields, &mut disagreements);
+        // the timezone is only a hint, parquet stores whether timestamps are adjusted to UTC
+        for field in arrow.fields() {
+            let parquet_field = match parquet_fields.get(field.name().as_str()) {
+                Some(parquet_field) => parquet_field,
+                None => continue,
+            };
+            if let (DataType::Timestamp(_, tz), DataType::Timestamp(_, parquet_tz)) =
+                (field.data_type(), parquet_field.data_type())
+            {
+                if tz.is_some() != parquet_tz.is_some() {
+                    disagreements.push(format!(
+                        "field {} has {} in {}, the parquet timestamp is {}adjusted to UTC",
+                        field.name(),
+                        tz.as_ref().map_or("no timezone".to_string(), |tz| format!("timezone {}", tz)),
+                        ARROW_SCHEMA_KEY,
+                        if parquet_tz.is_some() { "" } else { "not " }
+                    ));
+                }
+            }
+        }
+    }
+
+    if let Some(spark) = &embedded.spark {
+        let fields = spark
+            .fields
+            .iter()
+            .map(|f| (f.name.to_string(), spark_kind(&f.data_type), f.nullable))
+            .collect::<Vec<_>>();
+        compare_fields(SPARK_KEY, &fields, &parquet_fields, &mut disagreements);
+    }
+
+    if let Some(pandas) = &embedded.pandas {
+        for column in &pandas.columns {
+            let name = column.field_name.as_ref().or(column.name.as_ref());
+            if let Some(name) = name.filter(|name| !parquet_fields.contains_key(name.as_str())) {
+                disagreements.push(format!("{} has column {}, the parquet schema does not", PANDAS_KEY, name));
+            }
+        }
+    }
+
+    disagreements.sort();
+    embedded.disagreements = disagreements;
+}
+
+/// Decodes the schemas stored by Arrow, pandas and Spark in the key value metadata.
+pub fn decode_embedded_schemas(metadata: &FileMetaData) -> EmbeddedSchemas {
+    let mut embedded = EmbeddedSchemas::default();
+    let mut arrow = None;
+    for kv in metadata.key_value_metadata().into_iter().flatten() {
+        let value = match &kv.value {
+            Some(value) => value,
+            None => continue,
+        };
+        let error = match kv.key.as_str() {
+            ARROW_SCHEMA_KEY => match decode_arrow_schema(value) {
+                Ok(schema) => {
+                    embedded.arrow = Some(ArrowSchema {
+                        fields: schema.fields().iter().map(|f| arrow_field(f)).collect(),
+                        metadata: schema.metadata().clone().into_iter().collect(),
+                    });
+                    arrow = Some(schema);
+                    None
+                }
+                Err(e) => Some(e),
+            },
+            PANDAS_KEY => decode_pandas_metadata(value).map(|p| embedded.pandas = Some(p)).err(),
+            SPARK_KEY => decode_spark_schema(value).map(|s| embedded.spark = Some(s)).err(),
+            _ => None,
+        };
+        if let Some(error) = error {
+            embedded.errors.push(format!("{} can not be decoded: {}", kv.key, error));
+        }
+    }
+
+    if !embedded.is_empty() {
+        compare_schemas(&mut embedded, arrow.as_ref(), metadata);
+    }
+    embedded
+}
+
+/// Prints the embedded schemas, one field per line.
+pub fn print_embedded_schemas(embedded: &EmbeddedSchemas) {
+    fn print_arrow_field(field: &ArrowField, depth: usize) {
+        let mut details = vec![field.data_type.to_string()];
+        if !field.nullable {
+            details.push("not null".to_string());
+        }
+        if let Some(extension) = &field.extension {
+            details.push(format!("extension {}", extension));
+        }
+        println!("{}{}: {}", "  ".repeat(depth), field.name, details.join(", "));
+        for child in &field.children {
+            print_arrow_field(child, depth + 1);
+        }
+    }
+
+    if let Some(arrow) = &embedded.arrow {
+        println!("{}:", ARROW_SCHEMA_KEY);
+        for field in &arrow.fields {
+            print_arrow_field(field, 1);
+        }
+    }
+    if let Some(pandas) = &embedded.pandas {
+        let mut created_by = pandas.creator.iter().cloned().collect::<Vec<_>>();
+        created_by.extend(pandas.pandas_version.iter().map(|v| format!("pandas {}", v)));
+        match created_by.is_empty() {
+            true => println!("{}:", PANDAS_KEY),
+            false => println!("{} (written by {}):", PANDAS_KEY, created_by.join(", ")),
+        }
+        println!("  index: {}", pandas.index_columns.join(", "));
+        for column in &pandas.columns {
+            let name = column.name.as_deref().unwrap_or("None");
+            let field_name = column.field_name.as_deref().unwrap_or(name);
+            let stored = match field_name == name {
+                true => String::new(),
+                false => format!(" stored as {}", field_name),
+            };
+            println!("  {}{}: {} ({})", name, stored, column.pandas_type, column.numpy_type);
+        }
+    }
+    if let Some(spark) = &embedded.spark {
+        println!("{}:", SPARK_KEY);
+        for field in &spark.fields {
+            let nullable = if field.nullable { "" } else { ", not null" };
+            println!("  {}: {}{}", field.name, field.data_type, nullable);
+        }
+    }
+    for error in &embedded.errors {
+        println!("{}", error);
+    }
+    match embedded.disagreements.is_empty() {
+        true => println!("The embedded schemas agree with the parquet schema"),
+        false => {
+            println!("Disagreements with the parquet schema:");
+            for disagreement in &embedded.disagreements {
+                println!("  {}", disagreement);
+            }
+        }
+    }
+}
@@ -32,5 +32,43 @@ pub enum PQRSError {
     #[error("Could not create string from UTF8 bytes")]
     UTF8ConvertError(#[from] FromUtf8Error),
     #[error("Could not read/write to buffer")]
-    BufferWriteError(#[from] IntoInnerError<BufWriter<Vec<u8>>>)
+    BufferWriteError(#[from] IntoInnerError<BufWriter<Vec<u8>>>),
+    #[error("Invalid timestamp format: {0}")]
+    InvalidTimestampFormat(String),
+    #[error("Unknown timezone: {0}")]
+    UnknownTimezone(String),
+    #[error("Invalid row list: {0}")]
+    InvalidRowList(String),
+    #[error("Row {0} is out of range, the file has {1} rows")]
+    RowOutOfRange(usize, usize),
+    #[error("Unknown column: {0}")]
+    UnknownColumn(String),
+    #[error("Sample fraction must be greater than 0 and at most 1, got {0}")]
+    InvalidSampleFraction(f64),
+    #[error("More than {0} distinct values to stratify by")]
+    TooManyStrata(usize),
+    #[error("Value {1} is not valid for column {0}")]
+    InvalidColumnValue(String, String),
+    #[error("Invalid footer: {0}")]
+    InvalidFooter(String),
+    #[error("Could not decode thrift structure")]
+    ThriftError(#[from] thrift::Error),
+    #[error("{0} files failed verification")]
+    VerificationFailed(usize),
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(String),
+    #[error("Unknown compression codec: {0}")]
+    UnknownCompression(String),
+    #[error("Unknown lint rule or setting: {0}")]
+    UnknownLintRule(String),
+    #[error("{0} findings are at least of {1} severity")]
+    LintFailed(usize, String),
+    #[error("Invalid key value metadata: {0}")]
+    InvalidKeyValue(String),
+    #[error("Key {0} is not in the key value metadata")]
+    UnknownMetadataKey(String),
+    #[error("Invalid GeoParquet metadata: {0}")]
+    InvalidGeoMetadata(String),
+    #[error("Invalid generator: {0}")]
+    InvalidGenerator(String),
 }
\ No newline at end of file
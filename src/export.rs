@@ -0,0 +1,427 @@
+use serde_json::{json, Map, Value};
+
+use crate::fields::{FieldDef, FieldType, Unit};
+
+/// The URI of the JSON Schema draft the exported schemas follow.
+pub const JSON_SCHEMA_DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+// the name with the characters Avro and protobuf names do not allow replaced
+fn identifier(name: &str) -> String {
+    let mut identifier = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect::<String>();
+    if !identifier.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        identifier.insert(0, '_');
+    }
+    identifier
+}
+
+// the name of a protobuf message, in upper camel case
+fn message_name(name: &str) -> String {
+    identifier(name)
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars).collect::<String>()
+        })
+        .collect::<String>()
+}
+
+/// The Avro schema of the fields, a record named after the table.
+pub fn avro_schema(name: &str, fields: &[FieldDef]) -> Value {
+    avro_record(&identifier(name), fields)
+}
+
+fn avro_record(name: &str, fields: &[FieldDef]) -> Value {
+    let fields = fields
+        .iter()
+        .map(|f| {
+            let mut field = Map::new();
+            field.insert("name".to_string(), json!(identifier(&f.name)));
+            field.insert("type".to_string(), avro_field_type(&format!("{}_{}", name, identifier(&f.name)), f));
+            if f.nullable {
+                field.insert("default".to_string(), Value::Null);
+            }
+            Value::Object(field)
+        })
+        .collect::<Vec<_>>();
+    json!({"type": "record", "name": name, "fields": fields})
+}
+
+// the type of a field, a union with null when it is nullable
+fn avro_field_type(name: &str, field: &FieldDef) -> Value {
+    let avro_type = avro_type(name, &field.field_type);
+    match field.nullable {
+        true => json!(["null", avro_type]),
+        false => avro_type,
+    }
+}
+
+// named types, records and fixed, are named after their path to keep the names unique
+fn avro_type(name: &str, field_type: &FieldType) -> Value {
+    let logical = |base: &str, logical_type: &str| json!({"type": base, "logicalType": logical_type});
+    match field_type {
+        FieldType::Boolean => json!("boolean"),
+        FieldType::Integer { bits, signed } if *bits < 32 || (*bits == 32 && *signed) => json!("int"),
+        FieldType::Integer { .. } => json!("long"),
+        FieldType::Float => json!("float"),
+        FieldType::Double => json!("double"),
+        FieldType::Decimal { precision, scale } => {
+            json!({"type": "bytes", "logicalType": "decimal", "precision": precision, "scale": scale})
+        }
+        FieldType::String | FieldType::Enum | FieldType::Json => json!("string"),
+        FieldType::Uuid => logical("string", "uuid"),
+        FieldType::Bson | FieldType::Binary => json!("bytes"),
+        FieldType::FixedBinary(size) => json!({"type": "fixed", "name": name, "size": size}),
+        FieldType::Date => logical("int", "date"),
+        FieldType::Time { unit: Unit::Millis, .. } => logical("int", "time-millis"),
+        FieldType::Time { unit: Unit::Micros, .. } => logical("long", "time-micros"),
+        // Avro has no time of day in nanoseconds
+        FieldType::Time { unit: Unit::Nanos, .. } => json!("long"),
+        FieldType::Timestamp { unit, utc } => {
+            let unit = match unit {
+                Unit::Millis => "millis",
+                Unit::Micros => "micros",
+                Unit::Nanos => "nanos",
+            };
+            match utc {
+                true => logical("long", &format!("timestamp-{}", unit)),
+                false => logical("long", &format!("local-timestamp-{}", unit)),
+            }
+        }
+        FieldType::Interval => json!({"type": "fixed", "name": name, "size": 12, "logicalType": "duration"}),
+        FieldType::Struct(fields) => avro_record(name, fields),
+        FieldType::List(element) => json!({"type": "array", "items": avro_field_type(&format!("{}_element", name), element)}),
+        // Avro map keys are strings, other keys make an array of entries
+        FieldType::Map(key, value) if key.field_type == FieldType::String => {
+            json!({"type": "map", "values": avro_field_type(&format!("{}_value", name), value)})
+        }
+        FieldType::Map(key, value) => {
+            let fields = [
+                FieldDef { name: "key".to_string(), ..*key.clone() },
+                FieldDef { name: "value".to_string(), ..*value.clone() },
+            ];
+            json!({"type": "array", "items": avro_record(&format!("{}_entry", name), &fields)})
+        }
+    }
+}
+
+/// The JSON Schema of the rows of the fields, as `cat --json` prints them. The keys of JSON
+/// objects have no order, so each object also lists its properties in field order under
+/// `propertyOrder`.
+pub fn json_schema(name: &str, fields: &[FieldDef]) -> Value {
+    let mut schema = match json_object(fields) {
+        Value::Object(schema) => schema,
+        _ => unreachable!(),
+    };
+    let mut root = Map::new();
+    root.insert("$schema".to_string(), json!(JSON_SCHEMA_DRAFT));
+    root.insert("title".to_string(), json!(name));
+    root.append(&mut schema);
+    Value::Object(root)
+}
+
+fn json_object(fields: &[FieldDef]) -> Value {
+    let properties = fields.iter().map(|f| (f.name.to_string(), json_field(f))).collect::<Map<_, _>>();
+    let order = fields.iter().map(|f| json!(f.name)).collect::<Vec<_>>();
+    let required = fields.iter().filter(|f| !f.nullable).map(|f| json!(f.name)).collect::<Vec<_>>();
+    let mut object = Map::new();
+    object.insert("type".to_string(), json!("object"));
+    object.insert("properties".to_string(), Value::Object(properties));
+    object.insert("propertyOrder".to_string(), Value::Array(order));
+    if !required.is_empty() {
+        object.insert("required".to_string(), Value::Array(required));
+    }
+    object.insert("additionalProperties".to_string(), json!(false));
+    Value::Object(object)
+}
+
+// the schema of a field, whose type also allows null when it is nullable
+fn json_field(field: &FieldDef) -> Value {
+    let mut schema = json_type(&field.field_type);
+    if field.nullable {
+        if let Some(Value::String(json_type)) = schema.get("type").cloned() {
+            schema["type"] = json!([json_type, "null"]);
+        }
+    }
+    schema
+}
+
+fn json_type(field_type: &FieldType) -> Value {
+    let string = |format: &str| json!({"type": "string", "format": format});
+    match field_type {
+        FieldType::Boolean => json!({"type": "boolean"}),
+        FieldType::Integer { bits, signed } => {
+            let (minimum, maximum) = match (bits, signed) {
+                (8, true) => (json!(i8::MIN), json!(i8::MAX)),
+                (16, true) => (json!(i16::MIN), json!(i16::MAX)),
+                (32, true) => (json!(i32::MIN), json!(i32::MAX)),
+                (_, true) => (json!(i64::MIN), json!(i64::MAX)),
+                (8, false) => (json!(0), json!(u8::MAX)),
+                (16, false) => (json!(0), json!(u16::MAX)),
+                (32, false) => (json!(0), json!(u32::MAX)),
+                (_, false) => (json!(0), json!(u64::MAX)),
+            };
+            json!({"type": "integer", "minimum": minimum, "maximum": maximum})
+        }
+        FieldType::Float | FieldType::Double => json!({"type": "number"}),
+        // decimals are printed as strings, to keep their digits
+        FieldType::Decimal { precision, scale } => {
+            let pattern = match (precision - scale, scale) {
+                (integer, 0) => format!("^-?\\d{{1,{}}}$", integer.max(1)),
+                (integer, scale) => format!("^-?\\d{{1,{}}}(\\.\\d{{1,{}}})?$", integer.max(1), scale),
+            };
+            json!({"type": "string", "pattern": pattern})
+        }
+        FieldType::String | FieldType::Enum => json!({"type": "string"}),
+        FieldType::Json => json!({"type": "string", "contentMediaType": "application/json"}),
+        FieldType::Uuid => string("uuid"),
+        FieldType::Bson | FieldType::Binary | FieldType::FixedBinary(_) | FieldType::Interval => {
+            json!({"type": "string", "contentEncoding": "base64"})
+        }
+        FieldType::Date => string("date"),
+        FieldType::Time { .. } => json!({"type": "string", "pattern": "^\\d{2}:\\d{2}:\\d{2}(\\.\\d+)?$"}),
+        FieldType::Timestamp { .. } => {
+            json!({"type": "string", "pattern": "^-?\\d{4,}-\\d{2}-\\d{2} \\d{2}:\\d{2}:\\d{2} [+-]\\d{2}:\\d{2}$"})
+        }
+        FieldType::Struct(fields) => json_object(fields),
+        FieldType::List(element) => json!({"type": "array", "items": json_field(element)}),
+        // the keys are printed as strings whatever their type
+        FieldType::Map(_, value) => json!({"type": "object", "additionalProperties": json_field(value)}),
+    }
+}
+
+/// The proto3 definition of a message of the fields, named after the table.
+pub fn proto_schema(name: &str, fields: &[FieldDef]) -> String {
+    let mut imports = vec![];
+    let message = proto_message(&message_name(name), fields, 0, &mut imports);
+    let mut output = "syntax = \"proto3\";\n\n".to_string();
+    for import in &imports {
+        output.push_str(&format!("import \"{}\";\n", import));
+    }
+    if !imports.is_empty() {
+        output.push('\n');
+    }
+    output.push_str(&message);
+    output
+}
+
+fn proto_message(name: &str, fields: &[FieldDef], depth: usize, imports: &mut Vec<String>) -> String {
+    let indent = "  ".repeat(depth);
+    // the field ids of the schema when all fields have one
+    let ids = fields.iter().map(|f| f.id).collect::<Option<Vec<_>>>();
+    let mut nested = vec![];
+    let mut lines = vec![];
+    for (i, field) in fields.iter().enumerate() {
+        let number = ids.as_ref().map_or(i as i32 + 1, |ids| ids[i]);
+        let (label, proto_type, comment) = proto_field(field, &mut nested, depth + 1, imports);
+        let comment = comment.map(|c| format!(" // {}", c)).unwrap_or_default();
+        lines.push(format!("{}  {}{} {} = {};{}", indent, label, proto_type, identifier(&field.name), number, comment));
+    }
+
+    let mut message = format!("{}message {} {{\n", indent, name);
+    for definition in nested {
+        message.push_str(&definition);
+        message.push('\n');
+    }
+    for line in lines {
+        message.push_str(&line);
+        message.push('\n');
+    }
+    message.push_str(&format!("{}}}\n", indent));
+    message
+}
+
+// the label, type and comment of a field, the messages its type needs are added to nested
+fn proto_field(
+    field: &FieldDef,
+    nested: &mut Vec<String>,
+    depth: usize,
+    imports: &mut Vec<String>,
+) -> (&'static str, String, Option<String>) {
+    let name = message_name(&field.name);
+    match &field.field_type {
+        // repeated fields can not hold repeated values or maps, these are wrapped in a message
+        FieldType::List(element) if matches!(element.field_type, FieldType::List(_) | FieldType::Map(_, _)) => {
+            let wrapper = format!("{}Element", name);
+            nested.push(proto_message(&wrapper, &[FieldDef { nullable: false, ..*element.clone() }], depth, imports));
+            ("repeated ", wrapper, None)
+        }
+        FieldType::List(element) => {
+            let (proto_type, comment) = proto_type(&name, &element.field_type, nested, depth, imports);
+            ("repeated ", proto_type, comment)
+        }
+        FieldType::Map(key, value) => {
+            let valid_key = matches!(key.field_type, FieldType::Integer { .. } | FieldType::String | FieldType::Boolean);
+            if valid_key && !matches!(value.field_type, FieldType::List(_) | FieldType::Map(_, _)) {
+                let (key_type, _) = proto_type(&name, &key.field_type, nested, depth, imports);
+                let (value_type, comment) = proto_type(&format!("{}Value", name), &value.field_type, nested, depth, imports);
+                return ("", format!("map<{}, {}>", key_type, value_type), comment);
+            }
+            let entry = format!("{}Entry", name);
+            let fields = [
+                FieldDef { name: "key".to_string(), nullable: false, ..*key.clone() },
+                FieldDef { name: "value".to_string(), ..*value.clone() },
+            ];
+            nested.push(proto_message(&entry, &fields, depth, imports));
+            ("repeated ", entry, None)
+        }
+        field_type => {
+            let (proto_type, comment) = proto_type(&name, field_type, nested, depth, imports);
+            // explicit presence for nullable scalars, messages always have it
+            let label = match field.nullable && !matches!(field_type, FieldType::Struct(_)) {
+                true => "optional ",
+                false => "",
+            };
+            (label, proto_type, comment)
+        }
+    }
+}
+
+fn proto_type(
+    name: &str,
+    field_type: &FieldType,
+    nested: &mut Vec<String>,
+    depth: usize,
+    imports: &mut Vec<String>,
+) -> (String, Option<String>) {
+    let scalar = |proto_type: &str| (proto_type.to_string(), None);
+    let commented = |proto_type: &str, comment: String| (proto_type.to_string(), Some(comment));
+    match field_type {
+        FieldType::Boolean => scalar("bool"),
+        FieldType::Integer { bits: 64, signed: true } => scalar("int64"),
+        FieldType::Integer { bits: 64, signed: false } => scalar("uint64"),
+        FieldType::Integer { signed: true, .. } => scalar("int32"),
+        FieldType::Integer { signed: false, .. } => scalar("uint32"),
+        FieldType::Float => scalar("float"),
+        FieldType::Double => scalar("double"),
+        FieldType::Decimal { precision, scale } => commented("string", format!("DECIMAL({}, {})", precision, scale)),
+        FieldType::String | FieldType::Enum => scalar("string"),
+        FieldType::Json => commented("string", "JSON".to_string()),
+        FieldType::Uuid => commented("bytes", "UUID".to_string()),
+        FieldType::Bson | FieldType::Binary | FieldType::FixedBinary(_) => scalar("bytes"),
+        FieldType::Interval => commented("bytes", "INTERVAL, months, days and milliseconds".to_string()),
+        FieldType::Date => commented("int32", "days since the epoch".to_string()),
+        FieldType::Time { unit, .. } => commented("int64", format!("{} since midnight", unit.name())),
+        FieldType::Timestamp { utc: true, .. } => {
+            let import = "google/protobuf/timestamp.proto".to_string();
+            if !imports.contains(&import) {
+                imports.push(import);
+            }
+            scalar("google.protobuf.Timestamp")
+        }
+        FieldType::Timestamp { unit, utc: false } => {
+            commented("int64", format!("{} since the epoch, local time", unit.name()))
+        }
+        FieldType::Struct(fields) => {
+            nested.push(proto_message(name, fields, depth, imports));
+            scalar(name)
+        }
+        // only reached for the keys and values of maps, which can not be repeated
+        FieldType::List(_) | FieldType::Map(_, _) => commented("bytes", "nested collection".to_string()),
+    }
+}
+
+/// The fields of the object a JSON Schema describes, reading back the schemas `json_schema`
+/// writes: integer bounds give the width, string patterns and formats the logical types.
+pub fn json_schema_fields(schema: &Value) -> Result<Vec<FieldDef>, String> {
+    match json_schema_type(schema)? {
+        (FieldType::Struct(fields), _) => Ok(fields),
+        _ => Err("the schema does not describe objects".to_string()),
+    }
+}
+
+// the type a schema describes and whether it allows null
+fn json_schema_type(schema: &Value) -> Result<(FieldType, bool), String> {
+    let types = match &schema["type"] {
+        Value::String(name) => vec![name.as_str()],
+        Value::Array(names) => names.iter().filter_map(|n| n.as_str()).collect(),
+        Value::Null => return Err(format!("no type in {}", schema)),
+        other => return Err(format!("invalid type {}", other)),
+    };
+    let nullable = types.contains(&"null");
+    let field_type = match types.iter().find(|t| **t != "null").copied() {
+        Some("boolean") => FieldType::Boolean,
+        Some("integer") => json_schema_integer(schema),
+        Some("number") => FieldType::Double,
+        Some("string") => json_schema_string(schema),
+        Some("array") => {
+            let (element, nullable) = json_schema_type(&schema["items"])?;
+            let element = FieldDef { name: "element".to_string(), nullable, field_type: element, id: None };
+            FieldType::List(Box::new(element))
+        }
+        Some("object") if schema["properties"].is_object() => {
+            let required = schema["required"].as_array().cloned().unwrap_or_default();
+            // the listed properties first in their order, the others after them
+            let order = schema["propertyOrder"].as_array().cloned().unwrap_or_default();
+            let mut properties = schema["properties"].as_object().into_iter().flatten().collect::<Vec<_>>();
+            properties.sort_by_key(|(name, _)| order.iter().position(|n| n == *name).unwrap_or(order.len()));
+            let mut fields = vec![];
+            for (name, property) in properties {
+                let (field_type, nullable) = json_schema_type(property)?;
+                let nullable = nullable || !required.contains(&json!(name));
+                fields.push(FieldDef { name: name.to_string(), nullable, field_type, id: None });
+            }
+            FieldType::Struct(fields)
+        }
+        // objects of any keys are maps of strings
+        Some("object") if schema["additionalProperties"].is_object() => {
+            let (value, nullable) = json_schema_type(&schema["additionalProperties"])?;
+            let key = FieldDef { name: "key".to_string(), nullable: false, field_type: FieldType::String, id: None };
+            let value = FieldDef { name: "value".to_string(), nullable, field_type: value, id: None };
+            FieldType::Map(Box::new(key), Box::new(value))
+        }
+        Some("object") => return Err(format!("objects need properties or additionalProperties: {}", schema)),
+        Some(other) => return Err(format!("unsupported type {}", other)),
+        None => return Err("the type null has no column type".to_string()),
+    };
+    Ok((field_type, nullable))
+}
+
+// the narrowest integer holding the bounds, 64 bits without them
+fn json_schema_integer(schema: &Value) -> FieldType {
+    let (minimum, maximum) = (schema["minimum"].as_f64(), schema["maximum"].as_f64());
+    let signed = minimum.is_none_or(|minimum| minimum < 0.0);
+    let fits = |bits: i32| match (minimum, maximum, signed) {
+        (Some(minimum), Some(maximum), true) => {
+            minimum >= -(2f64.powi(bits - 1)) && maximum <= 2f64.powi(bits - 1) - 1.0
+        }
+        (_, Some(maximum), false) => maximum <= 2f64.powi(bits) - 1.0,
+        _ => false,
+    };
+    let bits = [8, 16, 32].into_iter().find(|bits| fits(*bits)).unwrap_or(64);
+    FieldType::Integer { bits: bits as u8, signed }
+}
+
+fn json_schema_string(schema: &Value) -> FieldType {
+    match (schema["format"].as_str(), schema["contentEncoding"].as_str(), schema["contentMediaType"].as_str()) {
+        (Some("date"), _, _) => return FieldType::Date,
+        (Some("uuid"), _, _) => return FieldType::Uuid,
+        (Some("date-time"), _, _) => return FieldType::Timestamp { unit: Unit::Micros, utc: true },
+        (_, Some("base64"), _) => return FieldType::Binary,
+        (_, _, Some("application/json")) => return FieldType::Json,
+        _ => {}
+    }
+    let pattern = schema["pattern"].as_str().unwrap_or_default();
+    if pattern.starts_with("^-?\\d{4,}-") {
+        return FieldType::Timestamp { unit: Unit::Micros, utc: true };
+    }
+    if pattern.starts_with("^\\d{2}:") {
+        return FieldType::Time { unit: Unit::Micros, utc: true };
+    }
+    // the patterns of decimals, ^-?\d{1,I}$ or ^-?\d{1,I}(\.\d{1,S})?$
+    let digits = pattern
+        .split("\\d{1,")
+        .skip(1)
+        .map(|part| part.split('}').next().and_then(|n| n.parse::<i32>().ok()))
+        .collect::<Option<Vec<_>>>();
+    match digits.as_deref() {
+        Some([integer]) if pattern.starts_with("^-?") => FieldType::Decimal { precision: *integer, scale: 0 },
+        Some([integer, scale]) if pattern.starts_with("^-?") => {
+            FieldType::Decimal { precision: integer + scale, scale: *scale }
+        }
+        _ => FieldType::String,
+    }
+}
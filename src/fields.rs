@@ -0,0 +1,326 @@
+use std::sync::Arc;
+
+use parquet::basic::{ConvertedType, LogicalType, Repetition, Type as PhysicalType};
+use parquet::errors::Result;
+use parquet::format::{MicroSeconds, MilliSeconds, NanoSeconds, TimeUnit};
+use parquet::schema::types::{SchemaDescriptor, Type};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Unit {
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl Unit {
+    fn from_thrift(unit: &TimeUnit) -> Self {
+        match unit {
+            TimeUnit::MILLIS(_) => Unit::Millis,
+            TimeUnit::MICROS(_) => Unit::Micros,
+            TimeUnit::NANOS(_) => Unit::Nanos,
+        }
+    }
+
+    fn to_thrift(self) -> TimeUnit {
+        match self {
+            Unit::Millis => TimeUnit::MILLIS(MilliSeconds {}),
+            Unit::Micros => TimeUnit::MICROS(MicroSeconds {}),
+            Unit::Nanos => TimeUnit::NANOS(NanoSeconds {}),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Unit::Millis => "milliseconds",
+            Unit::Micros => "microseconds",
+            Unit::Nanos => "nanoseconds",
+        }
+    }
+}
+
+/// The type of a field once its physical type, logical or converted type and the list and map
+/// conventions are resolved, what schema exports map to the types of other systems.
+#[derive(Clone, PartialEq, Debug)]
+pub enum FieldType {
+    Boolean,
+    Integer { bits: u8, signed: bool },
+    Float,
+    Double,
+    Decimal { precision: i32, scale: i32 },
+    String,
+    Enum,
+    Json,
+    Bson,
+    Uuid,
+    Binary,
+    FixedBinary(i32),
+    Date,
+    Time { unit: Unit, utc: bool },
+    Timestamp { unit: Unit, utc: bool },
+    /// The INTERVAL converted type, months, days and milliseconds in 12 bytes.
+    Interval,
+    Struct(Vec<FieldDef>),
+    List(Box<FieldDef>),
+    Map(Box<FieldDef>, Box<FieldDef>),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FieldDef {
+    pub name: String,
+    pub nullable: bool,
+    pub field_type: FieldType,
+    /// The field id of the schema, when the writer set one.
+    pub id: Option<i32>,
+}
+
+impl FieldType {
+    pub fn is_nested(&self) -> bool {
+        matches!(self, FieldType::Struct(_) | FieldType::List(_) | FieldType::Map(_, _))
+    }
+}
+
+/// The fields of the top level columns of a schema.
+pub fn schema_fields(schema: &SchemaDescriptor) -> Vec<FieldDef> {
+    schema.root_schema().get_fields().iter().map(|f| field_def(f)).collect()
+}
+
+fn is_list(t: &Type) -> bool {
+    let info = t.get_basic_info();
+    matches!(info.logical_type(), Some(LogicalType::List)) || info.converted_type() == ConvertedType::LIST
+}
+
+fn is_map(t: &Type) -> bool {
+    let info = t.get_basic_info();
+    matches!(info.logical_type(), Some(LogicalType::Map))
+        || matches!(info.converted_type(), ConvertedType::MAP | ConvertedType::MAP_KEY_VALUE)
+}
+
+pub fn field_def(t: &Type) -> FieldDef {
+    let info = t.get_basic_info();
+    let id = info.has_id().then(|| info.id());
+    // a repeated field out of a LIST or MAP annotated group is a required list of required values
+    if info.has_repetition() && info.repetition() == Repetition::REPEATED {
+        let element = FieldDef { name: "element".to_string(), nullable: false, field_type: field_type(t), id: None };
+        return FieldDef {
+            name: t.name().to_string(),
+            nullable: false,
+            field_type: FieldType::List(Box::new(element)),
+            id,
+        };
+    }
+    FieldDef { name: t.name().to_string(), nullable: t.is_optional(), field_type: field_type(t), id }
+}
+
+// the type of a field, ignoring its repetition
+fn field_type(t: &Type) -> FieldType {
+    match t {
+        Type::GroupType { fields, .. } if is_list(t) && fields.len() == 1 => {
+            FieldType::List(Box::new(list_element(t, &fields[0])))
+        }
+        Type::GroupType { fields, .. } if is_map(t) && fields.len() == 1 && fields[0].is_group() => {
+            let key_value = fields[0].get_fields();
+            let key = key_value.first().map(|f| field_def(f));
+            let value = key_value.get(1).map(|f| field_def(f));
+            match (key, value) {
+                (Some(key), Some(value)) => FieldType::Map(Box::new(key), Box::new(value)),
+                // a map without values is a set of keys
+                (Some(key), None) => FieldType::List(Box::new(key)),
+                _ => FieldType::Struct(vec![]),
+            }
+        }
+        Type::GroupType { fields, .. } => FieldType::Struct(fields.iter().map(|f| field_def(f)).collect()),
+        Type::PrimitiveType { .. } => primitive_type(t),
+    }
+}
+
+// the element of a LIST annotated group, following the backward compatibility rules of the format
+fn list_element(list: &Type, repeated: &Type) -> FieldDef {
+    let element = match repeated {
+        Type::PrimitiveType { .. } => None,
+        Type::GroupType { fields, .. } if fields.len() != 1 => None,
+        Type::GroupType { .. } if repeated.name() == "array" => None,
+        Type::GroupType { .. } if repeated.name() == format!("{}_tuple", list.name()) => None,
+        Type::GroupType { fields, .. } => Some(field_def(&fields[0])),
+    };
+    // the repeated field is itself the element
+    element.unwrap_or_else(|| FieldDef {
+        name: repeated.name().to_string(),
+        nullable: false,
+        field_type: field_type(repeated),
+        id: None,
+    })
+}
+
+fn primitive_type(t: &Type) -> FieldType {
+    let info = t.get_basic_info();
+    let physical = t.get_physical_type();
+    let integer = |bits, signed| FieldType::Integer { bits, signed };
+    let from_logical = match info.logical_type() {
+        Some(LogicalType::String) => Some(FieldType::String),
+        Some(LogicalType::Enum) => Some(FieldType::Enum),
+        Some(LogicalType::Json) => Some(FieldType::Json),
+        Some(LogicalType::Bson) => Some(FieldType::Bson),
+        Some(LogicalType::Uuid) => Some(FieldType::Uuid),
+        Some(LogicalType::Decimal { precision, scale }) => Some(FieldType::Decimal { precision, scale }),
+        Some(LogicalType::Date) => Some(FieldType::Date),
+        Some(LogicalType::Time { is_adjusted_to_u_t_c, unit }) => {
+            Some(FieldType::Time { unit: Unit::from_thrift(&unit), utc: is_adjusted_to_u_t_c })
+        }
+        Some(LogicalType::Timestamp { is_adjusted_to_u_t_c, unit }) => {
+            Some(FieldType::Timestamp { unit: Unit::from_thrift(&unit), utc: is_adjusted_to_u_t_c })
+        }
+        Some(LogicalType::Integer { bit_width, is_signed }) => Some(integer(bit_width as u8, is_signed)),
+        _ => None,
+    };
+    if let Some(field_type) = from_logical {
+        return field_type;
+    }
+
+    // the converted types of older writers, whose times and timestamps are adjusted to UTC
+    match info.converted_type() {
+        ConvertedType::UTF8 => return FieldType::String,
+        ConvertedType::ENUM => return FieldType::Enum,
+        ConvertedType::JSON => return FieldType::Json,
+        ConvertedType::BSON => return FieldType::Bson,
+        ConvertedType::DECIMAL => {
+            return FieldType::Decimal { precision: t.get_precision(), scale: t.get_scale() }
+        }
+        ConvertedType::DATE => return FieldType::Date,
+        ConvertedType::TIME_MILLIS => return FieldType::Time { unit: Unit::Millis, utc: true },
+        ConvertedType::TIME_MICROS => return FieldType::Time { unit: Unit::Micros, utc: true },
+        ConvertedType::TIMESTAMP_MILLIS => return FieldType::Timestamp { unit: Unit::Millis, utc: true },
+        ConvertedType::TIMESTAMP_MICROS => return FieldType::Timestamp { unit: Unit::Micros, utc: true },
+        ConvertedType::UINT_8 => return integer(8, false),
+        ConvertedType::UINT_16 => return integer(16, false),
+        ConvertedType::UINT_32 => return integer(32, false),
+        ConvertedType::UINT_64 => return integer(64, false),
+        ConvertedType::INT_8 => return integer(8, true),
+        ConvertedType::INT_16 => return integer(16, true),
+        ConvertedType::INT_32 => return integer(32, true),
+        ConvertedType::INT_64 => return integer(64, true),
+        ConvertedType::INTERVAL => return FieldType::Interval,
+        _ => {}
+    }
+
+    match physical {
+        PhysicalType::BOOLEAN => FieldType::Boolean,
+        PhysicalType::INT32 => integer(32, true),
+        PhysicalType::INT64 => integer(64, true),
+        // INT96 holds the nanosecond timestamps of Hive, Impala and Spark, which store instants
+        PhysicalType::INT96 => FieldType::Timestamp { unit: Unit::Nanos, utc: true },
+        PhysicalType::FLOAT => FieldType::Float,
+        PhysicalType::DOUBLE => FieldType::Double,
+        PhysicalType::BYTE_ARRAY => FieldType::Binary,
+        PhysicalType::FIXED_LEN_BYTE_ARRAY => match t {
+            Type::PrimitiveType { type_length, .. } => FieldType::FixedBinary(*type_length),
+            _ => FieldType::Binary,
+        },
+    }
+}
+
+/// The message type of the fields, written the way the Arrow writer lays out lists and maps.
+pub fn message_type(name: &str, fields: &[FieldDef]) -> Result<Type> {
+    let mut fields = fields.iter().map(|f| parquet_type(f).map(Arc::new)).collect::<Result<Vec<_>>>()?;
+    Type::group_type_builder(name).with_fields(&mut fields).build()
+}
+
+fn parquet_type(field: &FieldDef) -> Result<Type> {
+    let repetition = match field.nullable {
+        true => Repetition::OPTIONAL,
+        false => Repetition::REQUIRED,
+    };
+    let primitive = |physical_type, logical_type: Option<LogicalType>| {
+        let builder = Type::primitive_type_builder(&field.name, physical_type)
+            .with_repetition(repetition)
+            .with_logical_type(logical_type);
+        match field.id {
+            Some(id) => builder.with_id(id),
+            None => builder,
+        }
+    };
+    let group = |logical_type: Option<LogicalType>, mut fields: Vec<Arc<Type>>| {
+        let builder = Type::group_type_builder(&field.name)
+            .with_repetition(repetition)
+            .with_logical_type(logical_type)
+            .with_fields(&mut fields);
+        match field.id {
+            Some(id) => builder.with_id(id),
+            None => builder,
+        }
+        .build()
+    };
+    let primitive = match &field.field_type {
+        FieldType::Boolean => primitive(PhysicalType::BOOLEAN, None),
+        FieldType::Integer { bits, signed } => {
+            let physical_type = match bits {
+                0..=32 => PhysicalType::INT32,
+                _ => PhysicalType::INT64,
+            };
+            // plain INT32 and INT64 are signed integers of their width
+            let logical_type = match (bits, signed) {
+                (32 | 64, true) => None,
+                _ => Some(LogicalType::Integer { bit_width: *bits as i8, is_signed: *signed }),
+            };
+            primitive(physical_type, logical_type)
+        }
+        FieldType::Float => primitive(PhysicalType::FLOAT, None),
+        FieldType::Double => primitive(PhysicalType::DOUBLE, None),
+        FieldType::Decimal { precision, scale } => {
+            let logical_type = Some(LogicalType::Decimal { precision: *precision, scale: *scale });
+            let builder = match precision {
+                0..=9 => primitive(PhysicalType::INT32, logical_type),
+                10..=18 => primitive(PhysicalType::INT64, logical_type),
+                // the bytes of the two's complement of the largest unscaled value
+                _ => {
+                    let length = ((*precision as f64 * 10f64.log2() + 1.0) / 8.0).ceil() as i32;
+                    primitive(PhysicalType::FIXED_LEN_BYTE_ARRAY, logical_type).with_length(length)
+                }
+            };
+            builder.with_precision(*precision).with_scale(*scale)
+        }
+        FieldType::String => primitive(PhysicalType::BYTE_ARRAY, Some(LogicalType::String)),
+        FieldType::Enum => primitive(PhysicalType::BYTE_ARRAY, Some(LogicalType::Enum)),
+        FieldType::Json => primitive(PhysicalType::BYTE_ARRAY, Some(LogicalType::Json)),
+        FieldType::Bson => primitive(PhysicalType::BYTE_ARRAY, Some(LogicalType::Bson)),
+        FieldType::Uuid => primitive(PhysicalType::FIXED_LEN_BYTE_ARRAY, Some(LogicalType::Uuid)).with_length(16),
+        FieldType::Binary => primitive(PhysicalType::BYTE_ARRAY, None),
+        FieldType::FixedBinary(length) => primitive(PhysicalType::FIXED_LEN_BYTE_ARRAY, None).with_length(*length),
+        FieldType::Date => primitive(PhysicalType::INT32, Some(LogicalType::Date)),
+        FieldType::Time { unit, utc } => {
+            let physical_type = match unit {
+                Unit::Millis => PhysicalType::INT32,
+                _ => PhysicalType::INT64,
+            };
+            let logical_type = LogicalType::Time { is_adjusted_to_u_t_c: *utc, unit: unit.to_thrift() };
+            primitive(physical_type, Some(logical_type))
+        }
+        FieldType::Timestamp { unit, utc } => {
+            let logical_type = LogicalType::Timestamp { is_adjusted_to_u_t_c: *utc, unit: unit.to_thrift() };
+            primitive(PhysicalType::INT64, Some(logical_type))
+        }
+        FieldType::Interval => primitive(PhysicalType::FIXED_LEN_BYTE_ARRAY, None)
+            .with_converted_type(ConvertedType::INTERVAL)
+            .with_length(12),
+        FieldType::Struct(fields) => {
+            return group(None, fields.iter().map(|f| parquet_type(f).map(Arc::new)).collect::<Result<_>>()?)
+        }
+        FieldType::List(element) => {
+            let element = FieldDef { name: "element".to_string(), ..*element.clone() };
+            let list = Type::group_type_builder("list")
+                .with_repetition(Repetition::REPEATED)
+                .with_fields(&mut vec![Arc::new(parquet_type(&element)?)])
+                .build()?;
+            return group(Some(LogicalType::List), vec![Arc::new(list)]);
+        }
+        FieldType::Map(key, value) => {
+            let key = FieldDef { name: "key".to_string(), nullable: false, ..*key.clone() };
+            let value = FieldDef { name: "value".to_string(), ..*value.clone() };
+            let key_value = Type::group_type_builder("key_value")
+                .with_repetition(Repetition::REPEATED)
+                .with_fields(&mut vec![Arc::new(parquet_type(&key)?), Arc::new(parquet_type(&value)?)])
+                .build()?;
+            return group(Some(LogicalType::Map), vec![Arc::new(key_value)]);
+        }
+    };
+    primitive.build()
+}
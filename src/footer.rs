@@ -0,0 +1,118 @@
+use std::fs::File;
+use std::io::{Cursor, Write};
+
+use parquet::file::reader::{ChunkReader, Length};
+use parquet::format::{CompressionCodec, FileMetaData};
+use serde::Serialize;
+use thrift::protocol::{TCompactInputProtocol, TCompactOutputProtocol, TOutputProtocol, TSerializable};
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::InvalidFooter;
+
+/// The magic bytes a parquet file starts and ends with.
+pub const MAGIC: &[u8; 4] = b"PAR1";
+/// The metadata length and the trailing magic bytes.
+pub const FOOTER_SIZE: u64 = 8;
+
+/// What the first and last bytes of a file tell about it, before decoding its metadata.
+#[derive(Serialize, Debug)]
+pub struct FooterCheck {
+    /// Size of the file in bytes.
+    pub file_size: u64,
+    /// Whether the file starts with PAR1.
+    pub header_magic: bool,
+    /// Whether the file ends with PAR1.
+    pub footer_magic: bool,
+    /// Length of the thrift encoded file metadata, stored right before the trailing magic.
+    pub metadata_length: Option<u32>,
+    /// Where the file metadata starts, when its length fits between the magic bytes.
+    pub metadata_offset: Option<u64>,
+}
+
+impl FooterCheck {
+    pub fn is_valid(&self) -> bool {
+        self.problems().is_empty()
+    }
+
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+        if !self.header_magic {
+            problems.push(String::from("the file does not start with PAR1"));
+        }
+        if !self.footer_magic {
+            problems.push(String::from("the file does not end with PAR1"));
+        }
+        match (self.metadata_length, self.metadata_offset) {
+            (None, _) => problems.push(String::from("the file is too small to hold a footer")),
+            (Some(length), None) => problems.push(format!(
+                "the metadata length {} does not fit in the {} bytes of the file",
+                length, self.file_size
+            )),
+            _ => {}
+        }
+        problems
+    }
+}
+
+/// Checks the magic bytes and the metadata length of a file.
+pub fn check_footer(file: &File) -> Result<FooterCheck, PQRSError> {
+    let file_size = file.len();
+    let header_magic = file_size >= MAGIC.len() as u64
+        && file.get_bytes(0, MAGIC.len())?.as_ref() == MAGIC;
+
+    let mut footer_magic = false;
+    let mut metadata_length = None;
+    let mut metadata_offset = None;
+    if file_size >= FOOTER_SIZE {
+        let footer = file.get_bytes(file_size - FOOTER_SIZE, FOOTER_SIZE as usize)?;
+        footer_magic = &footer[4..] == MAGIC;
+        let length = u32::from_le_bytes(footer[..4].try_into().unwrap());
+        metadata_length = Some(length);
+        if MAGIC.len() as u64 + length as u64 + FOOTER_SIZE <= file_size {
+            metadata_offset = Some(file_size - FOOTER_SIZE - length as u64);
+        }
+    }
+
+    Ok(FooterCheck { file_size, header_magic, footer_magic, metadata_length, metadata_offset })
+}
+
+/// Decodes the thrift file metadata as stored, once the footer checks passed.
+pub fn read_file_metadata(file: &File, check: &FooterCheck) -> Result<FileMetaData, PQRSError> {
+    let (offset, length) = match (check.metadata_offset, check.metadata_length) {
+        (Some(offset), Some(length)) => (offset, length),
+        _ => return Err(InvalidFooter(check.problems().join(", "))),
+    };
+    let bytes = file.get_bytes(offset, length as usize)?;
+    let mut protocol = TCompactInputProtocol::new(Cursor::new(bytes.as_ref()));
+    Ok(FileMetaData::read_from_in_protocol(&mut protocol)?)
+}
+
+/// Writes the thrift file metadata, its length and the trailing magic bytes.
+pub fn write_footer<W: Write>(mut sink: W, metadata: &FileMetaData) -> Result<W, PQRSError> {
+    let mut buffer = vec![];
+    {
+        let mut protocol = TCompactOutputProtocol::new(&mut buffer);
+        metadata.write_to_out_protocol(&mut protocol)?;
+        protocol.flush()?;
+    }
+    sink.write_all(&buffer)?;
+    sink.write_all(&(buffer.len() as u32).to_le_bytes())?;
+    sink.write_all(MAGIC)?;
+    Ok(sink)
+}
+
+/// The name of a compression codec as stored in the file, `Compression` in parquet::basic
+/// renders the levels of some codecs which are not stored.
+pub fn codec_name(codec: CompressionCodec) -> String {
+    match codec {
+        CompressionCodec::UNCOMPRESSED => String::from("UNCOMPRESSED"),
+        CompressionCodec::SNAPPY => String::from("SNAPPY"),
+        CompressionCodec::GZIP => String::from("GZIP"),
+        CompressionCodec::LZO => String::from("LZO"),
+        CompressionCodec::BROTLI => String::from("BROTLI"),
+        CompressionCodec::LZ4 => String::from("LZ4"),
+        CompressionCodec::ZSTD => String::from("ZSTD"),
+        CompressionCodec::LZ4_RAW => String::from("LZ4_RAW"),
+        _ => format!("UNKNOWN({})", codec.0),
+    }
+}
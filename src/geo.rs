@@ -0,0 +1,584 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use parquet::basic::Type as PhysicalType;
+use parquet::file::metadata::{FileMetaData, RowGroupMetaData};
+use parquet::file::statistics::Statistics;
+use parquet::schema::types::SchemaDescriptor;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::InvalidGeoMetadata;
+
+/// The key of the GeoParquet metadata.
+pub const GEO_KEY: &str = "geo";
+
+const VERSIONS: [&str; 5] = ["0.4.0", "1.0.0-beta.1", "1.0.0", "1.1.0-beta.1", "1.1.0"];
+const GEOMETRY_TYPES: [&str; 7] = [
+    "Point",
+    "LineString",
+    "Polygon",
+    "MultiPoint",
+    "MultiLineString",
+    "MultiPolygon",
+    "GeometryCollection",
+];
+
+// tells an absent key, which has a default value, from an explicit null
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// The columns of a bounding box covering, as the path of each of their leaves.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CoveringBbox {
+    pub xmin: Vec<String>,
+    pub ymin: Vec<String>,
+    pub xmax: Vec<String>,
+    pub ymax: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Covering {
+    pub bbox: Option<CoveringBbox>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GeoColumn {
+    pub encoding: String,
+    #[serde(default)]
+    pub geometry_types: Vec<String>,
+    /// The PROJJSON of the CRS, OGC:CRS84 when absent and unknown when null.
+    #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+    pub crs: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edges: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orientation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub covering: Option<Covering>,
+}
+
+impl GeoColumn {
+    /// The name of the CRS, with its identifier when it has one.
+    pub fn crs_name(&self) -> String {
+        let crs = match &self.crs {
+            None => return "OGC:CRS84 (default)".to_string(),
+            Some(Value::Null) => return "unknown".to_string(),
+            Some(crs) => crs,
+        };
+        let id = match (crs["id"]["authority"].as_str(), &crs["id"]["code"]) {
+            (Some(authority), Value::String(code)) => Some(format!("{}:{}", authority, code)),
+            (Some(authority), Value::Number(code)) => Some(format!("{}:{}", authority, code)),
+            _ => None,
+        };
+        match (crs["name"].as_str(), id) {
+            (Some(name), Some(id)) => format!("{} ({})", name, id),
+            (Some(name), None) => name.to_string(),
+            (None, Some(id)) => id,
+            (None, None) => "PROJJSON without name".to_string(),
+        }
+    }
+
+    pub fn covering_bbox(&self) -> Option<&CoveringBbox> {
+        self.covering.as_ref().and_then(|c| c.bbox.as_ref())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GeoMetadata {
+    pub version: String,
+    pub primary_column: String,
+    pub columns: BTreeMap<String, GeoColumn>,
+}
+
+impl GeoMetadata {
+    /// Checks the metadata against the specification and the parquet schema.
+    pub fn validate(&self, schema: &SchemaDescriptor) -> Vec<String> {
+        let mut problems = vec![];
+        if !VERSIONS.contains(&self.version.as_str()) {
+            problems.push(format!("version {} is not a known GeoParquet version", self.version));
+        }
+        if !self.columns.contains_key(&self.primary_column) {
+            problems.push(format!("the primary column {} is not a geometry column", self.primary_column));
+        }
+
+        for (name, column) in &self.columns {
+            let leaf = schema.columns().iter().find(|c| c.path().parts() == [name.to_string()]);
+            match leaf {
+                None => problems.push(format!("column {} is not a top level column of the file", name)),
+                Some(leaf) if column.encoding == "WKB" && leaf.physical_type() != PhysicalType::BYTE_ARRAY => {
+                    problems.push(format!("column {} is WKB encoded but stored as {}", name, leaf.physical_type()))
+                }
+                _ => {}
+            }
+            if !["WKB", "point", "linestring", "polygon", "multipoint", "multilinestring", "multipolygon"]
+                .contains(&column.encoding.as_str())
+            {
+                problems.push(format!("column {} has the unknown encoding {}", name, column.encoding));
+            }
+
+            for (i, geometry_type) in column.geometry_types.iter().enumerate() {
+                let base = geometry_type.strip_suffix(" Z").unwrap_or(geometry_type);
+                if !GEOMETRY_TYPES.contains(&base) {
+                    problems.push(format!("column {} has the unknown geometry type {}", name, geometry_type));
+                }
+                if column.geometry_types[..i].contains(geometry_type) {
+                    problems.push(format!("column {} lists the geometry type {} twice", name, geometry_type));
+                }
+            }
+
+            if let Some(crs) = &column.crs {
+                if !crs.is_null() && !crs.is_object() {
+                    problems.push(format!("column {} has a CRS which is neither PROJJSON nor null", name));
+                }
+            }
+            if let Some(edges) = &column.edges {
+                if edges != "planar" && edges != "spherical" {
+                    problems.push(format!("column {} has the unknown edges {}", name, edges));
+                }
+            }
+            if let Some(orientation) = &column.orientation {
+                if orientation != "counterclockwise" {
+                    problems.push(format!("column {} has the unknown orientation {}", name, orientation));
+                }
+            }
+            if let Some(bbox) = &column.bbox {
+                match BoundingBox::from_values(bbox) {
+                    // xmin may exceed xmax for boxes crossing the antimeridian
+                    Some(b) if b.ymin > b.ymax => {
+                        problems.push(format!("column {} has a bbox whose ymin exceeds its ymax", name))
+                    }
+                    Some(_) => {}
+                    None => problems.push(format!("column {} has a bbox of {} values, not 4 or 6", name, bbox.len())),
+                }
+            }
+
+            if let Some(covering) = column.covering_bbox() {
+                for path in [&covering.xmin, &covering.ymin, &covering.xmax, &covering.ymax] {
+                    let leaf = schema.columns().iter().find(|c| c.path().parts() == path.as_slice());
+                    match leaf {
+                        None => problems.push(format!(
+                            "column {} has the covering column {} which is not in the file",
+                            name,
+                            path.join(".")
+                        )),
+                        Some(leaf)
+                            if !matches!(leaf.physical_type(), PhysicalType::DOUBLE | PhysicalType::FLOAT) =>
+                        {
+                            problems.push(format!(
+                                "column {} has the covering column {} which is not floating point",
+                                name,
+                                path.join(".")
+                            ))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        problems
+    }
+}
+
+/// Reads the GeoParquet metadata of a file, none when it has no `geo` key.
+pub fn read_geo_metadata(metadata: &FileMetaData) -> Result<Option<GeoMetadata>, PQRSError> {
+    let value = metadata
+        .key_value_metadata()
+        .into_iter()
+        .flatten()
+        .find(|kv| kv.key == GEO_KEY)
+        .and_then(|kv| kv.value.as_ref());
+    match value {
+        Some(value) => serde_json::from_str(value).map(Some).map_err(|e| InvalidGeoMetadata(e.to_string())),
+        None => Ok(None),
+    }
+}
+
+/// A two dimensional bounding box.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+pub struct BoundingBox {
+    pub xmin: f64,
+    pub ymin: f64,
+    pub xmax: f64,
+    pub ymax: f64,
+}
+
+impl BoundingBox {
+    pub fn empty() -> Self {
+        BoundingBox { xmin: f64::INFINITY, ymin: f64::INFINITY, xmax: f64::NEG_INFINITY, ymax: f64::NEG_INFINITY }
+    }
+
+    /// From the 4 or 6 values of a GeoParquet bbox, dropping the z range.
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        match *values {
+            [xmin, ymin, xmax, ymax] | [xmin, ymin, _, xmax, ymax, _] => Some(BoundingBox { xmin, ymin, xmax, ymax }),
+            _ => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.xmin > self.xmax || self.ymin > self.ymax
+    }
+
+    pub fn extend(&mut self, x: f64, y: f64) {
+        if x.is_nan() || y.is_nan() {
+            return;
+        }
+        self.xmin = self.xmin.min(x);
+        self.ymin = self.ymin.min(y);
+        self.xmax = self.xmax.max(x);
+        self.ymax = self.ymax.max(y);
+    }
+
+    pub fn merge(&mut self, other: &BoundingBox) {
+        if !other.is_empty() {
+            self.extend(other.xmin, other.ymin);
+            self.extend(other.xmax, other.ymax);
+        }
+    }
+
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && self.xmin <= other.xmax
+            && other.xmin <= self.xmax
+            && self.ymin <= other.ymax
+            && other.ymin <= self.ymax
+    }
+
+    /// Whether the other box lies within this one, which may cross the antimeridian.
+    pub fn contains(&self, other: &BoundingBox) -> bool {
+        let x_within = match self.xmin <= self.xmax {
+            true => self.xmin <= other.xmin && other.xmax <= self.xmax,
+            false => other.xmin >= self.xmin || other.xmax <= self.xmax,
+        };
+        x_within && self.ymin <= other.ymin && other.ymax <= self.ymax
+    }
+}
+
+impl fmt::Display for BoundingBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.is_empty() {
+            true => write!(f, "empty"),
+            false => write!(f, "[{}, {}, {}, {}]", self.xmin, self.ymin, self.xmax, self.ymax),
+        }
+    }
+}
+
+impl FromStr for BoundingBox {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split(',')
+            .map(|v| v.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("expected xmin,ymin,xmax,ymax: {}", e))?;
+        match values[..] {
+            // an inverted box would be empty and match nothing
+            [xmin, _, xmax, _] if xmin > xmax => Err(format!(
+                "xmin {} is greater than xmax {}, boxes crossing the antimeridian are not supported",
+                xmin, xmax
+            )),
+            [_, ymin, _, ymax] if ymin > ymax => Err(format!("ymin {} is greater than ymax {}", ymin, ymax)),
+            [xmin, ymin, xmax, ymax] => Ok(BoundingBox { xmin, ymin, xmax, ymax }),
+            _ => Err(format!("expected xmin,ymin,xmax,ymax, got {} values", values.len())),
+        }
+    }
+}
+
+fn double_range(statistics: Option<&Statistics>) -> Option<(f64, f64)> {
+    match statistics? {
+        Statistics::Double(s) if s.has_min_max_set() => Some((*s.min(), *s.max())),
+        Statistics::Float(s) if s.has_min_max_set() => Some((*s.min() as f64, *s.max() as f64)),
+        _ => None,
+    }
+}
+
+/// The bounding box of a row group from the statistics of the covering columns, none when
+/// they have no statistics.
+pub fn covering_row_group_bbox(row_group: &RowGroupMetaData, covering: &CoveringBbox) -> Option<BoundingBox> {
+    let range = |path: &Vec<String>| {
+        let column = row_group.columns().iter().find(|c| c.column_path().parts() == path.as_slice())?;
+        double_range(column.statistics())
+    };
+    let (xmin, _) = range(&covering.xmin)?;
+    let (ymin, _) = range(&covering.ymin)?;
+    let (_, xmax) = range(&covering.xmax)?;
+    let (_, ymax) = range(&covering.ymax)?;
+    Some(BoundingBox { xmin, ymin, xmax, ymax })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    /// Empty points are stored with NaN coordinates.
+    Point(Option<Vec<f64>>),
+    LineString(Vec<Vec<f64>>),
+    Polygon(Vec<Vec<Vec<f64>>>),
+    MultiPoint(Vec<Geometry>),
+    MultiLineString(Vec<Geometry>),
+    MultiPolygon(Vec<Geometry>),
+    GeometryCollection(Vec<Geometry>),
+}
+
+/// A geometry decoded from WKB.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Geometry {
+    shape: Shape,
+    has_z: bool,
+    has_m: bool,
+}
+
+// how deep collections may nest, much deeper than any real geometry, well within the stack
+const MAX_WKB_DEPTH: usize = 64;
+
+struct WkbReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl WkbReader<'_> {
+    fn take(&mut self, length: usize) -> Result<&[u8], String> {
+        let end = self.position + length;
+        let bytes = self.bytes.get(self.position..end).ok_or("the WKB is truncated")?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self, little_endian: bool) -> Result<u32, String> {
+        let bytes = self.take(4)?.try_into().unwrap();
+        Ok(if little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    }
+
+    fn f64(&mut self, little_endian: bool) -> Result<f64, String> {
+        let bytes = self.take(8)?.try_into().unwrap();
+        Ok(if little_endian { f64::from_le_bytes(bytes) } else { f64::from_be_bytes(bytes) })
+    }
+
+    // a count of items of at least `size` bytes each, checked against the bytes left
+    fn count(&mut self, little_endian: bool, size: usize) -> Result<usize, String> {
+        let count = self.u32(little_endian)? as usize;
+        if count.saturating_mul(size) > self.bytes.len() - self.position {
+            return Err(format!("the WKB is truncated, {} items are expected", count));
+        }
+        Ok(count)
+    }
+
+    fn coordinates(&mut self, little_endian: bool, dimensions: usize) -> Result<Vec<f64>, String> {
+        (0..dimensions).map(|_| self.f64(little_endian)).collect()
+    }
+
+    fn points(&mut self, little_endian: bool, dimensions: usize) -> Result<Vec<Vec<f64>>, String> {
+        let count = self.count(little_endian, dimensions * 8)?;
+        (0..count).map(|_| self.coordinates(little_endian, dimensions)).collect()
+    }
+
+    fn geometry(&mut self, depth: usize) -> Result<Geometry, String> {
+        if depth > MAX_WKB_DEPTH {
+            return Err(format!("the WKB nests geometries deeper than {} levels", MAX_WKB_DEPTH));
+        }
+        let little_endian = match self.take(1)?[0] {
+            0 => false,
+            1 => true,
+            other => return Err(format!("invalid WKB byte order {}", other)),
+        };
+        let code = self.u32(little_endian)?;
+        // EWKB flags, then ISO dimensions in the thousands
+        let (mut has_z, mut has_m) = (code & 0x8000_0000 != 0, code & 0x4000_0000 != 0);
+        if code & 0x2000_0000 != 0 {
+            self.u32(little_endian)?;
+        }
+        let code = code & 0x0fff_ffff;
+        match code / 1000 {
+            0 => {}
+            1 => has_z = true,
+            2 => has_m = true,
+            3 => (has_z, has_m) = (true, true),
+            _ => return Err(format!("invalid WKB geometry type {}", code)),
+        }
+        let dimensions = 2 + has_z as usize + has_m as usize;
+
+        let shape = match code % 1000 {
+            1 => {
+                let coordinates = self.coordinates(little_endian, dimensions)?;
+                Shape::Point((!coordinates.iter().all(|c| c.is_nan())).then_some(coordinates))
+            }
+            2 => Shape::LineString(self.points(little_endian, dimensions)?),
+            3 => {
+                let count = self.count(little_endian, 4)?;
+                Shape::Polygon((0..count).map(|_| self.points(little_endian, dimensions)).collect::<Result<_, _>>()?)
+            }
+            kind @ 4..=7 => {
+                let count = self.count(little_endian, 5)?;
+                let geometries = (0..count).map(|_| self.geometry(depth + 1)).collect::<Result<Vec<_>, _>>()?;
+                match kind {
+                    4 => Shape::MultiPoint(geometries),
+                    5 => Shape::MultiLineString(geometries),
+                    6 => Shape::MultiPolygon(geometries),
+                    _ => Shape::GeometryCollection(geometries),
+                }
+            }
+            _ => return Err(format!("invalid WKB geometry type {}", code)),
+        };
+        Ok(Geometry { shape, has_z, has_m })
+    }
+}
+
+/// Decodes a geometry from ISO WKB, or extended WKB.
+pub fn parse_wkb(bytes: &[u8]) -> Result<Geometry, String> {
+    let mut reader = WkbReader { bytes, position: 0 };
+    let geometry = reader.geometry(0)?;
+    if reader.position != bytes.len() {
+        return Err(format!("{} bytes follow the WKB geometry", bytes.len() - reader.position));
+    }
+    Ok(geometry)
+}
+
+fn join<T, F: Fn(&T) -> String>(items: &[T], render: F) -> String {
+    items.iter().map(render).collect::<Vec<_>>().join(", ")
+}
+
+fn coordinates_text(coordinates: &[f64]) -> String {
+    coordinates.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+fn points_text(points: &[Vec<f64>]) -> String {
+    format!("({})", join(points, |p| coordinates_text(p)))
+}
+
+// GeoJSON positions only hold x, y and z
+fn position(coordinates: &[f64], has_z: bool) -> Value {
+    json!(coordinates[..if has_z { 3 } else { 2 }])
+}
+
+impl Geometry {
+    fn is_empty(&self) -> bool {
+        match &self.shape {
+            Shape::Point(coordinates) => coordinates.is_none(),
+            Shape::LineString(points) => points.is_empty(),
+            Shape::Polygon(rings) => rings.is_empty(),
+            Shape::MultiPoint(g) | Shape::MultiLineString(g) | Shape::MultiPolygon(g) | Shape::GeometryCollection(g) => {
+                g.is_empty()
+            }
+        }
+    }
+
+    /// The GeoParquet name of the geometry type, such as `Point Z`.
+    pub fn type_name(&self) -> String {
+        let name = match &self.shape {
+            Shape::Point(_) => "Point",
+            Shape::LineString(_) => "LineString",
+            Shape::Polygon(_) => "Polygon",
+            Shape::MultiPoint(_) => "MultiPoint",
+            Shape::MultiLineString(_) => "MultiLineString",
+            Shape::MultiPolygon(_) => "MultiPolygon",
+            Shape::GeometryCollection(_) => "GeometryCollection",
+        };
+        match self.has_z {
+            true => format!("{} Z", name),
+            false => name.to_string(),
+        }
+    }
+
+    // the text between the parentheses of the WKT, none when empty
+    fn wkt_body(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(match &self.shape {
+            Shape::Point(coordinates) => format!("({})", coordinates_text(coordinates.as_ref()?)),
+            Shape::LineString(points) => points_text(points),
+            Shape::Polygon(rings) => format!("({})", join(rings, |r| points_text(r))),
+            Shape::MultiPoint(g) | Shape::MultiLineString(g) | Shape::MultiPolygon(g) => {
+                format!("({})", join(g, |g| g.wkt_body().unwrap_or("EMPTY".to_string())))
+            }
+            Shape::GeometryCollection(g) => format!("({})", join(g, |g| g.to_wkt())),
+        })
+    }
+
+    pub fn to_wkt(&self) -> String {
+        let name = self.type_name().trim_end_matches(" Z").to_uppercase();
+        let dimensions = match (self.has_z, self.has_m) {
+            (true, true) => " ZM",
+            (true, false) => " Z",
+            (false, true) => " M",
+            (false, false) => "",
+        };
+        format!("{}{} {}", name, dimensions, self.wkt_body().unwrap_or("EMPTY".to_string()))
+    }
+
+    fn geojson_coordinates(&self) -> Value {
+        let z = self.has_z;
+        match &self.shape {
+            Shape::Point(coordinates) => coordinates.as_ref().map_or(json!([]), |c| position(c, z)),
+            Shape::LineString(points) => Value::Array(points.iter().map(|p| position(p, z)).collect()),
+            Shape::Polygon(rings) => Value::Array(
+                rings.iter().map(|r| Value::Array(r.iter().map(|p| position(p, z)).collect())).collect(),
+            ),
+            Shape::MultiPoint(g) | Shape::MultiLineString(g) | Shape::MultiPolygon(g) | Shape::GeometryCollection(g) => {
+                Value::Array(g.iter().map(|g| g.geojson_coordinates()).collect())
+            }
+        }
+    }
+
+    pub fn to_geojson(&self) -> Value {
+        let name = self.type_name().trim_end_matches(" Z").to_string();
+        match &self.shape {
+            Shape::GeometryCollection(g) => {
+                json!({"type": name, "geometries": g.iter().map(|g| g.to_geojson()).collect::<Vec<_>>()})
+            }
+            _ => json!({"type": name, "coordinates": self.geojson_coordinates()}),
+        }
+    }
+
+    pub fn bbox(&self) -> BoundingBox {
+        let mut bbox = BoundingBox::empty();
+        self.extend_bbox(&mut bbox);
+        bbox
+    }
+
+    fn extend_bbox(&self, bbox: &mut BoundingBox) {
+        match &self.shape {
+            Shape::Point(coordinates) => coordinates.iter().for_each(|c| bbox.extend(c[0], c[1])),
+            Shape::LineString(points) => points.iter().for_each(|p| bbox.extend(p[0], p[1])),
+            Shape::Polygon(rings) => rings.iter().flatten().for_each(|p| bbox.extend(p[0], p[1])),
+            Shape::MultiPoint(g) | Shape::MultiLineString(g) | Shape::MultiPolygon(g) | Shape::GeometryCollection(g) => {
+                g.iter().for_each(|g| g.extend_bbox(bbox))
+            }
+        }
+    }
+}
+
+/// How WKB geometries are printed.
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub enum GeometryFormat {
+    Wkt,
+    Geojson,
+}
+
+impl GeometryFormat {
+    /// The geometry as text, the WKB error when it can not be decoded.
+    pub fn render_text(&self, wkb: &[u8]) -> String {
+        match (parse_wkb(wkb), self) {
+            (Ok(geometry), GeometryFormat::Wkt) => geometry.to_wkt(),
+            (Ok(geometry), GeometryFormat::Geojson) => geometry.to_geojson().to_string(),
+            (Err(e), _) => format!("invalid WKB: {}", e),
+        }
+    }
+
+    /// The geometry as a JSON value, GeoJSON ones are objects.
+    pub fn render_json(&self, wkb: &[u8]) -> Value {
+        match (parse_wkb(wkb), self) {
+            (Ok(geometry), GeometryFormat::Geojson) => geometry.to_geojson(),
+            _ => Value::String(self.render_text(wkb)),
+        }
+    }
+}
@@ -3,19 +3,51 @@ use clap::{Parser, Subcommand};
 use crate::errors::PQRSError;
 
 mod errors;
+mod footer;
 mod utils;
+mod bloom;
 mod commands;
+mod pages;
+mod recover;
+mod sampling;
+mod timestamp;
+mod embedded;
+mod geo;
+mod fields;
+mod ddl;
+mod export;
+mod synthetic;
 
 #[derive(Subcommand, Debug)]
 enum Commands {
+    Advise(commands::advise::AdviseCommandArgs),
+    Bench(commands::bench::BenchCommandArgs),
+    Bloom(commands::bloom::BloomCommandArgs),
     Cat(commands::cat::CatCommandArgs),
+    Create(commands::create::CreateCommandArgs),
+    Dictionary(commands::dictionary::DictionaryCommandArgs),
+    Footer(commands::footer::FooterCommandArgs),
+    Generate(commands::generate::GenerateCommandArgs),
+    Geo(commands::geo::GeoCommandArgs),
+    Get(commands::get::GetCommandArgs),
     Head(commands::head::HeadCommandArgs),
+    Layout(commands::layout::LayoutCommandArgs),
+    Levels(commands::levels::LevelsCommandArgs),
+    Lint(commands::lint::LintCommandArgs),
     Merge(commands::merge::MergeCommandArgs),
+    Meta(commands::meta::MetaCommandArgs),
+    PageIndex(commands::page_index::PageIndexCommandArgs),
+    #[clap(alias = "dump")]
+    Pages(commands::pages::PagesCommandArgs),
+    Recover(commands::recover::RecoverCommandArgs),
     #[clap(alias = "rowcount")]
     RowCount(commands::rowcount::RowCountCommandArgs),
     Sample(commands::sample::SampleCommandArgs),
     Schema(commands::schema::SchemaCommandArgs),
-    Size(commands::size::SizeCommandArgs)
+    Size(commands::size::SizeCommandArgs),
+    Slice(commands::slice::SliceCommandArgs),
+    Tail(commands::tail::TailCommandArgs),
+    Verify(commands::verify::VerifyCommandArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -38,13 +70,32 @@ fn main() -> Result<(), PQRSError> {
     log::debug!("args: {:?}", args);
 
     match args.command {
+        Commands::Advise(opts) => commands::advise::execute(opts)?,
+        Commands::Bench(opts) => commands::bench::execute(opts)?,
+        Commands::Bloom(opts) => commands::bloom::execute(opts)?,
         Commands::Cat(opts) => commands::cat::execute(opts)?,
+        Commands::Create(opts) => commands::create::execute(opts)?,
+        Commands::Dictionary(opts) => commands::dictionary::execute(opts)?,
+        Commands::Footer(opts) => commands::footer::execute(opts)?,
+        Commands::Generate(opts) => commands::generate::execute(opts)?,
+        Commands::Geo(opts) => commands::geo::execute(opts)?,
+        Commands::Get(opts) => commands::get::execute(opts)?,
         Commands::Head(opts) => commands::head::execute(opts)?,
+        Commands::Layout(opts) => commands::layout::execute(opts)?,
+        Commands::Levels(opts) => commands::levels::execute(opts)?,
+        Commands::Lint(opts) => commands::lint::execute(opts)?,
         Commands::Merge(opts) => commands::merge::execute(opts)?,
+        Commands::Meta(opts) => commands::meta::execute(opts)?,
+        Commands::PageIndex(opts) => commands::page_index::execute(opts)?,
+        Commands::Pages(opts) => commands::pages::execute(opts)?,
+        Commands::Recover(opts) => commands::recover::execute(opts)?,
         Commands::RowCount(opts) => commands::rowcount::execute(opts)?,
         Commands::Sample(opts) => commands::sample::execute(opts)?,
         Commands::Schema(opts) => commands::schema::execute(opts)?,
         Commands::Size(opts) => commands::size::execute(opts)?,
+        Commands::Slice(opts) => commands::slice::execute(opts)?,
+        Commands::Tail(opts) => commands::tail::execute(opts)?,
+        Commands::Verify(opts) => commands::verify::execute(opts)?,
     }
 
     Ok(())
@@ -0,0 +1,310 @@
+use std::fs::File;
+use std::io::Cursor;
+
+use parquet::basic::{Encoding, PageType, Type as PhysicalType};
+use parquet::column::page::Page;
+use parquet::column::reader::{ColumnReader, ColumnReaderImpl};
+use parquet::data_type::DataType;
+use parquet::errors::ParquetError;
+use parquet::file::metadata::ColumnChunkMetaData;
+use parquet::file::reader::{ChunkReader, RowGroupReader};
+use parquet::format::{PageHeader, Statistics};
+use parquet::schema::types::ColumnDescriptor;
+use serde::Serialize;
+use thrift::protocol::{TCompactInputProtocol, TSerializable};
+
+use crate::errors::PQRSError;
+
+/// A page header as stored in the file, with where it was found.
+#[derive(Debug)]
+pub struct PageHeaderInfo {
+    /// Absolute offset of the page header in the file.
+    pub offset: u64,
+    pub header_size: usize,
+    pub header: PageHeader,
+}
+
+impl PageHeaderInfo {
+    pub fn page_type(&self) -> Option<PageType> {
+        PageType::try_from(self.header.type_).ok()
+    }
+
+    pub fn num_values(&self) -> Option<i32> {
+        let header = &self.header;
+        header
+            .data_page_header
+            .as_ref()
+            .map(|h| h.num_values)
+            .or_else(|| header.data_page_header_v2.as_ref().map(|h| h.num_values))
+            .or_else(|| header.dictionary_page_header.as_ref().map(|h| h.num_values))
+    }
+
+    pub fn encoding(&self) -> Option<Encoding> {
+        let header = &self.header;
+        header
+            .data_page_header
+            .as_ref()
+            .map(|h| h.encoding)
+            .or_else(|| header.data_page_header_v2.as_ref().map(|h| h.encoding))
+            .or_else(|| header.dictionary_page_header.as_ref().map(|h| h.encoding))
+            .and_then(|e| Encoding::try_from(e).ok())
+    }
+
+    pub fn statistics(&self) -> Option<&Statistics> {
+        let header = &self.header;
+        header
+            .data_page_header
+            .as_ref()
+            .and_then(|h| h.statistics.as_ref())
+            .or_else(|| header.data_page_header_v2.as_ref().and_then(|h| h.statistics.as_ref()))
+    }
+
+    pub fn is_data_page(&self) -> bool {
+        matches!(self.page_type(), Some(PageType::DATA_PAGE) | Some(PageType::DATA_PAGE_V2))
+    }
+}
+
+/// Returns the start and the length of a column chunk, like `ColumnChunkMetaData::byte_range`
+/// but with an error instead of a panic for negative offsets or sizes, or a chunk ending past
+/// the largest file offset.
+pub fn column_byte_range(column: &ColumnChunkMetaData) -> Result<(u64, u64), PQRSError> {
+    let start = column.dictionary_page_offset().unwrap_or(column.data_page_offset());
+    let invalid = |message: String| ParquetError::General(format!("{}: {}", column.column_path(), message));
+    let start = u64::try_from(start)
+        .map_err(|_| invalid(format!("the column chunk starts at a negative offset {}", start)))?;
+    let length = u64::try_from(column.compressed_size()).map_err(|_| {
+        invalid(format!("the column chunk has a negative size {}", column.compressed_size()))
+    })?;
+    if start.checked_add(length).is_none() {
+        return Err(invalid(format!("the column chunk size {} overflows the file offsets", length)).into());
+    }
+    Ok((start, length))
+}
+
+/// Reads the thrift headers of every page in a column chunk, without decompressing the pages.
+pub fn read_page_headers(
+    file: &File,
+    column: &ColumnChunkMetaData,
+) -> Result<Vec<PageHeaderInfo>, PQRSError> {
+    let (start, length) = column_byte_range(column)?;
+    let bytes = file.get_bytes(start, length as usize)?;
+
+    let mut headers = vec![];
+    let mut cursor = Cursor::new(bytes.as_ref());
+    while (cursor.position() as usize) < bytes.len() {
+        let position = cursor.position();
+        let header = {
+            let mut protocol = TCompactInputProtocol::new(&mut cursor);
+            PageHeader::read_from_in_protocol(&mut protocol)?
+        };
+        let header_size = (cursor.position() - position) as usize;
+        let page_size = u64::try_from(header.compressed_page_size).map_err(|_| {
+            ParquetError::General(format!(
+                "page at offset {} has a negative compressed size {}",
+                start + position,
+                header.compressed_page_size
+            ))
+        })?;
+        let next = cursor.position().checked_add(page_size).ok_or_else(|| {
+            ParquetError::General(format!("page at offset {} ends past the column chunk", start + position))
+        })?;
+        cursor.set_position(next);
+        headers.push(PageHeaderInfo { offset: start + position, header_size, header });
+    }
+
+    Ok(headers)
+}
+
+/// Renders raw bytes as text when they are valid UTF-8, as hex otherwise.
+pub fn bytes_text(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => {
+            let hex = bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+            format!("0x{}", hex)
+        }
+    }
+}
+
+fn take<'a>(buf: &mut &'a [u8], size: usize) -> Result<&'a [u8], PQRSError> {
+    if buf.len() < size {
+        return Err(ParquetError::EOF(String::from("not enough bytes to decode a plain value")).into());
+    }
+    let (value, rest) = buf.split_at(size);
+    *buf = rest;
+    Ok(value)
+}
+
+/// Decodes `num_values` PLAIN encoded values, as found in dictionary pages.
+pub fn plain_values(
+    buf: &[u8],
+    descr: &ColumnDescriptor,
+    num_values: usize,
+) -> Result<Vec<String>, PQRSError> {
+    let mut buf = buf;
+    let mut values = Vec::with_capacity(num_values);
+    for i in 0..num_values {
+        let value = match descr.physical_type() {
+            PhysicalType::BOOLEAN => {
+                let byte = buf.get(i / 8).ok_or_else(|| {
+                    ParquetError::EOF(String::from("not enough bytes to decode a plain value"))
+                })?;
+                ((byte >> (i % 8)) & 1 == 1).to_string()
+            }
+            PhysicalType::INT32 => {
+                i32::from_le_bytes(take(&mut buf, 4)?.try_into().unwrap()).to_string()
+            }
+            PhysicalType::INT64 => {
+                i64::from_le_bytes(take(&mut buf, 8)?.try_into().unwrap()).to_string()
+            }
+            PhysicalType::INT96 => {
+                let words = take(&mut buf, 12)?
+                    .chunks(4)
+                    .map(|w| u32::from_le_bytes(w.try_into().unwrap()))
+                    .collect::<Vec<_>>();
+                format!("{:?}", words)
+            }
+            PhysicalType::FLOAT => {
+                f32::from_le_bytes(take(&mut buf, 4)?.try_into().unwrap()).to_string()
+            }
+            PhysicalType::DOUBLE => {
+                f64::from_le_bytes(take(&mut buf, 8)?.try_into().unwrap()).to_string()
+            }
+            PhysicalType::BYTE_ARRAY => {
+                let length = u32::from_le_bytes(take(&mut buf, 4)?.try_into().unwrap());
+                bytes_text(take(&mut buf, length as usize)?)
+            }
+            PhysicalType::FIXED_LEN_BYTE_ARRAY => {
+                bytes_text(take(&mut buf, descr.type_length() as usize)?)
+            }
+        };
+        values.push(value);
+    }
+    Ok(values)
+}
+
+/// Renders a min or max value from the statistics of a page or column chunk, which are
+/// plain encoded except for byte arrays which lose their length prefix.
+pub fn statistics_value(bytes: &[u8], physical_type: PhysicalType) -> String {
+    let value = match (physical_type, bytes.len()) {
+        (PhysicalType::BOOLEAN, 1) => Some((bytes[0] & 1 == 1).to_string()),
+        (PhysicalType::INT32, 4) => Some(i32::from_le_bytes(bytes.try_into().unwrap()).to_string()),
+        (PhysicalType::INT64, 8) => Some(i64::from_le_bytes(bytes.try_into().unwrap()).to_string()),
+        (PhysicalType::FLOAT, 4) => Some(f32::from_le_bytes(bytes.try_into().unwrap()).to_string()),
+        (PhysicalType::DOUBLE, 8) => Some(f64::from_le_bytes(bytes.try_into().unwrap()).to_string()),
+        _ => None,
+    };
+    value.unwrap_or_else(|| bytes_text(bytes))
+}
+
+/// Statistics of a single page, rendered for printing.
+#[derive(Serialize, Debug)]
+pub struct PageStatistics {
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub null_count: Option<i64>,
+    pub distinct_count: Option<i64>,
+}
+
+impl PageStatistics {
+    pub fn new(statistics: &Statistics, descr: &ColumnDescriptor) -> Self {
+        // min_value/max_value supersede the deprecated min/max fields
+        let min = statistics.min_value.as_ref().or(statistics.min.as_ref());
+        let max = statistics.max_value.as_ref().or(statistics.max.as_ref());
+        Self {
+            min: min.map(|v| statistics_value(v, descr.physical_type())),
+            max: max.map(|v| statistics_value(v, descr.physical_type())),
+            null_count: statistics.null_count,
+            distinct_count: statistics.distinct_count,
+        }
+    }
+}
+
+/// Decodes the values of the dictionary page of a column chunk, if it has one.
+pub fn dictionary_values(
+    row_group_reader: &dyn RowGroupReader,
+    column: usize,
+) -> Result<Option<Vec<String>>, PQRSError> {
+    let descr = row_group_reader.metadata().column(column).column_descr_ptr();
+    let mut page_reader = row_group_reader.get_column_page_reader(column)?;
+    match page_reader.get_next_page()? {
+        Some(Page::DictionaryPage { buf, num_values, .. }) => {
+            Ok(Some(plain_values(buf.as_ref(), &descr, num_values as usize)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Levels read from a column, with the text of its non null values.
+#[derive(Debug, Default)]
+pub struct LeveledValues {
+    pub values: Vec<String>,
+    pub def_levels: Vec<i16>,
+    pub rep_levels: Vec<i16>,
+}
+
+fn read_typed_batch<T: DataType>(
+    reader: &mut ColumnReaderImpl<T>,
+    batch_size: usize,
+    text: impl Fn(&T::T) -> String,
+) -> Result<LeveledValues, PQRSError> {
+    let mut values = vec![T::T::default(); batch_size];
+    let mut def_levels = vec![0; batch_size];
+    let mut rep_levels = vec![0; batch_size];
+    let (values_read, levels_read) = reader.read_batch(
+        batch_size,
+        Some(&mut def_levels),
+        Some(&mut rep_levels),
+        &mut values,
+    )?;
+    // columns that are neither optional nor repeated have no levels, one per value then
+    let levels_read = levels_read.max(values_read);
+    def_levels.truncate(levels_read);
+    rep_levels.truncate(levels_read);
+    Ok(LeveledValues {
+        values: values[..values_read].iter().map(text).collect(),
+        def_levels,
+        rep_levels,
+    })
+}
+
+/// Reads up to `batch_size` levels, and the values they hold, from a column reader. Less
+/// levels are returned only at the end of the column chunk.
+pub fn read_column_batch(
+    reader: &mut ColumnReader,
+    batch_size: usize,
+) -> Result<LeveledValues, PQRSError> {
+    match reader {
+        ColumnReader::BoolColumnReader(r) => read_typed_batch(r, batch_size, |v| v.to_string()),
+        ColumnReader::Int32ColumnReader(r) => read_typed_batch(r, batch_size, |v| v.to_string()),
+        ColumnReader::Int64ColumnReader(r) => read_typed_batch(r, batch_size, |v| v.to_string()),
+        ColumnReader::Int96ColumnReader(r) => {
+            read_typed_batch(r, batch_size, |v| format!("{:?}", v.data()))
+        }
+        ColumnReader::FloatColumnReader(r) => read_typed_batch(r, batch_size, |v| v.to_string()),
+        ColumnReader::DoubleColumnReader(r) => read_typed_batch(r, batch_size, |v| v.to_string()),
+        ColumnReader::ByteArrayColumnReader(r) => {
+            read_typed_batch(r, batch_size, |v| bytes_text(v.data()))
+        }
+        ColumnReader::FixedLenByteArrayColumnReader(r) => {
+            read_typed_batch(r, batch_size, |v| bytes_text(v.data()))
+        }
+    }
+}
+
+/// Decodes the non null values of the data pages of a column chunk, page by page.
+///
+/// `pages` holds the number of values, levels included, of every data page in order. The
+/// column reader never reads past the end of a page when asked for exactly that many values.
+pub fn data_page_values(
+    row_group_reader: &dyn RowGroupReader,
+    column: usize,
+    pages: &[usize],
+) -> Result<Vec<Vec<String>>, PQRSError> {
+    let mut reader = row_group_reader.get_column_reader(column)?;
+    let mut values = vec![];
+    for &num_values in pages {
+        values.push(read_column_batch(&mut reader, num_values)?.values);
+    }
+    Ok(values)
+}
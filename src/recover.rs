@@ -0,0 +1,464 @@
+use std::collections::BTreeSet;
+use std::io::{Cursor, Write};
+use std::mem;
+
+use parquet::basic::{Encoding, PageType};
+use parquet::format::{
+    self, ColumnChunk, ColumnMetaData, CompressionCodec, FileMetaData, KeyValue, PageHeader,
+    RowGroup,
+};
+use parquet::schema::types::{to_thrift, ColumnDescriptor, SchemaDescriptor};
+use thrift::protocol::{TCompactInputProtocol, TInputProtocol, TSerializable, TType};
+
+use crate::errors::PQRSError;
+use crate::footer::{write_footer, MAGIC};
+use crate::pages::PageHeaderInfo;
+
+/// What scanning a damaged file finds, in file order.
+#[derive(Debug)]
+pub enum ScanItem {
+    /// A page header which is consistent with the bytes following it.
+    Page(PageHeaderInfo),
+    /// The copy of the column metadata some writers store after the pages of a column chunk.
+    ChunkEnd(ColumnMetaData),
+    /// Bytes holding neither, such as a torn page or the remains of a footer.
+    Unknown { offset: u64, length: u64 },
+}
+
+// decodes a thrift structure at the start of `bytes`, returning it with its size. Skipping it
+// first checks the whole structure is there, so lists are never allocated from garbage lengths.
+fn decode<T: TSerializable>(bytes: &[u8]) -> Option<(T, usize)> {
+    let mut cursor = Cursor::new(bytes);
+    TCompactInputProtocol::new(&mut cursor).skip(TType::Struct).ok()?;
+    let mut cursor = Cursor::new(bytes);
+    let value = T::read_from_in_protocol(&mut TCompactInputProtocol::new(&mut cursor)).ok()?;
+    Some((value, cursor.position() as usize))
+}
+
+// the column metadata held by a column chunk. The chunk is read by hand as the generated code
+// panics on the empty unions garbage decodes to.
+fn decode_chunk_metadata(bytes: &[u8]) -> Option<(ColumnMetaData, usize)> {
+    let mut cursor = Cursor::new(bytes);
+    TCompactInputProtocol::new(&mut cursor).skip(TType::Struct).ok()?;
+    let size = cursor.position() as usize;
+
+    let mut cursor = Cursor::new(bytes);
+    let mut protocol = TCompactInputProtocol::new(&mut cursor);
+    let mut metadata = None;
+    protocol.read_struct_begin().ok()?;
+    loop {
+        let field = protocol.read_field_begin().ok()?;
+        match (field.id, field.field_type) {
+            (_, TType::Stop) => break,
+            (Some(3), TType::Struct) => {
+                metadata = Some(ColumnMetaData::read_from_in_protocol(&mut protocol).ok()?)
+            }
+            (_, field_type) => protocol.skip(field_type).ok()?,
+        }
+        protocol.read_field_end().ok()?;
+    }
+    protocol.read_struct_end().ok()?;
+    Some((metadata?, size))
+}
+
+// whether a decoded header describes a page that fits in the `available` bytes after it
+fn is_plausible_page(header: &PageHeader, available: usize) -> bool {
+    if header.compressed_page_size < 0
+        || header.uncompressed_page_size < 0
+        || header.compressed_page_size as usize > available
+    {
+        return false;
+    }
+    let known = |encoding: format::Encoding| Encoding::try_from(encoding).is_ok();
+    match PageType::try_from(header.type_) {
+        Ok(PageType::DATA_PAGE) => header.data_page_header.as_ref().is_some_and(|h| {
+            h.num_values > 0
+                && known(h.encoding)
+                && known(h.definition_level_encoding)
+                && known(h.repetition_level_encoding)
+        }),
+        Ok(PageType::DATA_PAGE_V2) => header.data_page_header_v2.as_ref().is_some_and(|h| {
+            h.num_values > 0
+                && h.num_nulls >= 0
+                && h.num_rows >= 0
+                && h.definition_levels_byte_length >= 0
+                && h.repetition_levels_byte_length >= 0
+                && h.definition_levels_byte_length as i64 + h.repetition_levels_byte_length as i64
+                    <= header.compressed_page_size as i64
+                && known(h.encoding)
+        }),
+        Ok(PageType::DICTIONARY_PAGE) => header
+            .dictionary_page_header
+            .as_ref()
+            .is_some_and(|h| h.num_values >= 0 && known(h.encoding)),
+        _ => false,
+    }
+}
+
+fn scan_item(bytes: &[u8], position: usize, schema: &SchemaDescriptor) -> Option<(ScanItem, usize)> {
+    let rest = &bytes[position..];
+    if let Some((header, header_size)) = decode::<PageHeader>(rest) {
+        if is_plausible_page(&header, rest.len() - header_size) {
+            let size = header_size + header.compressed_page_size as usize;
+            let page = PageHeaderInfo { offset: position as u64, header_size, header };
+            return Some((ScanItem::Page(page), size));
+        }
+    }
+
+    // parquet-rs stores the column metadata after the pages, parquet-cpp the whole column chunk
+    let (metadata, size) =
+        decode::<ColumnMetaData>(rest).or_else(|| decode_chunk_metadata(rest))?;
+    let known_column = schema.columns().iter().any(|c| {
+        c.path().parts() == metadata.path_in_schema.as_slice()
+            && format::Type::from(c.physical_type()) == metadata.type_
+    });
+    match known_column && metadata.num_values >= 0 {
+        true => Some((ScanItem::ChunkEnd(metadata), size)),
+        false => None,
+    }
+}
+
+/// Looks for pages and column metadata from the start of a file, without relying on its footer.
+/// Bytes which hold neither are skipped one at a time until a page header shows up again.
+pub fn scan_file(bytes: &[u8], schema: &SchemaDescriptor) -> Vec<ScanItem> {
+    let mut items = vec![];
+    let mut position = if bytes.starts_with(MAGIC) { MAGIC.len() } else { 0 };
+    let mut unknown = None;
+    while position < bytes.len() {
+        match scan_item(bytes, position, schema) {
+            Some((item, size)) => {
+                if let Some(offset) = unknown.take() {
+                    let length = (position - offset) as u64;
+                    items.push(ScanItem::Unknown { offset: offset as u64, length });
+                }
+                items.push(item);
+                position += size;
+            }
+            None => {
+                unknown.get_or_insert(position);
+                position += 1;
+            }
+        }
+    }
+    if let Some(offset) = unknown {
+        let length = (bytes.len() - offset) as u64;
+        items.push(ScanItem::Unknown { offset: offset as u64, length });
+    }
+    items
+}
+
+/// A column chunk put together from the pages found while scanning.
+#[derive(Debug)]
+pub struct ScannedChunk {
+    pub pages: Vec<PageHeaderInfo>,
+    /// The codec of the pages, known when the writer stored a copy of the column metadata.
+    pub codec: Option<CompressionCodec>,
+}
+
+impl ScannedChunk {
+    pub fn offset(&self) -> u64 {
+        self.pages[0].offset
+    }
+
+    pub fn num_values(&self) -> i64 {
+        self.pages
+            .iter()
+            .filter(|p| p.is_data_page())
+            .map(|p| p.num_values().unwrap_or_default() as i64)
+            .sum()
+    }
+
+    // rows can be told from the headers unless the column is repeated and its pages are v1
+    fn num_rows(&self, descr: &ColumnDescriptor) -> Option<i64> {
+        if descr.max_rep_level() == 0 {
+            return Some(self.num_values());
+        }
+        self.pages
+            .iter()
+            .filter(|p| p.is_data_page())
+            .map(|p| p.header.data_page_header_v2.as_ref().map(|h| h.num_rows as i64))
+            .sum()
+    }
+}
+
+/// A row group put together from one column chunk of each column, in schema order.
+#[derive(Debug)]
+pub struct ScannedRowGroup {
+    pub chunks: Vec<ScannedChunk>,
+    /// The number of rows, when the page headers tell.
+    pub num_rows: Option<i64>,
+}
+
+impl ScannedRowGroup {
+    pub fn offset(&self) -> u64 {
+        self.chunks[0].offset()
+    }
+}
+
+// puts scanned pages together into column chunks then row groups, writers store the column
+// chunks of a row group one after the other in schema order
+struct Assembler<'a> {
+    schema: &'a SchemaDescriptor,
+    row_groups: Vec<ScannedRowGroup>,
+    // column chunks of the row group being put together, and its rows when they are known
+    chunks: Vec<ScannedChunk>,
+    num_rows: Option<i64>,
+    // pages of the column chunk being put together
+    pages: Vec<PageHeaderInfo>,
+    // whether these pages hold as many rows as the other column chunks of the row group
+    pages_complete: bool,
+    notes: Vec<String>,
+}
+
+impl<'a> Assembler<'a> {
+    fn drop_row_group(&mut self, reason: &str) {
+        if let Some(first) = self.chunks.first() {
+            self.notes.push(format!(
+                "dropped {} column chunks from offset {}, {}",
+                self.chunks.len(),
+                first.offset(),
+                reason
+            ));
+        }
+        self.chunks.clear();
+        self.num_rows = None;
+    }
+
+    fn drop_pages(&mut self, reason: &str) {
+        if let Some(first) = self.pages.first() {
+            self.notes.push(format!(
+                "dropped {} pages from offset {}, {}",
+                self.pages.len(),
+                first.offset,
+                reason
+            ));
+        }
+        self.pages.clear();
+        self.pages_complete = false;
+    }
+
+    fn close_chunk(&mut self, codec: Option<CompressionCodec>, path: Option<&[String]>) {
+        if !self.pages.iter().any(|p| p.is_data_page()) {
+            self.drop_pages("they hold no data page");
+            return;
+        }
+        let chunk = ScannedChunk { pages: mem::take(&mut self.pages), codec };
+        self.pages_complete = false;
+
+        if let Some(path) = path {
+            let column = self.schema.columns().iter().position(|c| c.path().parts() == path);
+            if column != Some(self.chunks.len()) {
+                self.drop_row_group(&format!("the column chunk of {} follows them", path.join(".")));
+                if column != Some(0) {
+                    self.notes.push(format!(
+                        "dropped the column chunk of {} at offset {}, the chunks before it are missing",
+                        path.join("."),
+                        chunk.offset()
+                    ));
+                    return;
+                }
+            }
+        }
+
+        let descr = self.schema.column(self.chunks.len());
+        if let (Some(expected), Some(num_rows)) = (self.num_rows, chunk.num_rows(&descr)) {
+            if expected != num_rows {
+                self.drop_row_group("the next column chunk holds a different number of rows");
+            }
+        }
+        if self.chunks.is_empty() {
+            self.num_rows = chunk.num_rows(&self.schema.column(0));
+        }
+        self.chunks.push(chunk);
+
+        if self.chunks.len() == self.schema.num_columns() {
+            let chunks = mem::take(&mut self.chunks);
+            self.row_groups.push(ScannedRowGroup { chunks, num_rows: self.num_rows.take() });
+        }
+    }
+
+    fn add_page(&mut self, page: PageHeaderInfo) {
+        if self.pages_complete {
+            self.close_chunk(None, None);
+        }
+        // a dictionary page starts a column chunk
+        if page.page_type() == Some(PageType::DICTIONARY_PAGE) && !self.pages.is_empty() {
+            self.close_chunk(None, None);
+        }
+        self.pages.push(page);
+
+        // the chunks of columns which are not repeated hold one value per row
+        let descr = self.schema.column(self.chunks.len());
+        if let Some(num_rows) = self.num_rows {
+            let num_values = self
+                .pages
+                .iter()
+                .filter(|p| p.is_data_page())
+                .map(|p| p.num_values().unwrap_or_default() as i64)
+                .sum::<i64>();
+            self.pages_complete = descr.max_rep_level() == 0 && num_values >= num_rows;
+        }
+    }
+
+    fn add(&mut self, item: ScanItem) {
+        match item {
+            ScanItem::Page(page) => self.add_page(page),
+            // footers hold copies of the column metadata too, they follow no page
+            ScanItem::ChunkEnd(metadata) if !self.pages.is_empty() => {
+                self.close_chunk(Some(metadata.codec), Some(&metadata.path_in_schema));
+            }
+            ScanItem::ChunkEnd(_) => {}
+            // pages are only kept whole, the column chunk may still be complete then
+            ScanItem::Unknown { offset, length } => {
+                self.notes.push(format!("skipped {} bytes at offset {} holding no page", length, offset));
+                if !self.pages.is_empty() {
+                    self.close_chunk(None, None);
+                }
+                self.drop_row_group(&format!("they are cut off at offset {}", offset));
+            }
+        }
+    }
+
+    fn finish(mut self) -> (Vec<ScannedRowGroup>, Vec<String>) {
+        if !self.pages.is_empty() {
+            self.close_chunk(None, None);
+        }
+        self.drop_row_group("the file ends before the row group does");
+        (self.row_groups, self.notes)
+    }
+}
+
+/// Puts the scanned pages together into row groups, returning them with notes on what was
+/// dropped and why. Without column metadata copies, column chunks are told apart by their
+/// dictionary pages and by the rows of the first chunk of the row group.
+pub fn assemble_row_groups(
+    items: Vec<ScanItem>,
+    schema: &SchemaDescriptor,
+) -> (Vec<ScannedRowGroup>, Vec<String>) {
+    let mut assembler = Assembler {
+        schema,
+        row_groups: vec![],
+        chunks: vec![],
+        num_rows: None,
+        pages: vec![],
+        pages_complete: false,
+        notes: vec![],
+    };
+    for item in items {
+        assembler.add(item);
+    }
+    assembler.finish()
+}
+
+fn write_chunk<W: Write>(
+    sink: &mut W,
+    offset: &mut u64,
+    bytes: &[u8],
+    chunk: &ScannedChunk,
+    descr: &ColumnDescriptor,
+) -> Result<ColumnChunk, PQRSError> {
+    let start = *offset;
+    let (mut dictionary_page_offset, mut data_page_offset) = (None, None);
+    let (mut compressed_size, mut uncompressed_size) = (0, 0);
+    let mut encodings = BTreeSet::new();
+    for page in &chunk.pages {
+        let size = page.header_size + page.header.compressed_page_size as usize;
+        let page_start = page.offset as usize;
+        sink.write_all(&bytes[page_start..page_start + size])?;
+
+        match page.page_type() {
+            Some(PageType::DICTIONARY_PAGE) => dictionary_page_offset.get_or_insert(*offset as i64),
+            _ => data_page_offset.get_or_insert(*offset as i64),
+        };
+        encodings.extend(page.encoding().map(format::Encoding::from));
+        if let Some(header) = &page.header.data_page_header {
+            encodings.insert(header.definition_level_encoding);
+            encodings.insert(header.repetition_level_encoding);
+        }
+        if page.header.data_page_header_v2.is_some() {
+            encodings.insert(format::Encoding::RLE);
+        }
+        compressed_size += size as i64;
+        uncompressed_size += (page.header_size + page.header.uncompressed_page_size as usize) as i64;
+        *offset += size as u64;
+    }
+
+    let metadata = ColumnMetaData {
+        type_: descr.physical_type().into(),
+        encodings: encodings.into_iter().collect(),
+        path_in_schema: descr.path().parts().to_vec(),
+        codec: chunk.codec.unwrap_or(CompressionCodec::UNCOMPRESSED),
+        num_values: chunk.num_values(),
+        total_uncompressed_size: uncompressed_size,
+        total_compressed_size: compressed_size,
+        key_value_metadata: None,
+        data_page_offset: data_page_offset.unwrap_or(start as i64),
+        index_page_offset: None,
+        dictionary_page_offset,
+        statistics: None,
+        encoding_stats: None,
+        bloom_filter_offset: None,
+    };
+    Ok(ColumnChunk {
+        file_path: None,
+        file_offset: start as i64,
+        meta_data: Some(metadata),
+        offset_index_offset: None,
+        offset_index_length: None,
+        column_index_offset: None,
+        column_index_length: None,
+        crypto_metadata: None,
+        encrypted_column_metadata: None,
+    })
+}
+
+/// Writes a parquet file holding the given row groups, copying their pages from `bytes` as
+/// they are. Column chunks of unknown codec are written as uncompressed.
+pub fn write_row_groups<W: Write>(
+    mut sink: W,
+    bytes: &[u8],
+    schema: &SchemaDescriptor,
+    row_groups: &[ScannedRowGroup],
+    key_value_metadata: Option<Vec<KeyValue>>,
+) -> Result<W, PQRSError> {
+    sink.write_all(MAGIC)?;
+    let mut offset = MAGIC.len() as u64;
+
+    let mut thrift_row_groups = vec![];
+    for (ordinal, row_group) in row_groups.iter().enumerate() {
+        let start = offset;
+        let columns = row_group
+            .chunks
+            .iter()
+            .zip(schema.columns())
+            .map(|(chunk, descr)| write_chunk(&mut sink, &mut offset, bytes, chunk, descr))
+            .collect::<Result<Vec<_>, _>>()?;
+        let total_byte_size = columns
+            .iter()
+            .filter_map(|c| c.meta_data.as_ref())
+            .map(|m| m.total_uncompressed_size)
+            .sum();
+        thrift_row_groups.push(RowGroup {
+            columns,
+            total_byte_size,
+            num_rows: row_group.num_rows.unwrap_or_default(),
+            sorting_columns: None,
+            file_offset: Some(start as i64),
+            total_compressed_size: Some((offset - start) as i64),
+            ordinal: Some(ordinal as i16),
+        });
+    }
+
+    let metadata = FileMetaData {
+        version: 1,
+        schema: to_thrift(schema.root_schema())?,
+        num_rows: thrift_row_groups.iter().map(|r| r.num_rows).sum(),
+        row_groups: thrift_row_groups,
+        key_value_metadata,
+        created_by: Some(format!("pqrs-learn version {}", env!("CARGO_PKG_VERSION"))),
+        column_orders: None,
+        encryption_algorithm: None,
+        footer_signing_key_metadata: None,
+    };
+    write_footer(sink, &metadata)
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::ops::Range;
+
+use arrow::array::{Array, StringArray};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use log::debug;
+use parquet::arrow::arrow_reader::ArrowReaderBuilder;
+use parquet::arrow::ProjectionMask;
+use parquet::file::metadata::ParquetMetaData;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{TooManyStrata, UnknownColumn};
+use crate::timestamp::{TimestampOptions, TimestampRenderer};
+
+/// Keeps a uniform random sample of at most `capacity` items out of a stream whose length
+/// is not known upfront, using Algorithm R. The items grow as they are offered, the stream
+/// may be much shorter than the capacity.
+#[derive(Debug)]
+pub struct Reservoir<T> {
+    capacity: usize,
+    seen: usize,
+    items: Vec<T>,
+}
+
+impl<T> Reservoir<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, seen: 0, items: Vec::new() }
+    }
+
+    pub fn offer<R: Rng>(&mut self, item: T, rng: &mut R) {
+        self.seen += 1;
+        if self.items.len() < self.capacity {
+            self.items.push(item);
+        } else {
+            let slot = rng.gen_range(0..self.seen);
+            if slot < self.capacity {
+                self.items[slot] = item;
+            }
+        }
+    }
+
+    /// Number of items offered so far.
+    pub fn seen(&self) -> usize {
+        self.seen
+    }
+
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+}
+
+/// How many rows to sample.
+#[derive(Copy, Clone, Debug)]
+pub enum SampleSize {
+    Records(usize),
+    /// Each row, or row group, is kept with this probability.
+    Fraction(f64),
+}
+
+/// The random number generator used for sampling, seeded from the system when no seed is given.
+pub fn sample_rng(seed: Option<u64>) -> StdRng {
+    let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+    debug!("Sampling with seed {}", seed);
+    StdRng::seed_from_u64(seed)
+}
+
+/// Merges sorted row indexes into ranges of consecutive rows.
+pub fn to_ranges(indexes: &[usize]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = vec![];
+    for &index in indexes {
+        match ranges.last_mut() {
+            Some(last) if last.end == index => last.end += 1,
+            _ => ranges.push(index..index + 1),
+        }
+    }
+    ranges
+}
+
+/// Picks the rows to sample and returns them as sorted ranges of absolute row indexes.
+///
+/// Rows are picked from the row counts in the footer, so nothing is decoded here. With
+/// `by_row_group`, whole row groups are picked first so only those have to be read later.
+pub fn sample_rows<R: Rng>(
+    metadata: &ParquetMetaData,
+    size: SampleSize,
+    by_row_group: bool,
+    rng: &mut R,
+) -> Vec<Range<usize>> {
+    let mut row_groups = vec![];
+    let mut first_row = 0;
+    for row_group in metadata.row_groups() {
+        let num_rows = row_group.num_rows() as usize;
+        row_groups.push(first_row..first_row + num_rows);
+        first_row += num_rows;
+    }
+
+    if by_row_group {
+        match size {
+            SampleSize::Fraction(fraction) => {
+                row_groups.retain(|_| rng.gen_bool(fraction));
+                return row_groups;
+            }
+            SampleSize::Records(records) => {
+                row_groups.shuffle(rng);
+                let mut rows = 0;
+                let covering = row_groups
+                    .into_iter()
+                    .take_while(|rg| {
+                        let needed = rows < records;
+                        rows += rg.len();
+                        needed
+                    })
+                    .collect::<Vec<_>>();
+                row_groups = covering;
+                row_groups.sort_by_key(|rg| rg.start);
+            }
+        }
+    }
+    debug!("Sampling from the rows of {} row groups", row_groups.len());
+
+    let mut indexes = match size {
+        SampleSize::Records(records) => {
+            let mut reservoir = Reservoir::new(records);
+            for index in row_groups.into_iter().flatten() {
+                reservoir.offer(index, rng);
+            }
+            reservoir.into_items()
+        }
+        SampleSize::Fraction(fraction) => row_groups
+            .into_iter()
+            .flatten()
+            .filter(|_| rng.gen_bool(fraction))
+            .collect(),
+    };
+    indexes.sort_unstable();
+
+    to_ranges(&indexes)
+}
+
+/// How many rows are sampled from each stratum.
+#[derive(Copy, Clone, Debug)]
+pub enum Allocation {
+    PerStratum(usize),
+    /// The total is split between the strata in proportion to their size.
+    Proportional(usize),
+}
+
+/// A distinct value of the stratification column.
+#[derive(Debug)]
+pub struct Stratum {
+    pub value: String,
+    pub rows: usize,
+    pub sampled: usize,
+}
+
+// splits `total` in proportion to `sizes` with the largest remainder method
+fn allocate(total: usize, sizes: &[usize]) -> Vec<usize> {
+    let rows: usize = sizes.iter().sum();
+    let total = total.min(rows);
+    if rows == 0 {
+        return vec![0; sizes.len()];
+    }
+
+    let mut shares = sizes.iter().map(|size| total * size / rows).collect::<Vec<_>>();
+    let mut remainders = sizes
+        .iter()
+        .enumerate()
+        .map(|(i, size)| (total * size % rows, i))
+        .collect::<Vec<_>>();
+    remainders.sort_by(|a, b| b.cmp(a));
+    let left = total - shares.iter().sum::<usize>();
+    for (_, i) in remainders.into_iter().take(left) {
+        shares[i] += 1;
+    }
+
+    shares
+}
+
+/// Samples rows from every distinct value of the top level `column`, keeping one reservoir
+/// per value while streaming that column only. Values are compared as printed.
+pub fn sample_strata<R: Rng>(
+    file: File,
+    column: &str,
+    allocation: Allocation,
+    max_strata: usize,
+    timestamps: &TimestampOptions,
+    rng: &mut R,
+) -> Result<(Vec<Range<usize>>, Vec<Stratum>), PQRSError> {
+    let arrow_reader = ArrowReaderBuilder::try_new(file)?;
+    let root_index = arrow_reader
+        .parquet_schema()
+        .root_schema()
+        .get_fields()
+        .iter()
+        .position(|f| f.name() == column)
+        .ok_or_else(|| UnknownColumn(column.to_string()))?;
+    let renderer = TimestampRenderer::new(
+        timestamps,
+        arrow_reader.metadata().file_metadata().schema_descr_ptr());
+    let num_rows = arrow_reader.metadata().file_metadata().num_rows().max(0) as usize;
+    let mask = ProjectionMask::roots(arrow_reader.parquet_schema(), vec![root_index]);
+    let batch_reader = arrow_reader.with_projection(mask).with_batch_size(8192).build()?;
+
+    // no stratum holds more rows than the file, each reservoir grows with the rows of its value
+    let capacity = match allocation {
+        Allocation::PerStratum(records) | Allocation::Proportional(records) => records.min(num_rows),
+    };
+    let mut reservoirs: HashMap<Option<String>, Reservoir<usize>> = HashMap::new();
+    let mut index = 0;
+    for maybe_batch in batch_reader {
+        let batch = renderer.render_batch(&maybe_batch?)?;
+        let values = cast(batch.column(0), &DataType::Utf8)?;
+        let values = values.as_any().downcast_ref::<StringArray>().unwrap();
+        for value in values.iter() {
+            let key = value.map(|v| v.to_string());
+            if !reservoirs.contains_key(&key) && reservoirs.len() == max_strata {
+                return Err(TooManyStrata(max_strata));
+            }
+            reservoirs
+                .entry(key)
+                .or_insert_with(|| Reservoir::new(capacity))
+                .offer(index, rng);
+            index += 1;
+        }
+    }
+
+    let mut strata = reservoirs.into_iter().collect::<Vec<_>>();
+    strata.sort_by(|a, b| a.0.cmp(&b.0));
+    let shares = match allocation {
+        Allocation::PerStratum(records) => vec![records; strata.len()],
+        Allocation::Proportional(records) => {
+            allocate(records, &strata.iter().map(|(_, r)| r.seen()).collect::<Vec<_>>())
+        }
+    };
+
+    let mut indexes = vec![];
+    let mut report = vec![];
+    for ((value, reservoir), share) in strata.into_iter().zip(shares) {
+        let rows = reservoir.seen();
+        // a random subset of a uniform sample is a uniform sample too
+        let mut items = reservoir.into_items();
+        items.shuffle(rng);
+        items.truncate(share);
+        report.push(Stratum {
+            value: value.unwrap_or_else(|| String::from("null")),
+            rows,
+            sampled: items.len(),
+        });
+        indexes.extend(items);
+    }
+    indexes.sort_unstable();
+
+    Ok((to_ranges(&indexes), report))
+}
@@ -0,0 +1,626 @@
+use std::f64::consts::PI;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow::array::{
+    make_array, Array, ArrayData, ArrayRef, BinaryArray, BooleanArray, FixedSizeBinaryArray, Float64Array,
+    Int64Array, StringArray, UInt32Array,
+};
+use arrow::buffer::Buffer;
+use arrow::compute::{cast, take};
+use arrow::datatypes::{DataType, FieldRef, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{InvalidGenerator, UnknownColumn};
+
+/// A setting of one column given as COLUMN=VALUE. Nested columns are named by their path, the
+/// elements of a list as `element` and the entries of a map as `key` and `value`, such as
+/// `point.x`, `tags.element` or `attrs.key`.
+#[derive(Clone, Debug)]
+pub struct ColumnSetting<T> {
+    pub column: String,
+    pub value: T,
+}
+
+impl<T: FromStr> FromStr for ColumnSetting<T>
+where
+    T::Err: Display,
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (column, value) = s.split_once('=').ok_or_else(|| format!("expected COLUMN=VALUE, got {}", s))?;
+        Ok(ColumnSetting {
+            column: column.trim().to_string(),
+            value: value.trim().parse().map_err(|e: T::Err| e.to_string())?,
+        })
+    }
+}
+
+/// How the values of a column are drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Generator {
+    /// Between two bounds, numbers or dates, times and timestamps for the temporal columns.
+    Uniform(String, String),
+    Normal(f64, f64),
+    /// From a start, increasing by a step for each row.
+    Sequence(f64, f64),
+    /// A text where `#` stands for a digit, `?` for a letter and `*` for either.
+    Pattern(String),
+    Choice(Vec<String>),
+}
+
+impl Generator {
+    fn name(&self) -> &'static str {
+        match self {
+            Generator::Uniform(_, _) => "uniform",
+            Generator::Normal(_, _) => "normal",
+            Generator::Sequence(_, _) => "sequence",
+            Generator::Pattern(_) => "pattern",
+            Generator::Choice(_) => "choice",
+        }
+    }
+}
+
+// splits name(arguments)
+fn call(s: &str) -> Option<(&str, &str)> {
+    let (name, arguments) = s.trim().split_once('(')?;
+    Some((name.trim(), arguments.strip_suffix(')')?))
+}
+
+fn number(s: &str) -> Result<f64, String> {
+    s.trim().parse().map_err(|_| format!("{} is not a number", s.trim()))
+}
+
+fn pair(arguments: &str) -> Result<(&str, &str), String> {
+    arguments
+        .split_once(',')
+        .map(|(a, b)| (a.trim(), b.trim()))
+        .ok_or_else(|| format!("expected two arguments, got {}", arguments))
+}
+
+fn normal(arguments: &str) -> Result<(f64, f64), String> {
+    let (mean, stddev) = pair(arguments)?;
+    let (mean, stddev) = (number(mean)?, number(stddev)?);
+    match stddev >= 0.0 {
+        true => Ok((mean, stddev)),
+        false => Err(format!("the standard deviation can not be negative, got {}", stddev)),
+    }
+}
+
+impl FromStr for Generator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match call(s) {
+            Some(("uniform", arguments)) => {
+                let (min, max) = pair(arguments)?;
+                Ok(Generator::Uniform(min.to_string(), max.to_string()))
+            }
+            Some(("normal", arguments)) => normal(arguments).map(|(mean, stddev)| Generator::Normal(mean, stddev)),
+            Some(("sequence", arguments)) => match arguments.split_once(',') {
+                Some((start, step)) => Ok(Generator::Sequence(number(start)?, number(step)?)),
+                None => Ok(Generator::Sequence(number(arguments)?, 1.0)),
+            },
+            Some(("pattern", pattern)) => Ok(Generator::Pattern(pattern.to_string())),
+            Some(("choice", choices)) => Ok(Generator::Choice(choices.split('|').map(String::from).collect())),
+            _ => Err(format!(
+                "unknown generator {}, expected uniform(MIN,MAX), normal(MEAN,STDDEV), sequence(START[,STEP]), \
+                 pattern(TEXT) or choice(A|B|...)",
+                s
+            )),
+        }
+    }
+}
+
+/// The number of elements of lists and maps, or of bytes of binary values.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Length {
+    Fixed(usize),
+    Uniform(usize, usize),
+    Normal(f64, f64),
+}
+
+impl Length {
+    fn draw(&self, rng: &mut StdRng) -> usize {
+        match *self {
+            Length::Fixed(length) => length,
+            Length::Uniform(min, max) => rng.gen_range(min..=max),
+            Length::Normal(mean, stddev) => (mean + stddev * standard_normal(rng)).round().clamp(0.0, i32::MAX as f64) as usize,
+        }
+    }
+}
+
+// the offset after a value of the given length, the offsets of arrow arrays are 32 bit
+fn next_offset(offset: i32, length: usize) -> Result<i32, PQRSError> {
+    i32::try_from(length).ok().and_then(|length| offset.checked_add(length)).ok_or_else(|| {
+        InvalidGenerator(format!(
+            "a batch holds more than {} list elements or bytes, lower the lengths or --batch-size",
+            i32::MAX
+        ))
+    })
+}
+
+impl FromStr for Length {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let count = |s: &str| {
+            s.trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|length| *length <= i32::MAX as usize)
+                .ok_or_else(|| format!("{} is not a length up to {}", s.trim(), i32::MAX))
+        };
+        match call(s) {
+            Some(("uniform", arguments)) => {
+                let (min, max) = pair(arguments)?;
+                match (count(min)?, count(max)?) {
+                    (min, max) if min <= max => Ok(Length::Uniform(min, max)),
+                    (min, max) => Err(format!("the minimum {} is greater than the maximum {}", min, max)),
+                }
+            }
+            Some(("normal", arguments)) => normal(arguments).map(|(mean, stddev)| Length::Normal(mean, stddev)),
+            Some(_) => Err(format!("unknown length {}, expected N, uniform(MIN,MAX) or normal(MEAN,STDDEV)", s)),
+            None => count(s).map(Length::Fixed),
+        }
+    }
+}
+
+/// How the values of the columns are generated, the settings are looked up by column path and
+/// the last one of a column wins.
+#[derive(Debug, Default)]
+pub struct GeneratorConfig {
+    /// Number of rows drawn in all, no column needs more distinct values.
+    pub rows: usize,
+    /// Fraction of nulls of the optional columns without a setting of their own.
+    pub null_rate: f64,
+    pub values: Vec<ColumnSetting<Generator>>,
+    pub nulls: Vec<ColumnSetting<f64>>,
+    pub distinct: Vec<ColumnSetting<usize>>,
+    pub lengths: Vec<ColumnSetting<Length>>,
+}
+
+fn setting<'a, T>(settings: &'a [ColumnSetting<T>], path: &str) -> Option<&'a T> {
+    settings.iter().rev().find(|s| s.column == path).map(|s| &s.value)
+}
+
+// a draw of the standard normal distribution, by the Box-Muller transform
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+// the kinds of leaf columns, with the range of values their type holds
+enum Kind {
+    Boolean,
+    Integer { min: i64, max: i64 },
+    Float { min: f64, max: f64 },
+    Text,
+    Bytes(Option<i32>),
+}
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+fn per_second(unit: &TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Second => 1,
+        TimeUnit::Millisecond => 1_000,
+        TimeUnit::Microsecond => 1_000_000,
+        TimeUnit::Nanosecond => 1_000_000_000,
+    }
+}
+
+fn kind(data_type: &DataType) -> Option<Kind> {
+    let integer = |min: i64, max: i64| Some(Kind::Integer { min, max });
+    match data_type {
+        DataType::Boolean => Some(Kind::Boolean),
+        DataType::Int8 => integer(i8::MIN.into(), i8::MAX.into()),
+        DataType::Int16 => integer(i16::MIN.into(), i16::MAX.into()),
+        DataType::Int32 | DataType::Date32 => integer(i32::MIN.into(), i32::MAX.into()),
+        DataType::Int64 | DataType::Date64 | DataType::Timestamp(_, _) => integer(i64::MIN, i64::MAX),
+        DataType::UInt8 => integer(0, u8::MAX.into()),
+        DataType::UInt16 => integer(0, u16::MAX.into()),
+        DataType::UInt32 => integer(0, u32::MAX.into()),
+        DataType::UInt64 => integer(0, i64::MAX),
+        DataType::Time32(unit) | DataType::Time64(unit) => integer(0, SECONDS_PER_DAY * per_second(unit) - 1),
+        DataType::Float32 => Some(Kind::Float { min: f32::MIN.into(), max: f32::MAX.into() }),
+        DataType::Float64 => Some(Kind::Float { min: f64::MIN, max: f64::MAX }),
+        DataType::Decimal128(precision, scale) | DataType::Decimal256(precision, scale) => {
+            let max = 10f64.powi(*precision as i32 - *scale as i32) - 10f64.powi(-(*scale as i32));
+            Some(Kind::Float { min: -max, max })
+        }
+        DataType::Utf8 | DataType::LargeUtf8 => Some(Kind::Text),
+        DataType::Binary | DataType::LargeBinary => Some(Kind::Bytes(None)),
+        DataType::FixedSizeBinary(size) => Some(Kind::Bytes(Some(*size))),
+        _ => None,
+    }
+}
+
+// a bound of a uniform generator, temporal columns also take dates, times and timestamps
+// which are counted in the unit of the column, timestamps as UTC
+fn bound(data_type: &DataType, text: &str) -> Option<f64> {
+    if let Ok(number) = text.parse::<f64>() {
+        return Some(number);
+    }
+    let datetime = text
+        .parse::<NaiveDateTime>()
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f").ok())
+        .or_else(|| text.parse::<NaiveDate>().ok().and_then(|d| d.and_hms_opt(0, 0, 0)));
+    let in_unit = |seconds: i64, nanos: u32, unit: &TimeUnit| {
+        (seconds * per_second(unit) + nanos as i64 / (1_000_000_000 / per_second(unit))) as f64
+    };
+    match data_type {
+        DataType::Date32 => datetime.map(|d| d.and_utc().timestamp().div_euclid(SECONDS_PER_DAY) as f64),
+        DataType::Date64 => datetime.map(|d| d.and_utc().timestamp_millis() as f64),
+        DataType::Timestamp(unit, _) => {
+            datetime.map(|d| in_unit(d.and_utc().timestamp(), d.and_utc().timestamp_subsec_nanos(), unit))
+        }
+        DataType::Time32(unit) | DataType::Time64(unit) => text
+            .parse::<NaiveTime>()
+            .ok()
+            .map(|t| in_unit(t.num_seconds_from_midnight().into(), t.nanosecond(), unit)),
+        _ => None,
+    }
+}
+
+// how the values of a leaf are drawn, resolved for its type
+enum Values {
+    Coin,
+    Uniform(f64, f64),
+    Normal(f64, f64),
+    Sequence(f64, f64),
+    Pattern(Vec<char>),
+    Choice(ArrayRef),
+    Bytes(Length),
+}
+
+fn values(path: &str, data_type: &DataType, kind: &Kind, generator: Option<&Generator>) -> Result<Values, PQRSError> {
+    let (min, max) = match *kind {
+        Kind::Integer { min, max } => (min as f64, max as f64),
+        Kind::Float { min, max } => (min, max),
+        _ => (f64::MIN, f64::MAX),
+    };
+    let temporal_default = |from: &str, to: &str| Values::Uniform(bound(data_type, from).unwrap(), bound(data_type, to).unwrap());
+    let values = match (generator, kind) {
+        (None, Kind::Boolean) => Values::Coin,
+        (None, Kind::Integer { .. } | Kind::Float { .. }) => match data_type {
+            DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _) => temporal_default("2020-01-01", "2025-01-01"),
+            DataType::Time32(_) | DataType::Time64(_) => Values::Uniform(0.0, max),
+            _ => Values::Uniform(0.0, max.min(1000.0)),
+        },
+        (None, Kind::Text) => Values::Pattern("????????".chars().collect()),
+        (None, Kind::Bytes(size)) => Values::Bytes(Length::Fixed(size.unwrap_or(16) as usize)),
+        (Some(Generator::Choice(choices)), _) => {
+            let choices = cast(&StringArray::from(choices.clone()), data_type)?;
+            if choices.null_count() > 0 {
+                return Err(InvalidGenerator(format!("the choices of column {} are not all of type {}", path, data_type)));
+            }
+            Values::Choice(choices)
+        }
+        (Some(Generator::Uniform(from, to)), Kind::Integer { .. } | Kind::Float { .. }) => {
+            let bound = |text: &str| {
+                bound(data_type, text)
+                    .ok_or_else(|| InvalidGenerator(format!("{} is not a bound of column {} of type {}", text, path, data_type)))
+            };
+            let (from, to) = (bound(from)?.max(min), bound(to)?.min(max));
+            let (from, to) = match kind {
+                Kind::Integer { .. } => (from.ceil(), to.floor()),
+                _ => (from, to),
+            };
+            if from > to {
+                return Err(InvalidGenerator(format!("the range of column {} holds no value of type {}", path, data_type)));
+            }
+            // the uniform sampling needs the width of the range
+            if !(to - from).is_finite() {
+                return Err(InvalidGenerator(format!("the range of column {} is too wide to draw values of type {}", path, data_type)));
+            }
+            Values::Uniform(from, to)
+        }
+        (Some(Generator::Normal(mean, stddev)), Kind::Integer { .. } | Kind::Float { .. }) => Values::Normal(*mean, *stddev),
+        (Some(Generator::Sequence(start, step)), Kind::Integer { .. } | Kind::Float { .. }) => Values::Sequence(*start, *step),
+        (Some(Generator::Pattern(pattern)), Kind::Text) => Values::Pattern(pattern.chars().collect()),
+        (Some(generator), _) => {
+            return Err(InvalidGenerator(format!(
+                "{} does not apply to column {} of type {}",
+                generator.name(),
+                path,
+                data_type
+            )))
+        }
+    };
+    Ok(values)
+}
+
+const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const ALPHANUMERIC: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+fn pattern(pattern: &[char], rng: &mut StdRng) -> String {
+    pattern
+        .iter()
+        .map(|c| match c {
+            '#' => char::from(b'0' + rng.gen_range(0..10)),
+            '?' => char::from(LETTERS[rng.gen_range(0..LETTERS.len())]),
+            '*' => char::from(ALPHANUMERIC[rng.gen_range(0..ALPHANUMERIC.len())]),
+            c => *c,
+        })
+        .collect()
+}
+
+enum Content {
+    Leaf(Kind, Values),
+    Struct(Vec<Column>),
+    List(Length, Box<Column>),
+    /// The entries of a map, a struct of its keys and values.
+    Map(Length, Box<Column>),
+}
+
+struct Column {
+    data_type: DataType,
+    null_rate: f64,
+    distinct: Option<usize>,
+    /// The distinct values to pick from, drawn with the first batch.
+    pool: Option<ArrayRef>,
+    content: Content,
+}
+
+const DEFAULT_LENGTH: Length = Length::Uniform(0, 5);
+
+impl Column {
+    fn new(field: &FieldRef, path: String, config: &GeneratorConfig, paths: &mut Vec<String>) -> Result<Self, PQRSError> {
+        let data_type = field.data_type().clone();
+        let generator = setting(&config.values, &path);
+        let length = setting(&config.lengths, &path).copied();
+        let content = match &data_type {
+            DataType::Struct(fields) => Content::Struct(
+                fields
+                    .iter()
+                    .map(|f| Column::new(f, format!("{}.{}", path, f.name()), config, paths))
+                    .collect::<Result<_, _>>()?,
+            ),
+            DataType::List(element) => Content::List(
+                length.unwrap_or(DEFAULT_LENGTH),
+                Box::new(Column::new(element, format!("{}.element", path), config, paths)?),
+            ),
+            DataType::Map(entries, _) => {
+                let DataType::Struct(fields) = entries.data_type() else {
+                    return Err(InvalidGenerator(format!("the entries of map {} are not a struct", path)));
+                };
+                let children = fields
+                    .iter()
+                    .zip(["key", "value"])
+                    .map(|(f, name)| Column::new(f, format!("{}.{}", path, name), config, paths))
+                    .collect::<Result<_, _>>()?;
+                let entries = Column {
+                    data_type: entries.data_type().clone(),
+                    null_rate: 0.0,
+                    distinct: None,
+                    pool: None,
+                    content: Content::Struct(children),
+                };
+                Content::Map(length.unwrap_or(DEFAULT_LENGTH), Box::new(entries))
+            }
+            _ => {
+                let kind = kind(&data_type)
+                    .ok_or_else(|| InvalidGenerator(format!("column {} of type {} is not supported", path, data_type)))?;
+                let values = match (&kind, length) {
+                    (Kind::Bytes(None), Some(length)) => Values::Bytes(length),
+                    (_, Some(_)) => {
+                        return Err(InvalidGenerator(format!(
+                            "column {} has no length, only lists, maps and binary columns do",
+                            path
+                        )))
+                    }
+                    (_, None) => values(&path, &data_type, &kind, generator)?,
+                };
+                Content::Leaf(kind, values)
+            }
+        };
+        if generator.is_some() && !matches!(content, Content::Leaf(_, _)) {
+            return Err(InvalidGenerator(format!("column {} is nested, set the values of its leaves", path)));
+        }
+        if length.is_some() && matches!(content, Content::Struct(_)) {
+            return Err(InvalidGenerator(format!("column {} has no length, only lists, maps and binary columns do", path)));
+        }
+
+        let null_rate = match setting(&config.nulls, &path) {
+            Some(rate) if !(0.0..=1.0).contains(rate) => {
+                return Err(InvalidGenerator(format!("the null rate of column {} must be between 0 and 1, got {}", path, rate)))
+            }
+            Some(rate) if *rate > 0.0 && !field.is_nullable() => {
+                return Err(InvalidGenerator(format!("column {} is required, it can not have nulls", path)))
+            }
+            Some(rate) => *rate,
+            None if field.is_nullable() => config.null_rate,
+            None => 0.0,
+        };
+        let distinct = match setting(&config.distinct, &path) {
+            Some(0) => return Err(InvalidGenerator(format!("column {} needs at least one distinct value", path))),
+            // the values are picked by u32 indices
+            Some(&distinct) if distinct > u32::MAX as usize => {
+                return Err(InvalidGenerator(format!("column {} can have at most {} distinct values", path, u32::MAX)))
+            }
+            distinct => distinct.map(|&d| d.min(config.rows.max(1))),
+        };
+        paths.push(path);
+
+        Ok(Column { data_type, null_rate, distinct, pool: None, content })
+    }
+
+    fn generate(&mut self, len: usize, rng: &mut StdRng) -> Result<ArrayRef, PQRSError> {
+        let validity = (self.null_rate > 0.0).then(|| (0..len).map(|_| rng.gen::<f64>() >= self.null_rate).collect::<Vec<_>>());
+        let Some(distinct) = self.distinct else {
+            return self.values(len, validity, rng);
+        };
+        if self.pool.is_none() {
+            self.pool = Some(self.values(distinct, None, rng)?);
+        }
+        let indices = (0..len)
+            .map(|i| validity.as_ref().is_none_or(|v| v[i]).then(|| rng.gen_range(0..distinct) as u32))
+            .collect::<UInt32Array>();
+        Ok(take(self.pool.as_ref().unwrap(), &indices, None)?)
+    }
+
+    fn values(&mut self, len: usize, validity: Option<Vec<bool>>, rng: &mut StdRng) -> Result<ArrayRef, PQRSError> {
+        let valid = |i: usize| validity.as_ref().is_none_or(|v| v[i]);
+        let nulls = validity.as_ref().map(|v| v.iter().copied().collect::<Buffer>());
+        let (length, child) = match &mut self.content {
+            Content::Leaf(kind, values) => return leaf(&self.data_type, kind, values, len, valid, rng),
+            Content::Struct(children) => {
+                let children = children
+                    .iter_mut()
+                    .map(|c| c.generate(len, rng).map(|array| array.to_data()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let data = ArrayData::builder(self.data_type.clone())
+                    .len(len)
+                    .null_bit_buffer(nulls)
+                    .child_data(children)
+                    .build()?;
+                return Ok(make_array(data));
+            }
+            Content::List(length, child) | Content::Map(length, child) => (*length, child),
+        };
+        let mut offsets = Vec::with_capacity(len + 1);
+        offsets.push(0i32);
+        for i in 0..len {
+            let length = if valid(i) { length.draw(rng) } else { 0 };
+            offsets.push(next_offset(offsets[i], length)?);
+        }
+        let child = child.generate(offsets[len] as usize, rng)?;
+        let data = ArrayData::builder(self.data_type.clone())
+            .len(len)
+            .null_bit_buffer(nulls)
+            .add_buffer(Buffer::from_slice_ref(&offsets))
+            .add_child_data(child.to_data())
+            .build()?;
+        Ok(make_array(data))
+    }
+}
+
+fn leaf(
+    data_type: &DataType,
+    kind: &Kind,
+    values: &mut Values,
+    len: usize,
+    valid: impl Fn(usize) -> bool,
+    rng: &mut StdRng,
+) -> Result<ArrayRef, PQRSError> {
+    let numbers = |rng: &mut StdRng, values: &mut Values| {
+        (0..len)
+            .map(|_| match values {
+                Values::Uniform(min, max) => match kind {
+                    Kind::Integer { .. } => rng.gen_range(*min as i64..=*max as i64) as f64,
+                    _ => rng.gen_range(*min..=*max),
+                },
+                Values::Normal(mean, stddev) => *mean + *stddev * standard_normal(rng),
+                Values::Sequence(next, step) => {
+                    *next += *step;
+                    *next - *step
+                }
+                _ => unreachable!("only numeric values are drawn as numbers"),
+            })
+            .collect::<Vec<_>>()
+    };
+    let array: ArrayRef = match values {
+        Values::Coin => Arc::new((0..len).map(|i| valid(i).then(|| rng.gen_bool(0.5))).collect::<BooleanArray>()),
+        Values::Uniform(_, _) | Values::Normal(_, _) | Values::Sequence(_, _) => match *kind {
+            Kind::Integer { min, max } => {
+                let numbers = numbers(rng, values);
+                let array = (0..len)
+                    .map(|i| valid(i).then(|| (numbers[i].round() as i64).clamp(min, max)))
+                    .collect::<Int64Array>();
+                match data_type {
+                    // the 32 bit temporal types are only cast from 32 bit integers
+                    DataType::Date32 | DataType::Time32(_) => cast(&cast(&array, &DataType::Int32)?, data_type)?,
+                    _ => cast(&array, data_type)?,
+                }
+            }
+            Kind::Float { min, max } => {
+                let numbers = numbers(rng, values);
+                let array = (0..len).map(|i| valid(i).then(|| numbers[i].clamp(min, max))).collect::<Float64Array>();
+                cast(&array, data_type)?
+            }
+            _ => unreachable!("numeric values are only resolved for numeric columns"),
+        },
+        Values::Pattern(chars) => {
+            let array = (0..len).map(|i| valid(i).then(|| pattern(chars, rng))).collect::<StringArray>();
+            cast(&array, data_type)?
+        }
+        Values::Choice(choices) => {
+            let indices = (0..len)
+                .map(|i| valid(i).then(|| rng.gen_range(0..choices.len()) as u32))
+                .collect::<UInt32Array>();
+            take(choices, &indices, None)?
+        }
+        Values::Bytes(length) => {
+            let mut offset = 0;
+            let mut bytes = Vec::with_capacity(len);
+            for i in 0..len {
+                let value = match valid(i) {
+                    true => {
+                        let length = length.draw(rng);
+                        offset = next_offset(offset, length)?;
+                        Some((0..length).map(|_| rng.gen::<u8>()).collect::<Vec<_>>())
+                    }
+                    false => None,
+                };
+                bytes.push(value);
+            }
+            match *kind {
+                Kind::Bytes(Some(size)) => Arc::new(FixedSizeBinaryArray::try_from_sparse_iter_with_size(bytes.into_iter(), size)?),
+                _ => cast(&bytes.iter().map(|b| b.as_deref()).collect::<BinaryArray>(), data_type)?,
+            }
+        }
+    };
+    Ok(array)
+}
+
+/// Draws batches of rows of a schema.
+pub struct RowGenerator {
+    schema: SchemaRef,
+    columns: Vec<Column>,
+    rng: StdRng,
+}
+
+impl RowGenerator {
+    pub fn new(schema: SchemaRef, config: &GeneratorConfig, rng: StdRng) -> Result<Self, PQRSError> {
+        if !(0.0..=1.0).contains(&config.null_rate) {
+            return Err(InvalidGenerator(format!("the null rate must be between 0 and 1, got {}", config.null_rate)));
+        }
+        let mut paths = vec![];
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|f| Column::new(f, f.name().to_string(), config, &mut paths))
+            .collect::<Result<Vec<_>, _>>()?;
+        let columns_set = config
+            .values
+            .iter()
+            .map(|s| &s.column)
+            .chain(config.nulls.iter().map(|s| &s.column))
+            .chain(config.distinct.iter().map(|s| &s.column))
+            .chain(config.lengths.iter().map(|s| &s.column));
+        for column in columns_set {
+            if !paths.contains(column) {
+                return Err(UnknownColumn(column.to_string()));
+            }
+        }
+        Ok(RowGenerator { schema, columns, rng })
+    }
+
+    pub fn batch(&mut self, rows: usize) -> Result<RecordBatch, PQRSError> {
+        let columns = self
+            .columns
+            .iter_mut()
+            .map(|c| c.generate(rows, &mut self.rng))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
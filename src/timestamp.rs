@@ -0,0 +1,565 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BinaryArray, Int64Array, StringArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field as ArrowField, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use clap::Args;
+use parquet::basic::{LogicalType, Repetition, TimeUnit as ParquetTimeUnit};
+use parquet::record::{Field, Row};
+use parquet::schema::types::{SchemaDescPtr, Type};
+use serde_json::Value;
+
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{InvalidTimestampFormat, UnknownTimezone};
+use crate::geo::GeometryFormat;
+
+static ISO_8601_UTC: &str = "%Y-%m-%dT%H:%M:%S%.f%:z";
+static ISO_8601_LOCAL: &str = "%Y-%m-%dT%H:%M:%S%.f";
+static ISO_8601_DATE: &str = "%Y-%m-%d";
+static ISO_8601_TIME: &str = "%H:%M:%S%.f";
+// how the row reader prints the timestamps it knows
+static READER_TIMESTAMP: &str = "%Y-%m-%d %H:%M:%S %:z";
+
+// timestamp related options shared by every command printing rows
+#[derive(Args, Debug, Default)]
+pub struct TimestampArgs {
+    #[arg(short, long, conflicts_with = "timestamp_format", help = "print timestamps, dates and times as their raw stored value")]
+    raw_timestamp: bool,
+    #[arg(long, value_name = "FORMAT", help = "print timestamps as `iso8601` or with a strftime pattern such as \"%Y-%m-%d %H:%M\"")]
+    timestamp_format: Option<String>,
+    #[arg(long, value_name = "TZ", help = "convert UTC adjusted timestamps to the given IANA timezone, e.g. Asia/Shanghai")]
+    timezone: Option<String>,
+}
+
+impl TimestampArgs {
+    pub fn options(&self) -> Result<TimestampOptions, PQRSError> {
+        let style = if self.raw_timestamp {
+            TimestampStyle::Raw
+        } else {
+            match self.timestamp_format.as_deref() {
+                None => TimestampStyle::Default,
+                Some(format) if format.eq_ignore_ascii_case("iso8601") => TimestampStyle::Iso8601,
+                Some(pattern) => {
+                    if StrftimeItems::new(pattern).any(|item| item == Item::Error) {
+                        return Err(InvalidTimestampFormat(pattern.to_string()));
+                    }
+                    TimestampStyle::Pattern(pattern.to_string())
+                }
+            }
+        };
+
+        let timezone = match &self.timezone {
+            Some(name) => Some(
+                name.parse::<Tz>()
+                    .map_err(|_| UnknownTimezone(name.to_string()))?,
+            ),
+            None => None,
+        };
+
+        Ok(TimestampOptions { style, timezone })
+    }
+}
+
+/// How timestamps, dates and times are written to the output.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum TimestampStyle {
+    /// Keep the rendering of the underlying reader.
+    #[default]
+    Default,
+    /// The stored integer: epoch value in the column's unit, days or time of day.
+    Raw,
+    Iso8601,
+    /// A strftime pattern, applied to timestamps and, when possible, dates and times.
+    Pattern(String),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TimestampOptions {
+    pub style: TimestampStyle,
+    /// Timezone UTC adjusted timestamps are converted to, UTC when not given.
+    pub timezone: Option<Tz>,
+}
+
+impl TimestampOptions {
+    pub fn is_default(&self) -> bool {
+        self.style == TimestampStyle::Default && self.timezone.is_none()
+    }
+
+    // asking for a timezone only makes sense with a readable output
+    fn effective_style(&self) -> &TimestampStyle {
+        match self.style {
+            TimestampStyle::Default => &TimestampStyle::Iso8601,
+            ref style => style,
+        }
+    }
+
+    pub fn format(&self, value: i64, temporal: &Temporal) -> String {
+        match temporal {
+            Temporal::Timestamp(unit, utc_adjusted) => {
+                self.format_timestamp(value, unit, *utc_adjusted)
+            }
+            Temporal::Date => self.format_date(value),
+            Temporal::Time(unit) => self.format_time(value, unit),
+        }
+    }
+
+    fn format_timestamp(&self, value: i64, unit: &TimeUnit, utc_adjusted: bool) -> String {
+        let pattern = match self.effective_style() {
+            TimestampStyle::Raw => return value.to_string(),
+            TimestampStyle::Pattern(pattern) => pattern.as_str(),
+            _ if utc_adjusted => ISO_8601_UTC,
+            _ => ISO_8601_LOCAL,
+        };
+
+        let (seconds, nanos) = split_seconds(value, unit);
+        let datetime = match DateTime::<Utc>::from_timestamp(seconds, nanos) {
+            Some(datetime) => datetime,
+            None => return value.to_string(),
+        };
+
+        let mut output = String::new();
+        let written = match (utc_adjusted, self.timezone) {
+            (true, Some(tz)) => write!(output, "{}", datetime.with_timezone(&tz).format(pattern)),
+            (true, None) => write!(output, "{}", datetime.format(pattern)),
+            // timestamps with local semantics are not an instant, so no conversion applies
+            (false, _) => write!(output, "{}", datetime.naive_utc().format(pattern)),
+        };
+
+        match written {
+            Ok(_) => output,
+            Err(_) => value.to_string(),
+        }
+    }
+
+    fn format_date(&self, days: i64) -> String {
+        let date = NaiveDate::from_ymd_opt(1970, 1, 1)
+            .and_then(|epoch| epoch.checked_add_signed(chrono::Duration::days(days)));
+        match (self.effective_style(), date) {
+            (TimestampStyle::Raw, _) | (_, None) => days.to_string(),
+            (TimestampStyle::Pattern(pattern), Some(date)) => {
+                let mut output = String::new();
+                match write!(output, "{}", date.format(pattern)) {
+                    Ok(_) => output,
+                    Err(_) => date.format(ISO_8601_DATE).to_string(),
+                }
+            }
+            (_, Some(date)) => date.format(ISO_8601_DATE).to_string(),
+        }
+    }
+
+    fn format_time(&self, value: i64, unit: &TimeUnit) -> String {
+        let (seconds, nanos) = split_seconds(value, unit);
+        let time = u32::try_from(seconds)
+            .ok()
+            .and_then(|seconds| NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanos));
+        match (self.effective_style(), time) {
+            (TimestampStyle::Raw, _) | (_, None) => value.to_string(),
+            (TimestampStyle::Pattern(pattern), Some(time)) => {
+                let mut output = String::new();
+                match write!(output, "{}", time.format(pattern)) {
+                    Ok(_) => output,
+                    Err(_) => time.format(ISO_8601_TIME).to_string(),
+                }
+            }
+            (_, Some(time)) => time.format(ISO_8601_TIME).to_string(),
+        }
+    }
+}
+
+/// The kind of a temporal value stored as an integer.
+pub enum Temporal {
+    /// Epoch value in the given unit, and whether it is an instant adjusted to UTC.
+    Timestamp(TimeUnit, bool),
+    /// Days since the epoch.
+    Date,
+    /// Time of day in the given unit.
+    Time(TimeUnit),
+}
+
+fn split_seconds(value: i64, unit: &TimeUnit) -> (i64, u32) {
+    let per_second = units_per_second(unit);
+    let nanos = value.rem_euclid(per_second) * (1_000_000_000 / per_second);
+    (value.div_euclid(per_second), nanos as u32)
+}
+
+fn units_per_second(unit: &TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Second => 1,
+        TimeUnit::Millisecond => 1_000,
+        TimeUnit::Microsecond => 1_000_000,
+        TimeUnit::Nanosecond => 1_000_000_000,
+    }
+}
+
+fn from_parquet_unit(unit: &ParquetTimeUnit) -> TimeUnit {
+    match unit {
+        ParquetTimeUnit::MILLIS(_) => TimeUnit::Millisecond,
+        ParquetTimeUnit::MICROS(_) => TimeUnit::Microsecond,
+        ParquetTimeUnit::NANOS(_) => TimeUnit::Nanosecond,
+    }
+}
+
+// legacy converted types and INT96 carry no flag, writers using them store instants
+fn is_utc_adjusted(column: Option<&Type>) -> bool {
+    match column.and_then(|c| c.get_basic_info().logical_type()) {
+        Some(LogicalType::Timestamp { is_adjusted_to_u_t_c, .. }) => is_adjusted_to_u_t_c,
+        _ => true,
+    }
+}
+
+/// A value rendered for output: the raw number or a text for temporal values, a JSON value
+/// for geometries.
+enum Rendered {
+    Raw(i64),
+    Text(String),
+    Json(Value),
+}
+
+/// Renders the temporal values of rows and record batches read from one file, and their WKB
+/// geometries when asked to.
+pub struct TimestampRenderer {
+    options: TimestampOptions,
+    schema: SchemaDescPtr,
+    geometries: Option<(GeometryFormat, Vec<String>)>,
+    /// Whether the file has temporal columns the row reader gives as plain numbers.
+    plain_temporals: bool,
+}
+
+impl TimestampRenderer {
+    pub fn new(options: &TimestampOptions, schema: SchemaDescPtr) -> Self {
+        let plain_temporals = schema.columns().iter().any(|c| {
+            matches!(
+                c.logical_type(),
+                Some(LogicalType::Timestamp { unit: ParquetTimeUnit::NANOS(_), .. } | LogicalType::Time { .. })
+            )
+        });
+        Self { options: options.clone(), schema, geometries: None, plain_temporals }
+    }
+
+    /// Renders the WKB values of these top level columns in the given format.
+    pub fn with_geometries(mut self, format: Option<GeometryFormat>, columns: Vec<String>) -> Self {
+        self.geometries = format.filter(|_| !columns.is_empty()).map(|format| (format, columns));
+        self
+    }
+
+    fn is_default(&self) -> bool {
+        self.options.is_default() && self.geometries.is_none() && !self.plain_temporals
+    }
+
+    // the format of a top level geometry column
+    fn geometry_format(&self, column: Option<&Type>) -> Option<GeometryFormat> {
+        let (format, columns) = self.geometries.as_ref()?;
+        let column = column?;
+        let is_root = self.schema.root_schema().get_fields().iter().any(|f| std::ptr::eq(f.as_ref(), column));
+        (is_root && columns.iter().any(|c| c == column.name())).then_some(*format)
+    }
+
+    fn render_leaf(&self, field: &Field, column: Option<&Type>) -> Option<Rendered> {
+        if let (Field::Bytes(bytes), Some(format)) = (field, self.geometry_format(column)) {
+            return Some(Rendered::Json(format.render_json(bytes.data())));
+        }
+        let logical_type = column.and_then(|c| c.get_basic_info().logical_type());
+        if self.options.is_default() {
+            // the row reader renders the timestamps it knows, the nanosecond ones and the
+            // times come as plain numbers
+            return match (field, logical_type) {
+                (Field::Long(v), Some(LogicalType::Timestamp { unit, .. })) => {
+                    let (seconds, _) = split_seconds(*v, &from_parquet_unit(&unit));
+                    DateTime::<Utc>::from_timestamp(seconds, 0)
+                        .map(|datetime| Rendered::Text(datetime.format(READER_TIMESTAMP).to_string()))
+                }
+                (Field::Long(v), Some(LogicalType::Time { unit, .. })) => {
+                    Some(Rendered::Text(self.options.format(*v, &Temporal::Time(from_parquet_unit(&unit)))))
+                }
+                (Field::Int(v), Some(LogicalType::Time { unit, .. })) => {
+                    Some(Rendered::Text(self.options.format(*v as i64, &Temporal::Time(from_parquet_unit(&unit)))))
+                }
+                _ => None,
+            };
+        }
+        let (value, temporal) = match (field, logical_type) {
+            (Field::TimestampMillis(v), _) => {
+                (*v, Temporal::Timestamp(TimeUnit::Millisecond, is_utc_adjusted(column)))
+            }
+            (Field::TimestampMicros(v), _) => {
+                (*v, Temporal::Timestamp(TimeUnit::Microsecond, is_utc_adjusted(column)))
+            }
+            (Field::Date(days), _) => (*days as i64, Temporal::Date),
+            // the row reader only knows the legacy converted types, nanoseconds come as longs
+            (Field::Long(v), Some(LogicalType::Timestamp { unit, is_adjusted_to_u_t_c })) => {
+                (*v, Temporal::Timestamp(from_parquet_unit(&unit), is_adjusted_to_u_t_c))
+            }
+            (Field::Long(v), Some(LogicalType::Time { unit, .. })) => {
+                (*v, Temporal::Time(from_parquet_unit(&unit)))
+            }
+            (Field::Int(v), Some(LogicalType::Time { unit, .. })) => {
+                (*v as i64, Temporal::Time(from_parquet_unit(&unit)))
+            }
+            _ => return None,
+        };
+
+        match self.options.effective_style() {
+            TimestampStyle::Raw => Some(Rendered::Raw(value)),
+            _ => Some(Rendered::Text(self.options.format(value, &temporal))),
+        }
+    }
+
+    /// Renders a row the same way `Row` implements `Display`.
+    pub fn format_row(&self, row: &Row) -> String {
+        if self.is_default() {
+            return row.to_string();
+        }
+        self.format_fields(&row.get_column_iter().collect::<Vec<_>>())
+    }
+
+    /// Renders a row the same way `Row::to_json_value` does.
+    pub fn row_to_json(&self, row: &Row) -> Value {
+        if self.is_default() {
+            return row.to_json_value();
+        }
+        self.fields_to_json(&row.get_column_iter().collect::<Vec<_>>())
+    }
+
+    /// Renders the top level fields of a row, like `format_row`.
+    pub fn format_fields(&self, fields: &[(&String, &Field)]) -> String {
+        let mut output = String::new();
+        self.write_group(&mut output, fields, Some(self.schema.root_schema()));
+        output
+    }
+
+    /// Renders the top level fields of a row, like `row_to_json`.
+    pub fn fields_to_json(&self, fields: &[(&String, &Field)]) -> Value {
+        self.group_to_json(fields, Some(self.schema.root_schema()))
+    }
+
+    /// The text of a top level value as printed, strings without their quotes.
+    pub fn field_text(&self, name: &str, field: &Field) -> String {
+        match self.render_leaf(field, child_type(Some(self.schema.root_schema()), name)) {
+            Some(Rendered::Raw(value)) => value.to_string(),
+            Some(Rendered::Text(text)) => text,
+            Some(Rendered::Json(Value::String(text))) => text,
+            Some(Rendered::Json(value)) => value.to_string(),
+            None => match field {
+                Field::Str(s) => s.to_owned(),
+                _ => field.to_string(),
+            },
+        }
+    }
+
+    fn write_group(&self, output: &mut String, fields: &[(&String, &Field)], group: Option<&Type>) {
+        output.push('{');
+        for (i, (name, field)) in fields.iter().enumerate() {
+            if i > 0 {
+                output.push_str(", ");
+            }
+            output.push_str(name);
+            output.push_str(": ");
+            self.write_field(output, field, child_type(group, name));
+        }
+        output.push('}');
+    }
+
+    fn write_field(&self, output: &mut String, field: &Field, column: Option<&Type>) {
+        match field {
+            Field::Group(row) => {
+                self.write_group(output, &row.get_column_iter().collect::<Vec<_>>(), column)
+            }
+            Field::ListInternal(list) => {
+                let element = list_element_type(column);
+                output.push('[');
+                for (i, element_field) in list.elements().iter().enumerate() {
+                    if i > 0 {
+                        output.push_str(", ");
+                    }
+                    self.write_field(output, element_field, element);
+                }
+                output.push(']');
+            }
+            Field::MapInternal(map) => {
+                let (key_type, value_type) = map_entry_types(column);
+                output.push('{');
+                for (i, (key, value)) in map.entries().iter().enumerate() {
+                    if i > 0 {
+                        output.push_str(", ");
+                    }
+                    self.write_field(output, key, key_type);
+                    output.push_str(" -> ");
+                    self.write_field(output, value, value_type);
+                }
+                output.push('}');
+            }
+            _ => match self.render_leaf(field, column) {
+                Some(Rendered::Raw(value)) => output.push_str(&value.to_string()),
+                Some(Rendered::Text(text)) => output.push_str(&text),
+                // quoted like the strings of rows
+                Some(Rendered::Json(Value::String(text))) => output.push_str(&format!("\"{}\"", text)),
+                Some(Rendered::Json(value)) => output.push_str(&value.to_string()),
+                None => output.push_str(&field.to_string()),
+            },
+        }
+    }
+
+    fn group_to_json(&self, fields: &[(&String, &Field)], group: Option<&Type>) -> Value {
+        Value::Object(
+            fields
+                .iter()
+                .map(|(name, field)| {
+                    (name.to_string(), self.field_to_json(field, child_type(group, name)))
+                })
+                .collect(),
+        )
+    }
+
+    fn field_to_json(&self, field: &Field, column: Option<&Type>) -> Value {
+        match field {
+            Field::Group(row) => {
+                self.group_to_json(&row.get_column_iter().collect::<Vec<_>>(), column)
+            }
+            Field::ListInternal(list) => {
+                let element = list_element_type(column);
+                Value::Array(
+                    list.elements()
+                        .iter()
+                        .map(|f| self.field_to_json(f, element))
+                        .collect(),
+                )
+            }
+            Field::MapInternal(map) => {
+                let (key_type, value_type) = map_entry_types(column);
+                Value::Object(
+                    map.entries()
+                        .iter()
+                        .map(|(key, value)| {
+                            let key = match self.field_to_json(key, key_type) {
+                                Value::String(s) => s,
+                                other => other.to_string(),
+                            };
+                            (key, self.field_to_json(value, value_type))
+                        })
+                        .collect(),
+                )
+            }
+            _ => match self.render_leaf(field, column) {
+                Some(Rendered::Raw(value)) => Value::from(value),
+                Some(Rendered::Text(text)) => Value::String(text),
+                Some(Rendered::Json(value)) => value,
+                None => field.to_json_value(),
+            },
+        }
+    }
+
+    /// Replaces the temporal and geometry columns of a batch with their rendered values.
+    pub fn render_batch(&self, batch: &RecordBatch) -> Result<RecordBatch, PQRSError> {
+        if self.is_default() {
+            return Ok(batch.clone());
+        }
+
+        let utc_adjusted: HashMap<&str, bool> = self
+            .schema
+            .root_schema()
+            .get_fields()
+            .iter()
+            .map(|f| (f.name(), is_utc_adjusted(Some(f))))
+            .collect();
+
+        let mut fields = Vec::with_capacity(batch.num_columns());
+        let mut columns = Vec::with_capacity(batch.num_columns());
+        for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+            let geometry = self
+                .geometries
+                .as_ref()
+                .filter(|(_, columns)| columns.contains(field.name()))
+                .map(|(format, _)| format);
+            if let (Some(format), Some(values)) = (geometry, column.as_any().downcast_ref::<BinaryArray>()) {
+                let strings: StringArray = values.iter().map(|v| v.map(|v| format.render_text(v))).collect();
+                fields.push(ArrowField::new(field.name(), DataType::Utf8, field.is_nullable()));
+                columns.push(Arc::new(strings) as ArrayRef);
+                continue;
+            }
+            if self.options.is_default() {
+                fields.push(field.as_ref().clone());
+                columns.push(column.clone());
+                continue;
+            }
+
+            let (raw_type, temporal) = match field.data_type() {
+                DataType::Timestamp(unit, tz) => {
+                    let adjusted = tz.is_some()
+                        || *utc_adjusted.get(field.name().as_str()).unwrap_or(&true);
+                    (DataType::Int64, Temporal::Timestamp(unit.clone(), adjusted))
+                }
+                DataType::Date32 => (DataType::Int32, Temporal::Date),
+                DataType::Time32(unit) => (DataType::Int32, Temporal::Time(unit.clone())),
+                DataType::Time64(unit) => (DataType::Int64, Temporal::Time(unit.clone())),
+                _ => {
+                    fields.push(field.as_ref().clone());
+                    columns.push(column.clone());
+                    continue;
+                }
+            };
+
+            let (data_type, rendered) = match self.options.effective_style() {
+                TimestampStyle::Raw => (raw_type.clone(), cast(column, &raw_type)?),
+                _ => {
+                    let values = cast(column, &DataType::Int64)?;
+                    let values = values.as_any().downcast_ref::<Int64Array>().unwrap();
+                    let strings: StringArray = values
+                        .iter()
+                        .map(|v| v.map(|v| self.options.format(v, &temporal)))
+                        .collect();
+                    (DataType::Utf8, Arc::new(strings) as ArrayRef)
+                }
+            };
+            fields.push(ArrowField::new(field.name(), data_type, field.is_nullable()));
+            columns.push(rendered);
+        }
+
+        Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?)
+    }
+}
+
+fn child_type<'a>(group: Option<&'a Type>, name: &str) -> Option<&'a Type> {
+    group
+        .filter(|g| g.is_group())
+        .and_then(|g| g.get_fields().iter().find(|f| f.name() == name))
+        .map(|f| f.as_ref())
+}
+
+// follows the backward compatibility rules of the parquet LIST specification
+fn list_element_type(column: Option<&Type>) -> Option<&Type> {
+    let column = column?;
+    let info = column.get_basic_info();
+    if column.is_primitive() || (info.has_repetition() && info.repetition() == Repetition::REPEATED) {
+        return Some(column);
+    }
+    let repeated = column.get_fields().first()?.as_ref();
+    if repeated.is_primitive()
+        || repeated.get_fields().len() > 1
+        || repeated.name() == "array"
+        || repeated.name() == format!("{}_tuple", column.name())
+    {
+        Some(repeated)
+    } else {
+        repeated.get_fields().first().map(|f| f.as_ref())
+    }
+}
+
+fn map_entry_types(column: Option<&Type>) -> (Option<&Type>, Option<&Type>) {
+    let key_value = column
+        .filter(|c| c.is_group())
+        .and_then(|c| c.get_fields().first())
+        .filter(|kv| kv.is_group());
+    match key_value {
+        Some(kv) => (
+            kv.get_fields().first().map(|f| f.as_ref()),
+            kv.get_fields().get(1).map(|f| f.as_ref()),
+        ),
+        None => (None, None),
+    }
+}
@@ -1,23 +1,48 @@
 use std::cmp::min;
+use std::collections::HashMap;
 use std::fmt::Formatter;
 use std::fs::File;
 use std::io::Read;
-use std::ops::Add;
+use std::ops::{Add, Range};
 use std::path::{Path};
+use std::str::FromStr;
+use std::sync::Arc;
 
-use arrow::{datatypes::Schema, record_batch::RecordBatch};
+use arrow::{datatypes::Schema, record_batch::{RecordBatch, RecordBatchReader}};
+use arrow::array::{Array, ArrayRef, BinaryArray, BooleanArray, Int32Array, Int64Array, StringArray};
+use arrow::compute::{and, cast, filter_record_batch};
 use arrow::csv;
+use arrow::datatypes::{DataType, Field as ArrowField};
+use clap::{Args, ValueEnum};
 use log::debug;
-use parquet::arrow::arrow_reader::ArrowReaderBuilder;
-use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::arrow::{ArrowWriter, ProjectionMask};
+use parquet::arrow::arrow_reader::{ArrowReaderBuilder, ArrowReaderOptions, ParquetRecordBatchReader, RowSelection, RowSelector};
+use parquet::basic::{BrotliLevel, Compression, ConvertedType, GzipLevel, LogicalType, Type as PhysicalType, ZstdLevel};
+use parquet::bloom_filter::Sbbf;
+use parquet::column::page::PageReader;
+use parquet::column::reader::ColumnReader;
+use parquet::file::metadata::{FileMetaData, ParquetMetaData, RowGroupMetaData};
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::{FileReader, RowGroupReader, SerializedFileReader};
+use parquet::file::serialized_reader::ReadOptionsBuilder;
+use parquet::record::reader::RowIter;
+use parquet::format::KeyValue;
 use parquet::record::{Field, Row};
-use rand::seq::SliceRandom;
-use rand::thread_rng;
+use parquet::schema::parser::parse_message_type;
+use parquet::schema::types::{SchemaDescriptor, Type, TypePtr};
 use tempfile::NamedTempFile;
 use walkdir::DirEntry;
 
-use crate::errors::PQRSError::{CouldNotOpenFile, UnsupportedOperation};
+use crate::bloom::{bloom_filter_check, bloom_filter_reader, prints_stored_value};
+use crate::embedded::ARROW_SCHEMA_KEY;
+use crate::errors::PQRSError::{
+    CouldNotOpenFile, FileExists, InvalidGeoMetadata, InvalidSchema, UnknownColumn, UnknownCompression, UnsupportedOperation,
+};
 use crate::errors::PQRSError;
+use crate::export::json_schema_fields;
+use crate::fields::message_type;
+use crate::geo::{covering_row_group_bbox, parse_wkb, read_geo_metadata, BoundingBox, CoveringBbox, GeoMetadata, GeometryFormat};
+use crate::timestamp::{TimestampOptions, TimestampRenderer};
 
 // can this be implement by enum, then implement format function for enum?
 static ONE_KI_B: i64 = 1024;
@@ -42,6 +67,163 @@ impl std::fmt::Display for Formats {
 }
 
 
+/// Columns which are not stored in the file but describe where a row comes from.
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub enum VirtualColumn {
+    #[value(name = "_file")]
+    File,
+    #[value(name = "_row_group")]
+    RowGroup,
+    #[value(name = "_row_index")]
+    RowIndex,
+}
+
+impl VirtualColumn {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::value_variants()
+            .iter()
+            .find(|c| c.name() == name)
+            .copied()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VirtualColumn::File => "_file",
+            VirtualColumn::RowGroup => "_row_group",
+            VirtualColumn::RowIndex => "_row_index",
+        }
+    }
+
+    fn field(&self, file_name: &Path, row_group: usize, row_index: usize) -> Field {
+        match self {
+            VirtualColumn::File => Field::Str(file_name.display().to_string()),
+            VirtualColumn::RowGroup => Field::Int(row_group as i32),
+            VirtualColumn::RowIndex => Field::Long(row_index as i64),
+        }
+    }
+
+    // the values of `num_rows` rows starting at the absolute row `first_row`
+    fn array(&self, file_name: &Path, row_group: usize, first_row: usize, num_rows: usize) -> (ArrowField, ArrayRef) {
+        let array: ArrayRef = match self {
+            VirtualColumn::File => Arc::new(StringArray::from_iter_values(
+                std::iter::repeat_n(file_name.display().to_string(), num_rows))),
+            VirtualColumn::RowGroup => Arc::new(Int32Array::from_iter_values(
+                std::iter::repeat_n(row_group as i32, num_rows))),
+            VirtualColumn::RowIndex => Arc::new(Int64Array::from_iter_values(
+                (first_row..first_row + num_rows).map(|i| i as i64))),
+        };
+        (ArrowField::new(self.name(), array.data_type().clone(), false), array)
+    }
+}
+
+/// Keeps the rows whose top level `column`, as printed, equals `value`.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    pub column: String,
+    pub value: String,
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((column, value)) => Ok(Filter {
+                column: column.trim().to_string(),
+                value: value.to_string(),
+            }),
+            None => Err(format!("expected COLUMN=VALUE, got {}", s)),
+        }
+    }
+}
+
+/// Selects what is printed for each row of a file.
+#[derive(Debug, Default)]
+pub struct RowOptions {
+    pub timestamps: TimestampOptions,
+    /// Top level columns to print, all of them when `None`.
+    pub columns: Option<Vec<String>>,
+    pub virtual_columns: Vec<VirtualColumn>,
+    pub filters: Vec<Filter>,
+    /// How the WKB values of GeoParquet geometry columns are printed, as bytes when `None`.
+    pub geometry: Option<GeometryFormat>,
+    /// Only the rows whose primary geometry intersects the box are printed.
+    pub bbox: Option<BoundingBox>,
+}
+
+impl RowOptions {
+    /// Virtual columns given in `columns` are printed too, after the stored ones.
+    pub fn new(
+        timestamps: TimestampOptions,
+        columns: Option<Vec<String>>,
+        mut virtual_columns: Vec<VirtualColumn>,
+        filters: Vec<Filter>) -> Self {
+        let columns = columns.map(|columns| {
+            columns
+                .into_iter()
+                .filter(|c| match VirtualColumn::from_name(c) {
+                    Some(virtual_column) => {
+                        if !virtual_columns.contains(&virtual_column) {
+                            virtual_columns.push(virtual_column);
+                        }
+                        false
+                    }
+                    None => true,
+                })
+                .collect()
+        });
+
+        Self { timestamps, columns, virtual_columns, filters, ..Default::default() }
+    }
+
+    fn selects_all(&self) -> bool {
+        self.columns.is_none() && self.virtual_columns.is_empty() && self.filters.is_empty() && self.bbox.is_none()
+    }
+
+    // the renderer of the values of a file, its WKB geometry columns included when asked for
+    fn renderer(&self, metadata: &ParquetMetaData) -> Result<TimestampRenderer, PQRSError> {
+        let renderer = TimestampRenderer::new(&self.timestamps, metadata.file_metadata().schema_descr_ptr());
+        // the geo metadata is only read for --geometry, a broken one does not stop plain reads
+        let geo = match self.geometry {
+            Some(_) => read_geo_metadata(metadata.file_metadata())?,
+            None => None,
+        };
+        let columns = match geo {
+            Some(geo) => geo
+                .columns
+                .iter()
+                .filter(|(_, column)| column.encoding == "WKB")
+                .map(|(name, _)| name.to_string())
+                .collect(),
+            None => vec![],
+        };
+        Ok(renderer.with_geometries(self.geometry, columns))
+    }
+
+    fn virtual_fields(&self, file_name: &Path, row_group: usize, row: usize) -> Vec<(String, Field)> {
+        self.virtual_columns
+            .iter()
+            .map(|c| (c.name().to_string(), c.field(file_name, row_group, row)))
+            .collect()
+    }
+
+    // every format compares the filtered values as the default output prints them
+    fn matches(&self, fields: &[(&String, &Field)], renderer: &TimestampRenderer) -> bool {
+        self.filters.iter().all(|filter| {
+            fields.iter().any(|(name, field)| {
+                **name == filter.column && renderer.field_text(name, field) == filter.value
+            })
+        })
+    }
+
+    fn is_printed(&self, name: &str) -> bool {
+        match &self.columns {
+            Some(columns) => columns.iter().any(|c| c == name),
+            None => true,
+        }
+    }
+}
+
 // /// check if a particular path is present on the filesystem
 // pub fn check_path_present<P: AsRef<Path>>(file_path: P) -> bool {
 //     Path::new(file_path.as_ref()).exists()
@@ -55,7 +237,7 @@ pub fn check_path_present<P: AsRef<Path>>(file_path: P) -> bool {
 pub fn open_file<P: AsRef<Path>>(file_name: P) -> Result<File, PQRSError> {
     let file_name = file_name.as_ref();
     let path = Path::new(&file_name);
-    let file = match File::open(&path) {
+    let file = match File::open(path) {
         Err(_) => return Err(CouldNotOpenFile(file_name.to_path_buf())),
         Ok(f) => f
     };
@@ -63,18 +245,170 @@ pub fn open_file<P: AsRef<Path>>(file_name: P) -> Result<File, PQRSError> {
     Ok(file)
 }
 
+/// The key value metadata of a file, none when it has no metadata.
+pub fn get_schema_metadata(metadata: &ParquetMetaData) -> Option<HashMap<String, Option<String>>> {
+    if let Some(metadata) = metadata.file_metadata().key_value_metadata() {
+        let mut fields: HashMap<String, Option<String>> = HashMap::new();
+        for kv in metadata.iter() {
+            fields.insert(kv.key.to_string(), kv.value.to_owned());
+        }
+        Some(fields)
+    } else {
+        None
+    }
+}
+
+/// A compression codec from its name, with an optional level as `zstd:3`.
+pub fn parse_codec(spec: &str) -> Result<Compression, PQRSError> {
+    let (name, level) = match spec.split_once(':') {
+        Some((name, level)) => (name, Some(level)),
+        None => (spec, None),
+    };
+    let level = |default: u32| -> Result<u32, PQRSError> {
+        level.map_or(Ok(default), |l| l.parse().map_err(|_| UnknownCompression(spec.to_string())))
+    };
+    let invalid = |_| UnknownCompression(spec.to_string());
+    let codec = match name.to_lowercase().as_str() {
+        "uncompressed" => Compression::UNCOMPRESSED,
+        "snappy" => Compression::SNAPPY,
+        "gzip" => Compression::GZIP(GzipLevel::try_new(level(6)?).map_err(invalid)?),
+        "brotli" => Compression::BROTLI(BrotliLevel::try_new(level(1)?).map_err(invalid)?),
+        "zstd" => Compression::ZSTD(ZstdLevel::try_new(level(1)? as i32).map_err(invalid)?),
+        "lz4" => Compression::LZ4,
+        "lz4_raw" => Compression::LZ4_RAW,
+        _ => return Err(UnknownCompression(spec.to_string())),
+    };
+    Ok(codec)
+}
+
+// the writer properties of the commands writing files
+#[derive(Args, Debug)]
+pub struct WriterArgs {
+    #[arg(short, long, default_value = "uncompressed", help = "compression codec, as name or name:level")]
+    compression: String,
+
+    #[arg(long, default_value = "1048576", help = "maximum number of rows of a row group")]
+    row_group_size: usize,
+
+    #[arg(long, default_value = "1024", help = "number of rows decoded or generated at once")]
+    batch_size: usize,
+
+    #[arg(long, help = "do not dictionary encode the columns")]
+    no_dictionary: bool,
+}
+
+impl WriterArgs {
+    pub fn batch_size(&self) -> usize {
+        self.batch_size.max(1)
+    }
+
+    pub fn properties(&self, key_value_metadata: Vec<KeyValue>) -> Result<WriterProperties, PQRSError> {
+        Ok(WriterProperties::builder()
+            .set_compression(parse_codec(&self.compression)?)
+            .set_max_row_group_size(self.row_group_size.max(1))
+            .set_dictionary_enabled(!self.no_dictionary)
+            .set_key_value_metadata((!key_value_metadata.is_empty()).then_some(key_value_metadata))
+            .build())
+    }
+}
+
+/// A schema read from a file, with the key value metadata to write along.
+pub struct SchemaDefinition {
+    pub schema: TypePtr,
+    pub key_value_metadata: Vec<KeyValue>,
+}
+
+/// Reads the schema of a Parquet file, a message type, the output of `schema --json` or a JSON
+/// Schema. The Arrow schema of the metadata is left out, the Arrow writer writes its own.
+pub fn read_schema(path: &Path) -> Result<SchemaDefinition, PQRSError> {
+    let mut magic = vec![];
+    open_file(path)?.take(4).read_to_end(&mut magic)?;
+    let mut key_value_metadata = vec![];
+    let schema = if magic == b"PAR1" {
+        let reader = SerializedFileReader::new(open_file(path)?)?;
+        let file_metadata = reader.metadata().file_metadata();
+        key_value_metadata = file_metadata.key_value_metadata().cloned().unwrap_or_default();
+        file_metadata.schema_descr().root_schema_ptr()
+    } else {
+        let mut text = String::new();
+        open_file(path)?.read_to_string(&mut text)?;
+        match text.trim_start().starts_with('{') {
+            true => {
+                let json: serde_json::Value = serde_json::from_str(&text)?;
+                match json["message"].as_str() {
+                    // the output of `schema --json`
+                    Some(message) => {
+                        for (key, value) in json["metadata"].as_object().into_iter().flatten() {
+                            key_value_metadata.push(KeyValue::new(key.to_string(), value.as_str().map(String::from)));
+                        }
+                        Arc::new(parse_message_type(message)?)
+                    }
+                    None => {
+                        let fields = json_schema_fields(&json).map_err(InvalidSchema)?;
+                        let name = json["title"].as_str().unwrap_or("schema");
+                        Arc::new(message_type(name, &fields)?)
+                    }
+                }
+            }
+            // the output of `schema` has a header before its message type
+            false => {
+                let start = text
+                    .split_inclusive('\n')
+                    .take_while(|line| !line.starts_with("message"))
+                    .map(str::len)
+                    .sum::<usize>();
+                Arc::new(parse_message_type(&text[start..])?)
+            }
+        }
+    };
+    key_value_metadata.retain(|kv| kv.key != ARROW_SCHEMA_KEY);
+    Ok(SchemaDefinition { schema, key_value_metadata })
+}
+
+/// A temporary file next to the output, so a failed write leaves nothing behind.
+pub fn temporary_output(output: &Path) -> Result<NamedTempFile, PQRSError> {
+    let directory = match output.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut builder = tempfile::Builder::new();
+    // the mode File::create gives, temporary files are only readable by their owner
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(std::fs::Permissions::from_mode(0o666));
+    }
+    Ok(builder.tempfile_in(directory)?)
+}
+
+/// Moves the written temporary file to the output, unless a file appeared there meanwhile.
+pub fn persist_output(temporary: NamedTempFile, output: &Path) -> Result<(), PQRSError> {
+    temporary.persist_noclobber(output).map_err(|e| match e.error.kind() {
+        std::io::ErrorKind::AlreadyExists => FileExists(output.to_path_buf()),
+        _ => e.error.into(),
+    })?;
+    Ok(())
+}
+
 pub fn print_rows(
     file: File,
+    file_name: &Path,
     num_records: Option<usize>,
     format: Formats,
-    raw_timestamp: bool) -> Result<(), PQRSError> {
+    options: &RowOptions) -> Result<(), PQRSError> {
+
+    if !options.selects_all() {
+        return print_selected_rows(file, file_name, num_records, format, options);
+    }
 
     let mut left = num_records;
 
     match format {
         Formats::Default | Formats::Json => {
             let parquet_reader = SerializedFileReader::new(file)?;
-            let mut iter = parquet_reader.get_row_iter(None)?;
+            let renderer = options.renderer(parquet_reader.metadata())?;
+            let row_reader = RowFileReader::new(&parquet_reader)?;
+            let mut iter = row_reader.get_row_iter(None)?;
 
             let mut start: usize = 0;
             let end: usize = num_records.unwrap_or(0);
@@ -82,7 +416,7 @@ pub fn print_rows(
 
             while all_records || start < end {
                 match iter.next() {
-                    Some(row) => print_row(&row, format, raw_timestamp),
+                    Some(row) => print_row(&row, format, &renderer),
                     None => break,
                 }
 
@@ -93,7 +427,7 @@ pub fn print_rows(
             if num_records.is_some() {
                 return Err(UnsupportedOperation())
             } else {
-                let output = print_csv(file);
+                let output = print_csv(file, options);
                 if output.is_err() {
                     println!("{:?}", output);
                 }
@@ -101,6 +435,7 @@ pub fn print_rows(
         }
         Formats::CsvNoHeader => {
             let arrow_reader = ArrowReaderBuilder::try_new(file)?;
+            let renderer = options.renderer(arrow_reader.metadata())?;
             let batch_reader = arrow_reader.with_batch_size(8192).build()?;
             let writer_builder = arrow::csv::WriterBuilder::new().has_headers(false);
             let mut writer = writer_builder.build(std::io::stdout());
@@ -121,7 +456,7 @@ pub fn print_rows(
                     }
                 };
 
-                writer.write(&batch)?;
+                writer.write(&renderer.render_batch(&batch)?)?;
             }
         }
     }
@@ -131,21 +466,24 @@ pub fn print_rows(
 
 
 pub fn print_csv(
-    file: File
+    file: File,
+    options: &RowOptions
 ) -> Result<(), PQRSError> {
-    let data = get_row_batches(file)?;
+    let arrow_reader = ArrowReaderBuilder::try_new(file)?;
+    let renderer = options.renderer(arrow_reader.metadata())?;
+    let batch_reader = arrow_reader.with_batch_size(1024).build()?;
     let output = NamedTempFile::new()?;
 
     let mut writer = csv::Writer::new(&output);
-    for batch in &data.batches {
-        writer.write(batch)?;
+    for maybe_batch in batch_reader {
+        writer.write(&renderer.render_batch(&maybe_batch?)?)?;
     }
 
     let mut buf = String::new();
     let mut resutl = output.reopen()?;
     resutl.read_to_string(&mut buf)?;
 
-    if buf.len() == 0 {
+    if buf.is_empty() {
         println!("Empty.");
     } else {
         println!("{}", buf);
@@ -153,6 +491,459 @@ pub fn print_csv(
 
     Ok(())
 }
+
+/// Prints the projected columns, followed by the virtual ones, of the rows matching every
+/// filter. Only the printed and filtered top level columns are read.
+fn print_selected_rows(
+    file: File,
+    file_name: &Path,
+    num_records: Option<usize>,
+    format: Formats,
+    options: &RowOptions) -> Result<(), PQRSError> {
+
+    let parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
+    let metadata = parquet_reader.metadata();
+    let root = metadata.file_metadata().schema();
+    let geometry = match options.bbox {
+        Some(_) => Some(bbox_geometry(metadata, file_name)?),
+        None => None,
+    };
+    let geometry_column = geometry.as_ref().map(|(geo, _)| geo.primary_column.as_str());
+
+    let names = options.columns.iter().flatten().chain(options.filters.iter().map(|f| &f.column));
+    for name in names {
+        if VirtualColumn::from_name(name).is_none() && !root.get_fields().iter().any(|f| f.name() == name) {
+            return Err(UnknownColumn(name.to_string()));
+        }
+    }
+
+    let mut read = root
+        .get_fields()
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| {
+            options.is_printed(f.name())
+                || options.filters.iter().any(|filter| filter.column == f.name())
+                || geometry_column == Some(f.name())
+        })
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    // the rows still have to be counted when only virtual columns are printed
+    if read.is_empty() {
+        read.push(0);
+    }
+
+    let mut skipped = bloom_skipped_row_groups(&file, metadata, &options.filters)?;
+    if let (Some(bbox), Some((_, Some(covering)))) = (&options.bbox, &geometry) {
+        for (i, skip) in skipped.iter_mut().enumerate() {
+            if covering_row_group_bbox(metadata.row_group(i), covering).is_some_and(|b| !b.intersects(bbox)) {
+                debug!("Skipping row group {}, its covering bounding box is out of the filtered one", i);
+                *skip = true;
+            }
+        }
+    }
+    let mut left = num_records.unwrap_or(usize::MAX);
+    let renderer = options.renderer(metadata)?;
+    let row_reader = RowFileReader::new(&parquet_reader)?;
+    let projection = |columns: &[usize]| {
+        Type::group_type_builder(root.name())
+            .with_fields(&mut columns.iter().map(|i| root.get_fields()[*i].clone()).collect())
+            .build()
+    };
+    let first_rows = metadata
+        .row_groups()
+        .iter()
+        .scan(0, |first_row, row_group| {
+            *first_row += row_group.num_rows() as usize;
+            Some(*first_row - row_group.num_rows() as usize)
+        })
+        .collect::<Vec<_>>();
+    let kept = (0..skipped.len()).filter(|i| !skipped[*i]).collect::<Vec<_>>();
+
+    match format {
+        Formats::Default | Formats::Json => {
+            let projection = projection(&read)?;
+            for &i in &kept {
+                let row_group = row_reader.get_row_group(i)?;
+                for (position, row) in row_group.get_row_iter(Some(projection.clone()))?.enumerate() {
+                    if left == 0 {
+                        return Ok(());
+                    }
+
+                    let virtual_fields = options.virtual_fields(file_name, i, first_rows[i] + position);
+                    let mut fields = row
+                        .get_column_iter()
+                        .chain(virtual_fields.iter().map(|(name, field)| (name, field)))
+                        .collect::<Vec<_>>();
+
+                    let inside = match (&options.bbox, geometry_column) {
+                        (Some(bbox), Some(column)) => fields.iter().any(|(name, field)| {
+                            *name == column && matches!(field, Field::Bytes(wkb) if intersects(wkb.data(), bbox))
+                        }),
+                        _ => true,
+                    };
+                    if !options.matches(&fields, &renderer) || !inside {
+                        continue;
+                    }
+
+                    fields.retain(|(name, _)| {
+                        options.is_printed(name) || VirtualColumn::from_name(name).is_some()
+                    });
+                    match format {
+                        Formats::Json => println!("{}", renderer.fields_to_json(&fields)),
+                        _ => println!("{}", renderer.format_fields(&fields)),
+                    }
+                    left -= 1;
+                }
+            }
+        }
+        Formats::Csv | Formats::CsvNoHeader => {
+            let has_headers = matches!(format, Formats::Csv);
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(has_headers)
+                .build(std::io::stdout());
+
+            // the filters are tested on the rows of their columns, the same way as the other
+            // formats, and the batches only carry the printed values
+            let filtered = options
+                .filters
+                .iter()
+                .filter_map(|f| root.get_fields().iter().position(|field| field.name() == f.column))
+                .collect::<Vec<_>>();
+            let filter_projection = projection(if filtered.is_empty() { &read[..1] } else { &filtered })?;
+            let row_group_matches = |i: usize| -> Result<Vec<bool>, PQRSError> {
+                let num_rows = metadata.row_group(i).num_rows() as usize;
+                if options.filters.is_empty() {
+                    return Ok(vec![true; num_rows]);
+                }
+                let row_group = row_reader.get_row_group(i)?;
+                let rows = row_group.get_row_iter(Some(filter_projection.clone()))?;
+                Ok(rows
+                    .enumerate()
+                    .map(|(position, row)| {
+                        let virtual_fields = options.virtual_fields(file_name, i, first_rows[i] + position);
+                        let fields = row
+                            .get_column_iter()
+                            .chain(virtual_fields.iter().map(|(name, field)| (name, field)))
+                            .collect::<Vec<_>>();
+                        options.matches(&fields, &renderer)
+                    })
+                    .collect())
+            };
+
+            let arrow_reader = ArrowReaderBuilder::try_new(file.try_clone()?)?;
+            let mask = ProjectionMask::roots(arrow_reader.parquet_schema(), read.clone());
+            let batch_reader = arrow_reader
+                .with_row_groups(kept.clone())
+                .with_projection(mask)
+                .with_batch_size(8192)
+                .build()?;
+
+            // the batches run across row groups, they are cut at the end of each row group to
+            // know where their rows come from
+            let mut groups = kept.iter().copied();
+            let mut current: Option<(usize, usize, Vec<bool>)> = None;
+            for maybe_batch in batch_reader {
+                let whole = maybe_batch?;
+                let mut offset = 0;
+                while offset < whole.num_rows() {
+                    if left == 0 {
+                        return Ok(());
+                    }
+                    let (i, position, matches) = match &mut current {
+                        Some((i, position, matches)) if *position < matches.len() => (*i, position, matches),
+                        _ => {
+                            let Some(i) = groups.next() else { break };
+                            current = Some((i, 0, row_group_matches(i)?));
+                            continue;
+                        }
+                    };
+                    let num_rows = (matches.len() - *position).min(whole.num_rows() - offset);
+                    let raw = whole.slice(offset, num_rows);
+                    let mut keep = BooleanArray::from(matches[*position..*position + num_rows].to_vec());
+                    // geometries are tested before they are rendered as text
+                    if let (Some(bbox), Some(column)) = (&options.bbox, geometry_column) {
+                        let values = wkb_values(raw.column(raw.schema().index_of(column)?))?;
+                        let inside = values.iter().map(|v| Some(v.is_some_and(|wkb| intersects(wkb, bbox)))).collect();
+                        keep = and(&keep, &inside)?;
+                    }
+                    let batch = renderer.render_batch(&raw)?;
+                    let mut fields = batch.schema().fields().iter().map(|f| f.as_ref().clone()).collect::<Vec<_>>();
+                    let mut columns = batch.columns().to_vec();
+                    for virtual_column in &options.virtual_columns {
+                        let (field, array) = virtual_column.array(file_name, i, first_rows[i] + *position, num_rows);
+                        fields.push(field);
+                        columns.push(array);
+                    }
+                    *position += num_rows;
+                    offset += num_rows;
+                    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?;
+                    let batch = filter_record_batch(&batch, &keep)?;
+
+                    let printed = batch
+                        .schema()
+                        .fields()
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, f)| options.is_printed(f.name()) || VirtualColumn::from_name(f.name()).is_some())
+                        .map(|(index, _)| index)
+                        .collect::<Vec<_>>();
+                    let batch = batch.project(&printed)?;
+                    let batch = batch.slice(0, min(batch.num_rows(), left));
+                    left -= batch.num_rows();
+
+                    writer.write(&batch)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// the GeoParquet metadata of a file filtered by bounding box, and the covering columns of its
+// primary geometry column
+fn bbox_geometry(
+    metadata: &ParquetMetaData,
+    file_name: &Path) -> Result<(GeoMetadata, Option<CoveringBbox>), PQRSError> {
+    let geo = read_geo_metadata(metadata.file_metadata())?
+        .ok_or_else(|| InvalidGeoMetadata(format!("{} has no geo metadata", file_name.display())))?;
+    let column = geo.columns.get(&geo.primary_column).ok_or_else(|| {
+        InvalidGeoMetadata(format!("the primary column {} is not a geometry column", geo.primary_column))
+    })?;
+    if column.encoding != "WKB" {
+        return Err(InvalidGeoMetadata(format!(
+            "the primary column {} is {} encoded, only WKB can be filtered",
+            geo.primary_column, column.encoding
+        )));
+    }
+    let binary = metadata.file_metadata().schema().get_fields().iter().any(|f| {
+        f.name() == geo.primary_column && f.is_primitive() && f.get_physical_type() == PhysicalType::BYTE_ARRAY
+    });
+    if !binary {
+        return Err(InvalidGeoMetadata(NOT_BINARY_GEOMETRIES.to_string()));
+    }
+    let covering = column.covering_bbox().cloned();
+    Ok((geo, covering))
+}
+
+static NOT_BINARY_GEOMETRIES: &str = "the primary column is declared WKB but does not hold binary values";
+
+// the WKB values of the primary geometry column of a batch, which an arrow schema hint may
+// have read as large binary
+fn wkb_values(values: &ArrayRef) -> Result<BinaryArray, PQRSError> {
+    let values = match values.data_type() {
+        DataType::Binary => values.clone(),
+        DataType::LargeBinary => cast(values, &DataType::Binary)?,
+        _ => return Err(InvalidGeoMetadata(NOT_BINARY_GEOMETRIES.to_string())),
+    };
+    Ok(values.as_any().downcast_ref::<BinaryArray>().unwrap().clone())
+}
+
+fn intersects(wkb: &[u8], bbox: &BoundingBox) -> bool {
+    parse_wkb(wkb).is_ok_and(|geometry| geometry.bbox().intersects(bbox))
+}
+
+// row groups where the bloom filter of a filtered column proves that no row can match
+fn bloom_skipped_row_groups(
+    file: &File,
+    metadata: &ParquetMetaData,
+    filters: &[Filter]) -> Result<Vec<bool>, PQRSError> {
+    let schema = metadata.file_metadata().schema_descr();
+    let mut skipped = vec![false; metadata.num_row_groups()];
+
+    // nulls print as null but are never in a bloom filter
+    let probes = filters
+        .iter()
+        .filter(|f| f.value != "null")
+        .filter_map(|f| {
+            schema
+                .columns()
+                .iter()
+                .position(|c| c.path().parts().len() == 1 && c.name() == f.column)
+                .map(|i| (i, f))
+        })
+        .filter(|(i, _)| prints_stored_value(&schema.column(*i)))
+        .collect::<Vec<_>>();
+    if probes.is_empty() {
+        return Ok(skipped);
+    }
+
+    // only the filters of the filtered columns are loaded
+    let columns = probes.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+    let parquet_reader = bloom_filter_reader(file.try_clone()?, &columns)?;
+    for (i, skip) in skipped.iter_mut().enumerate() {
+        let row_group = parquet_reader.get_row_group(i)?;
+        *skip = probes.iter().any(|(column, filter)| {
+            row_group.get_column_bloom_filter(*column).is_some_and(|sbbf| {
+                !bloom_filter_check(sbbf, &schema.column(*column), &filter.value).unwrap_or(true)
+            })
+        });
+        if *skip {
+            debug!("Skipping row group {}, its bloom filters rule out every filtered value", i);
+        }
+    }
+
+    Ok(skipped)
+}
+
+// the type without the time converted and logical types of its columns
+fn without_times(tpe: &TypePtr) -> Result<TypePtr, PQRSError> {
+    let info = tpe.get_basic_info();
+    let stripped = match tpe.as_ref() {
+        Type::PrimitiveType { physical_type, .. } => {
+            let is_time = matches!(info.converted_type(), ConvertedType::TIME_MILLIS | ConvertedType::TIME_MICROS)
+                || matches!(info.logical_type(), Some(LogicalType::Time { .. }));
+            if !is_time {
+                return Ok(tpe.clone());
+            }
+            let mut builder = Type::primitive_type_builder(info.name(), *physical_type).with_repetition(info.repetition());
+            if info.has_id() {
+                builder = builder.with_id(info.id());
+            }
+            builder.build()?
+        }
+        Type::GroupType { fields, .. } => {
+            let mut stripped = fields.iter().map(without_times).collect::<Result<Vec<_>, _>>()?;
+            if stripped.iter().zip(fields).all(|(a, b)| Arc::ptr_eq(a, b)) {
+                return Ok(tpe.clone());
+            }
+            let mut builder = Type::group_type_builder(info.name())
+                .with_converted_type(info.converted_type())
+                .with_logical_type(info.logical_type())
+                .with_fields(&mut stripped);
+            if info.has_repetition() {
+                builder = builder.with_repetition(info.repetition());
+            }
+            if info.has_id() {
+                builder = builder.with_id(info.id());
+            }
+            builder.build()?
+        }
+    };
+    Ok(Arc::new(stripped))
+}
+
+/// Reads the rows of a file whatever the types of its columns. The row reader panics on the
+/// time converted types, their columns are read as plain integers and rendered from the schema
+/// of the file instead.
+pub struct RowFileReader<'a> {
+    reader: &'a dyn FileReader,
+    /// The metadata with the time types left out, when the file has time columns.
+    metadata: Option<ParquetMetaData>,
+}
+
+impl<'a> RowFileReader<'a> {
+    pub fn new(reader: &'a dyn FileReader) -> Result<Self, PQRSError> {
+        let metadata = reader.metadata();
+        let file_metadata = metadata.file_metadata();
+        let root = file_metadata.schema_descr().root_schema_ptr();
+        let stripped = without_times(&root)?;
+        if Arc::ptr_eq(&stripped, &root) {
+            return Ok(RowFileReader { reader, metadata: None });
+        }
+
+        let schema = Arc::new(SchemaDescriptor::new(stripped));
+        let row_groups = metadata
+            .row_groups()
+            .iter()
+            .map(|rg| RowGroupMetaData::from_thrift(schema.clone(), rg.to_thrift()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let file_metadata = FileMetaData::new(
+            file_metadata.version(),
+            file_metadata.num_rows(),
+            file_metadata.created_by().map(String::from),
+            file_metadata.key_value_metadata().cloned(),
+            schema,
+            file_metadata.column_orders().cloned(),
+        );
+        let metadata = ParquetMetaData::new_with_page_index(
+            file_metadata,
+            row_groups,
+            metadata.column_index().cloned(),
+            metadata.offset_index().cloned(),
+        );
+        Ok(RowFileReader { reader, metadata: Some(metadata) })
+    }
+
+    /// The row group whose rows are read from its row `first_row` on. The column readers skip
+    /// the rows before, whole pages at a time when the offset index is loaded.
+    pub fn get_row_group_from(&self, i: usize, first_row: usize) -> parquet::errors::Result<Box<dyn RowGroupReader + '_>> {
+        Ok(Box::new(PartialRowGroupReader {
+            reader: self.reader.get_row_group(i)?,
+            metadata: self.metadata().row_group(i),
+            first_row,
+        }))
+    }
+}
+
+impl FileReader for RowFileReader<'_> {
+    fn metadata(&self) -> &ParquetMetaData {
+        self.metadata.as_ref().unwrap_or_else(|| self.reader.metadata())
+    }
+
+    fn num_row_groups(&self) -> usize {
+        self.reader.num_row_groups()
+    }
+
+    fn get_row_group(&self, i: usize) -> parquet::errors::Result<Box<dyn RowGroupReader + '_>> {
+        match &self.metadata {
+            Some(_) => self.get_row_group_from(i, 0),
+            None => self.reader.get_row_group(i),
+        }
+    }
+
+    fn get_row_iter(&self, projection: Option<Type>) -> parquet::errors::Result<RowIter<'_>> {
+        RowIter::from_file(projection, self)
+    }
+}
+
+// a row group read from a given row, its values decoded after the metadata of the row reader
+struct PartialRowGroupReader<'a> {
+    reader: Box<dyn RowGroupReader + 'a>,
+    metadata: &'a RowGroupMetaData,
+    first_row: usize,
+}
+
+impl RowGroupReader for PartialRowGroupReader<'_> {
+    fn metadata(&self) -> &RowGroupMetaData {
+        self.metadata
+    }
+
+    fn get_column_reader(&self, i: usize) -> parquet::errors::Result<ColumnReader> {
+        let mut reader = self.reader.get_column_reader(i)?;
+        if self.first_row > 0 {
+            match &mut reader {
+                ColumnReader::BoolColumnReader(r) => r.skip_records(self.first_row)?,
+                ColumnReader::Int32ColumnReader(r) => r.skip_records(self.first_row)?,
+                ColumnReader::Int64ColumnReader(r) => r.skip_records(self.first_row)?,
+                ColumnReader::Int96ColumnReader(r) => r.skip_records(self.first_row)?,
+                ColumnReader::FloatColumnReader(r) => r.skip_records(self.first_row)?,
+                ColumnReader::DoubleColumnReader(r) => r.skip_records(self.first_row)?,
+                ColumnReader::ByteArrayColumnReader(r) => r.skip_records(self.first_row)?,
+                ColumnReader::FixedLenByteArrayColumnReader(r) => r.skip_records(self.first_row)?,
+            };
+        }
+        Ok(reader)
+    }
+
+    fn num_columns(&self) -> usize {
+        self.reader.num_columns()
+    }
+
+    fn get_column_page_reader(&self, i: usize) -> parquet::errors::Result<Box<dyn PageReader>> {
+        self.reader.get_column_page_reader(i)
+    }
+
+    fn get_column_bloom_filter(&self, i: usize) -> Option<&Sbbf> {
+        self.reader.get_column_bloom_filter(i)
+    }
+
+    fn get_row_iter(&self, projection: Option<Type>) -> parquet::errors::Result<RowIter<'_>> {
+        RowIter::from_row_group(projection, self)
+    }
+}
+
 // check if the given entry in the walking tree is a hidden file
 pub fn is_hidden(entry: &DirEntry) -> bool {
     entry.file_name()
@@ -164,30 +955,12 @@ pub fn is_hidden(entry: &DirEntry) -> bool {
 fn print_row(
     row: &Row,
     format: Formats,
-    raw_timestamp: bool) {
+    renderer: &TimestampRenderer) {
     match format {
-        Formats::Json => println!("{}", row.to_json_value()),
-        Formats::Default => {
-            if raw_timestamp {
-                print!("{{");
-                row.get_column_iter().for_each(|(key, field)| {
-                    match field {
-                        Field::TimestampMillis(millis) => {
-                            print!("{:?}: {:?},", key, millis);
-                        }
-                        Field::TimestampMicros(micros) => {
-                            print!("{:?}: {:?},", key, micros);
-                        }
-                        _ => print!("{:?}: {:?},", key, field)
-                    }
-                });
-                print!("}}");
-            } else {
-                println!("{}", row.to_string());
-            }
-        },
-        Formats::Csv => println!("Unsupported! {}", row.to_string()),
-        Formats::CsvNoHeader => println!("Unsupported! {}.", row.to_string()),
+        Formats::Json => println!("{}", renderer.row_to_json(row)),
+        Formats::Default => println!("{}", renderer.format_row(row)),
+        Formats::Csv => println!("Unsupported! {}", row),
+        Formats::CsvNoHeader => println!("Unsupported! {}.", row),
     }
 }
 
@@ -236,40 +1009,176 @@ pub fn get_pretty_size(bytes: i64) -> String {
         return format!("{:.3} TiB", bytes / ONE_TI_B);
     }
 
-    return format!("{:.3} PiB", bytes / ONE_PI_B);
+    format!("{:.3} PiB", bytes / ONE_PI_B)
 }
 
-pub fn print_rows_random(
+/// Returns the row group holding the absolute row `row` and the row's position inside it.
+pub fn locate_row(metadata: &ParquetMetaData, row: usize) -> Option<(usize, usize)> {
+    let mut first_row = 0;
+    for (i, row_group) in metadata.row_groups().iter().enumerate() {
+        let num_rows = row_group.num_rows() as usize;
+        if row < first_row + num_rows {
+            return Some((i, row - first_row));
+        }
+        first_row += num_rows;
+    }
+
+    None
+}
+
+/// Checks that every column chunk has an offset index, the reader can not skip pages otherwise.
+pub fn has_offset_index(metadata: &ParquetMetaData) -> bool {
+    metadata.row_groups().iter().all(|rg| {
+        rg.columns().iter().all(|c| c.offset_index_offset().is_some())
+    })
+}
+
+/// Splits sorted, non overlapping ranges of absolute row indexes by the row group holding
+/// them, the returned ranges are relative to the start of their row group.
+pub fn row_group_ranges(
+    metadata: &ParquetMetaData,
+    ranges: &[Range<usize>]) -> Vec<(usize, Vec<Range<usize>>)> {
+    let mut groups = vec![];
+    let mut first_row = 0;
+    for (i, row_group) in metadata.row_groups().iter().enumerate() {
+        let last_row = first_row + row_group.num_rows() as usize;
+        let local = ranges
+            .iter()
+            .filter(|r| r.start < last_row && r.end > first_row)
+            .map(|r| r.start.max(first_row) - first_row..r.end.min(last_row) - first_row)
+            .collect::<Vec<_>>();
+        if !local.is_empty() {
+            groups.push((i, local));
+        }
+        first_row = last_row;
+    }
+
+    groups
+}
+
+/// Prints `limit` rows, all remaining when `None`, starting from the absolute row `offset`.
+pub fn print_rows_range(
     file: File,
-    sample_size: usize,
-    format: Formats
-) -> Result<(), PQRSError> {
-    let parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
-    let mut iter = parquet_reader.get_row_iter(None)?;
+    offset: usize,
+    limit: Option<usize>,
+    format: Formats,
+    timestamps: &TimestampOptions) -> Result<(), PQRSError> {
+    let total_rows = get_row_count(file.try_clone()?)? as usize;
+    let end = match limit {
+        Some(limit) => min(offset.saturating_add(limit), total_rows),
+        None => total_rows,
+    };
 
-    let total_records_in_file: i64 = get_row_count(file)?;
-    let mut indexes = (0..total_records_in_file).collect::<Vec<_>>();
+    if offset >= end {
+        return Ok(());
+    }
+    let range = offset..end;
+    print_row_ranges(file, std::slice::from_ref(&range), format, timestamps)
+}
+
+/// Prints the rows of sorted, non overlapping ranges of absolute row indexes.
+///
+/// Only the row groups holding the rows are read, found with their `num_rows` from the
+/// footer, and the pages before the rows are skipped through the offset index when the file
+/// has one.
+pub fn print_row_ranges(
+    file: File,
+    ranges: &[Range<usize>],
+    format: Formats,
+    timestamps: &TimestampOptions) -> Result<(), PQRSError> {
+
+    let mut parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
+    if has_offset_index(parquet_reader.metadata()) {
+        let options = ReadOptionsBuilder::new().with_page_index().build();
+        parquet_reader = SerializedFileReader::new_with_options(file.try_clone()?, options)?;
+    }
+    let metadata = parquet_reader.metadata();
+    let groups = row_group_ranges(metadata, ranges);
 
-    let mut rng = thread_rng();
-    indexes.shuffle(&mut rng);
+    debug!("Rows to read by row group: {:?}", groups);
 
-    indexes = indexes
-        .into_iter()
-        .take(sample_size as usize)
-        .collect::<Vec<_>>();
+    match format {
+        Formats::Default | Formats::Json => {
+            let schema = metadata.file_metadata().schema_descr_ptr();
+            let renderer = TimestampRenderer::new(timestamps, schema);
+            let row_reader = RowFileReader::new(&parquet_reader)?;
 
-    debug!("Sampled indexes: {:#?}", indexes);
+            for (i, local) in &groups {
+                for range in local {
+                    let row_group = row_reader.get_row_group_from(*i, range.start)?;
+                    for row in row_group.get_row_iter(None)?.take(range.len()) {
+                        print_row(&row, format, &renderer);
+                    }
+                }
+            }
+        }
+        Formats::Csv | Formats::CsvNoHeader => {
+            let renderer = TimestampRenderer::new(
+                timestamps,
+                metadata.file_metadata().schema_descr_ptr());
+            let batch_reader = read_row_ranges(file, metadata, ranges)?;
 
-    let mut start: i64 = 0;
-    while let Some(row) = iter.next() {
-        if indexes.contains(&start) {
-            print_row(&row, format, false)
+            let has_headers = matches!(format, Formats::Csv);
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(has_headers)
+                .build(std::io::stdout());
+            for maybe_batch in batch_reader {
+                writer.write(&renderer.render_batch(&maybe_batch?)?)?;
+            }
         }
-        start += 1;
     }
 
     Ok(())
+}
 
+/// Reads the rows of sorted, non overlapping ranges of absolute row indexes as record
+/// batches, skipping pages through the offset index when the file has one.
+pub fn read_row_ranges(
+    file: File,
+    metadata: &ParquetMetaData,
+    ranges: &[Range<usize>]) -> Result<ParquetRecordBatchReader, PQRSError> {
+    let groups = row_group_ranges(metadata, ranges);
+
+    let mut selectors = vec![];
+    for (i, local) in &groups {
+        let mut position = 0;
+        for range in local {
+            selectors.push(RowSelector::skip(range.start - position));
+            selectors.push(RowSelector::select(range.len()));
+            position = range.end;
+        }
+        selectors.push(RowSelector::skip(metadata.row_group(*i).num_rows() as usize - position));
+    }
+    selectors.retain(|s| s.row_count > 0);
+
+    let page_index = has_offset_index(metadata);
+    debug!("Skipping pages with the offset index: {}", page_index);
+
+    let options = ArrowReaderOptions::new().with_page_index(page_index);
+    let batch_reader = ArrowReaderBuilder::try_new_with_options(file, options)?
+        .with_row_groups(groups.iter().map(|(i, _)| *i).collect())
+        .with_row_selection(RowSelection::from(selectors))
+        .with_batch_size(8192)
+        .build()?;
+
+    Ok(batch_reader)
+}
+
+/// Writes the rows of sorted, non overlapping ranges of absolute row indexes to a new file.
+pub fn write_row_ranges(
+    file: File,
+    ranges: &[Range<usize>],
+    output: &Path) -> Result<(), PQRSError> {
+    let parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
+    let batch_reader = read_row_ranges(file, parquet_reader.metadata(), ranges)?;
+
+    let mut writer = ArrowWriter::try_new(File::create(output)?, batch_reader.schema(), None)?;
+    for maybe_batch in batch_reader {
+        writer.write(&maybe_batch?)?;
+    }
+    writer.close()?;
+
+    Ok(())
 }
 
 #[derive(Debug)]
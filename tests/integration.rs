@@ -29,9 +29,326 @@ static SCHEMA_OUTPUT: &str = r#"message hive_schema {
 }"#;
 static SAMPLE_PARTIAL_OUTPUT_1: &str = "{continent:";
 static SAMPLE_PARTIAL_OUTPUT_2: &str = "country: {name:";
+static TIMESTAMPS_FILE_NAME: &str = "timestamps.parquet";
+static CAT_RAW_TIMESTAMP_OUTPUT: &str = r#"{event: 1700000000123, local: 1700000000123456789, day: 19675}
+"#;
+static CAT_TIMEZONE_OUTPUT: &str = r#"{"day":"2023-11-14","event":"2023-11-15T06:13:20.123+08:00","local":"2023-11-14T22:13:20.123456789"}
+"#;
+static CAT_CSV_TIMESTAMP_FORMAT_OUTPUT: &str = r#"event,local,day
+2023-11-14 22:13,2023-11-14 22:13,2023-11-14
+"#;
+
+// writes a file with a UTC adjusted millisecond timestamp, a local nanosecond timestamp and a date
+fn write_timestamps_file(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+    use arrow::array::{ArrayRef, Date32Array, TimestampMillisecondArray, TimestampNanosecondArray};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+
+    let event: ArrayRef = Arc::new(
+        TimestampMillisecondArray::from(vec![1_700_000_000_123]).with_timezone("UTC"));
+    let local: ArrayRef = Arc::new(TimestampNanosecondArray::from(vec![1_700_000_000_123_456_789]));
+    let day: ArrayRef = Arc::new(Date32Array::from(vec![19_675]));
+    let batch = RecordBatch::try_from_iter(vec![("event", event), ("local", local), ("day", day)])?;
+
+    let mut writer = ArrowWriter::try_new(std::fs::File::create(path)?, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+static CAT_VIRTUAL_COLUMNS_OUTPUT: &str = r#"{"_file":"data/cities.parquet","_row_group":0,"_row_index":1,"continent":"Europe"}
+"#;
+static CAT_CSV_VIRTUAL_COLUMNS_OUTPUT: &str = r#"foo,_file,_row_index
+1,data/simple.parquet,0
+10,data/simple.parquet,1
+"#;
+static NUMBERS_FILE_NAME: &str = "numbers.parquet";
+static SLICE_CSV_OUTPUT: &str = "25,name-25\n26,name-26\n27,name-27\n";
+static GET_OUTPUT: &str = r#"{id: 3, name: "name-3"}
+{id: 4, name: "name-4"}
+{id: 25, name: "name-25"}
+{id: 99, name: "name-99"}
+"#;
+static PAGES_OUTPUT: &str = r#"Row group 0, column continent (BYTE_ARRAY, UNCOMPRESSED):
+  page 0: DICTIONARY_PAGE @ 4, header 13 bytes, 27/27 bytes compressed/uncompressed, 2 values, encoding PLAIN_DICTIONARY
+    values: [Europe, North America]
+  page 1: DATA_PAGE @ 44, header 44 bytes, 9/9 bytes compressed/uncompressed, 3 values, encoding PLAIN_DICTIONARY, def levels RLE, rep levels BIT_PACKED
+    statistics: min Europe, max North America, nulls 0, distinct -
+    values: [Europe, Europe, North America]
+"#;
+static LEVELS_OUTPUT: &str = r#"Column ids.list.item: max repetition level 1, max definition level 3
+  OPTIONAL ids: definition level 1
+  REPEATED list: definition level 2, repetition level 1
+  OPTIONAL item: definition level 3
+
+record  rep  def  value                    path
+     0    0    3  1                        ids.list[0].item
+     0    1    3  2                        ids.list[1].item
+     1    0    1  null                     ids.list = [] (empty)
+     2    0    0  null                     ids = null
+     3    0    2  null                     ids.list[0].item = null
+     3    1    3  3                        ids.list[1].item
+"#;
+
+// writes `rows` rows of an id and a name column, `row_group_size` rows per row group and
+// 4 rows per page so that the file has several row groups and a page index
+fn write_numbers_file(
+    path: &std::path::Path,
+    rows: i64,
+    row_group_size: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+    use arrow::array::{ArrayRef, Int64Array, StringArray};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+
+    let id: ArrayRef = Arc::new(Int64Array::from_iter_values(0..rows));
+    let name: ArrayRef = Arc::new(StringArray::from_iter_values((0..rows).map(|i| format!("name-{}", i))));
+    let batch = RecordBatch::try_from_iter(vec![("id", id), ("name", name)])?;
+
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(row_group_size)
+        .set_data_page_row_count_limit(4)
+        .set_write_batch_size(4)
+        .build();
+    let mut writer = ArrowWriter::try_new(std::fs::File::create(path)?, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+// ids are all distinct, so the tiny dictionary page limit makes the writer fall back to plain
+fn write_dictionary_fallback_file(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+    use arrow::array::{ArrayRef, Int64Array};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+
+    let id: ArrayRef = Arc::new(Int64Array::from_iter_values(0..20));
+    let batch = RecordBatch::try_from_iter(vec![("id", id)])?;
+
+    let props = WriterProperties::builder()
+        .set_dictionary_pagesize_limit(64)
+        .set_data_page_row_count_limit(4)
+        .set_write_batch_size(4)
+        .build();
+    let mut writer = ArrowWriter::try_new(std::fs::File::create(path)?, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+fn write_bloom_filter_file(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+    use arrow::array::{ArrayRef, Int64Array, StringArray};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+
+    let id: ArrayRef = Arc::new(Int64Array::from_iter_values(0..30));
+    let name: ArrayRef = Arc::new(StringArray::from_iter_values((0..30).map(|i| format!("name-{}", i))));
+    let batch = RecordBatch::try_from_iter(vec![("id", id), ("name", name)])?;
+
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(10)
+        .set_bloom_filter_enabled(true)
+        .set_bloom_filter_ndv(10)
+        .build();
+    let mut writer = ArrowWriter::try_new(std::fs::File::create(path)?, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+// a list column with values, an empty list, a null list and a null element
+fn write_lists_file(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+    use arrow::array::{ArrayRef, ListArray};
+    use arrow::datatypes::Int32Type;
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+
+    let ids: ArrayRef = Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+        Some(vec![Some(1), Some(2)]),
+        Some(vec![]),
+        None,
+        Some(vec![None, Some(3)]),
+    ]));
+    let batch = RecordBatch::try_from_iter(vec![("ids", ids)])?;
+
+    let mut writer = ArrowWriter::try_new(std::fs::File::create(path)?, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+// a GeoParquet file of 3 row groups of 4 rows, the points (i, 2i) then a line string, with
+// bbox covering columns
+static GEO_METADATA: &str = r#"{"version":"1.1.0","primary_column":"geometry","columns":{"geometry":{"encoding":"WKB","geometry_types":["Point","LineString"],"bbox":[0,0,12,24],"covering":{"bbox":{"xmin":["bbox","xmin"],"ymin":["bbox","ymin"],"xmax":["bbox","xmax"],"ymax":["bbox","ymax"]}}}}}"#;
+
+fn point_wkb(x: f64, y: f64) -> Vec<u8> {
+    let mut wkb = vec![1, 1, 0, 0, 0];
+    wkb.extend(x.to_le_bytes());
+    wkb.extend(y.to_le_bytes());
+    wkb
+}
+
+fn write_geo_file(path: &std::path::Path, geo_metadata: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+    use arrow::array::{ArrayRef, BinaryArray, Float64Array, Int32Array, StructArray};
+    use arrow::datatypes::{DataType, Field};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use parquet::format::KeyValue;
+
+    let mut geometries = (0..11).map(|i| point_wkb(i as f64, 2.0 * i as f64)).collect::<Vec<_>>();
+    let mut line_string = vec![1, 2, 0, 0, 0, 2, 0, 0, 0];
+    for value in [11.0f64, 22.0, 12.0, 24.0] {
+        line_string.extend(value.to_le_bytes());
+    }
+    geometries.push(line_string);
+    let xmin = (0..12).map(|i| i as f64).collect::<Vec<_>>();
+    let xmax = (0..12).map(|i| if i == 11 { 12.0 } else { i as f64 }).collect::<Vec<_>>();
+    let bbox = StructArray::from(vec![
+        (Arc::new(Field::new("xmin", DataType::Float64, false)), Arc::new(Float64Array::from(xmin.clone())) as ArrayRef),
+        (Arc::new(Field::new("ymin", DataType::Float64, false)), Arc::new(Float64Array::from_iter_values(xmin.iter().map(|x| 2.0 * x)))),
+        (Arc::new(Field::new("xmax", DataType::Float64, false)), Arc::new(Float64Array::from(xmax.clone())) as ArrayRef),
+        (Arc::new(Field::new("ymax", DataType::Float64, false)), Arc::new(Float64Array::from_iter_values(xmax.iter().map(|x| 2.0 * x)))),
+    ]);
+    let id: ArrayRef = Arc::new(Int32Array::from_iter_values(0..12));
+    let geometry: ArrayRef = Arc::new(BinaryArray::from_iter_values(geometries));
+    let batch = RecordBatch::try_from_iter(vec![("id", id), ("geometry", geometry), ("bbox", Arc::new(bbox) as ArrayRef)])?;
+
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(4)
+        .set_key_value_metadata(Some(vec![KeyValue::new("geo".to_string(), geo_metadata.to_string())]))
+        .build();
+    let mut writer = ArrowWriter::try_new(std::fs::File::create(path)?, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+static DDL_POSTGRES_OUTPUT: &str = r#"-- warning: local: TIMESTAMP holds microseconds, the nanoseconds are truncated
+-- warning: point: structs are not supported, mapped to JSONB
+-- warning: attrs: maps are not supported, mapped to JSONB
+CREATE TABLE types (
+  id BIGINT NOT NULL,
+  small INTEGER,
+  price NUMERIC(10, 2),
+  created TIMESTAMPTZ NOT NULL,
+  local TIMESTAMP,
+  day DATE,
+  tags INTEGER[],
+  point JSONB,
+  attrs JSONB
+);
+"#;
+
+// a column of each kind of type the schema exports map: unsigned integers, decimals, timestamps
+// with and without UTC adjustment, dates, lists, structs and maps
+fn write_types_file(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+    use arrow::array::{
+        ArrayRef, Date32Array, Decimal128Array, Float64Array, Int32Builder, Int64Array, ListArray, MapBuilder,
+        StringBuilder, StructArray, TimestampMicrosecondArray, TimestampNanosecondArray, UInt16Array,
+    };
+    use arrow::datatypes::{DataType, Field, Int32Type};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+
+    let id: ArrayRef = Arc::new(Int64Array::from(vec![1, 2]));
+    let small: ArrayRef = Arc::new(UInt16Array::from(vec![Some(7), None]));
+    let price: ArrayRef = Arc::new(Decimal128Array::from(vec![Some(1250), Some(-99)]).with_precision_and_scale(10, 2)?);
+    let created: ArrayRef = Arc::new(TimestampMicrosecondArray::from(vec![1_600_000_000_000_000, 1_700_000_000_000_000]).with_timezone("UTC"));
+    let local: ArrayRef = Arc::new(TimestampNanosecondArray::from(vec![Some(1_600_000_000_000_000_000), None]));
+    let day: ArrayRef = Arc::new(Date32Array::from(vec![18_000, 19_000]));
+    let tags: ArrayRef = Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+        Some(vec![Some(1), Some(2)]),
+        None,
+    ]));
+    let point: ArrayRef = Arc::new(StructArray::from(vec![
+        (Arc::new(Field::new("x", DataType::Float64, false)), Arc::new(Float64Array::from(vec![1.5, 2.5])) as ArrayRef),
+        (Arc::new(Field::new("y", DataType::Float64, true)), Arc::new(Float64Array::from(vec![Some(3.5), None])) as ArrayRef),
+    ]));
+    let mut attrs = MapBuilder::new(None, StringBuilder::new(), Int32Builder::new());
+    attrs.keys().append_value("a");
+    attrs.values().append_value(1);
+    attrs.append(true)?;
+    attrs.append(false)?;
+    let attrs: ArrayRef = Arc::new(attrs.finish());
+
+    let batch = RecordBatch::try_from_iter_with_nullable(vec![
+        ("id", id, false),
+        ("small", small, true),
+        ("price", price, true),
+        ("created", created, false),
+        ("local", local, true),
+        ("day", day, true),
+        ("tags", tags, true),
+        ("point", point, true),
+        ("attrs", attrs, true),
+    ])?;
+    let mut writer = ArrowWriter::try_new(std::fs::File::create(path)?, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+// whether a JSON value conforms to the keywords of the JSON Schemas `schema --format json-schema`
+// writes, which leaves out the string patterns and formats
+fn json_schema_accepts(schema: &serde_json::Value, value: &serde_json::Value) -> bool {
+    use serde_json::Value;
+
+    let type_matches = |name: &str| match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => false,
+    };
+    let type_ok = match &schema["type"] {
+        Value::String(name) => type_matches(name),
+        Value::Array(names) => names.iter().filter_map(|n| n.as_str()).any(type_matches),
+        _ => true,
+    };
+    if !type_ok {
+        return false;
+    }
+    if let (Some(minimum), Some(n)) = (schema["minimum"].as_f64(), value.as_f64()) {
+        if n < minimum {
+            return false;
+        }
+    }
+    if let (Some(maximum), Some(n)) = (schema["maximum"].as_f64(), value.as_f64()) {
+        if n > maximum {
+            return false;
+        }
+    }
+    match value {
+        Value::Array(items) => items.iter().all(|item| json_schema_accepts(&schema["items"], item)),
+        Value::Object(object) => {
+            let required = schema["required"].as_array().cloned().unwrap_or_default();
+            required.iter().filter_map(|r| r.as_str()).all(|r| object.contains_key(r))
+                && object.iter().all(|(key, item)| match (&schema["properties"][key], &schema["additionalProperties"]) {
+                    (Value::Object(_), _) => json_schema_accepts(&schema["properties"][key], item),
+                    (_, Value::Bool(allowed)) => *allowed,
+                    (_, additional) => json_schema_accepts(additional, item),
+                })
+        }
+        _ => true,
+    }
+}
 
 mod integration {
-    use crate::{CAT_CSV_NO_HEADER_OUTPUT, CAT_CSV_OUTPUT, CAT_JSON_OUTPUT, CAT_OUTPUT, CITIES_PARQUET_PATH, MERGED_FILE_NAME, PEMS_1_PARQUET_PATH, PEMS_2_PARQUET_PATH, SAMPLE_PARTIAL_OUTPUT_1, SAMPLE_PARTIAL_OUTPUT_2, SCHEMA_OUTPUT, SIMPLE_PARQUET_PATH};
+    use crate::{CAT_CSV_VIRTUAL_COLUMNS_OUTPUT, CAT_VIRTUAL_COLUMNS_OUTPUT, CAT_CSV_NO_HEADER_OUTPUT, CAT_CSV_OUTPUT, CAT_CSV_TIMESTAMP_FORMAT_OUTPUT, CAT_JSON_OUTPUT, CAT_OUTPUT, CAT_RAW_TIMESTAMP_OUTPUT, CAT_TIMEZONE_OUTPUT, CITIES_PARQUET_PATH, MERGED_FILE_NAME, PEMS_1_PARQUET_PATH, PEMS_2_PARQUET_PATH, SAMPLE_PARTIAL_OUTPUT_1, SAMPLE_PARTIAL_OUTPUT_2, SCHEMA_OUTPUT, SIMPLE_PARQUET_PATH, TIMESTAMPS_FILE_NAME, write_timestamps_file, NUMBERS_FILE_NAME, SLICE_CSV_OUTPUT, GET_OUTPUT, write_numbers_file, PAGES_OUTPUT, write_dictionary_fallback_file, write_bloom_filter_file, write_lists_file, LEVELS_OUTPUT, GEO_METADATA, point_wkb, write_geo_file, write_types_file, DDL_POSTGRES_OUTPUT, json_schema_accepts};
     use assert_cmd::Command;
     
     use predicates::prelude::*;
@@ -96,6 +413,218 @@ mod integration {
         Ok(())
     }
 
+    #[test]
+    fn validate_cat_csv() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg(SIMPLE_PARQUET_PATH)
+            .arg("--csv")
+            .arg("--quiet");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::starts_with(CAT_CSV_OUTPUT));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_raw_timestamp() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join(TIMESTAMPS_FILE_NAME);
+        write_timestamps_file(&file_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg(&file_path)
+            .arg("--raw-timestamp")
+            .arg("--quiet");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff(CAT_RAW_TIMESTAMP_OUTPUT));
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_json_timezone() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join(TIMESTAMPS_FILE_NAME);
+        write_timestamps_file(&file_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg(&file_path)
+            .arg("--json")
+            .arg("--timezone")
+            .arg("Asia/Shanghai")
+            .arg("--quiet");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff(CAT_TIMEZONE_OUTPUT));
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_times() -> Result<(), Box<dyn std::error::Error>> {
+        use std::sync::Arc;
+        use arrow::array::{ArrayRef, Time32MillisecondArray, Time64MicrosecondArray};
+        use arrow::record_batch::RecordBatch;
+        use parquet::arrow::ArrowWriter;
+
+        let dir = tempdir()?;
+        let file_path = dir.path().join(TIMESTAMPS_FILE_NAME);
+        write_timestamps_file(&file_path)?;
+
+        // nanosecond timestamps are rendered like the other ones
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat").arg(&file_path).arg("--quiet");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("local: 2023-11-14 22:13:20 +00:00"));
+
+        let times_path = dir.path().join("times.parquet");
+        let alarm: ArrayRef = Arc::new(Time32MillisecondArray::from(vec![Some(25_200_250), None]));
+        let lap: ArrayRef = Arc::new(Time64MicrosecondArray::from(vec![Some(1_500_000), Some(86_399_999_999)]));
+        let batch = RecordBatch::try_from_iter(vec![("alarm", alarm), ("lap", lap)])?;
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(&times_path)?, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("head").arg(&times_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff("{alarm: 07:00:00.250, lap: 00:00:01.500}\n{alarm: null, lap: 23:59:59.999999}\n"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat").arg("--json").arg("--quiet").arg(&times_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff("{\"alarm\":\"07:00:00.250\",\"lap\":\"00:00:01.500\"}\n{\"alarm\":null,\"lap\":\"23:59:59.999999\"}\n"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat").arg("--raw-timestamp").arg("--quiet").arg(&times_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff("{alarm: 25200250, lap: 1500000}\n{alarm: null, lap: 86399999999}\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_csv_timestamp_format() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join(TIMESTAMPS_FILE_NAME);
+        write_timestamps_file(&file_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg(&file_path)
+            .arg("--csv")
+            .arg("--timestamp-format")
+            .arg("%Y-%m-%d %H:%M")
+            .arg("--quiet");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::starts_with(CAT_CSV_TIMESTAMP_FORMAT_OUTPUT));
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_unknown_timezone() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg(SIMPLE_PARQUET_PATH)
+            .arg("--timezone")
+            .arg("Mars/Olympus_Mons");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("UnknownTimezone"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_virtual_columns() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--json")
+            .arg("--quiet")
+            .arg("--columns")
+            .arg("continent,_row_group")
+            .arg("--virtual-columns")
+            .arg("_file,_row_index")
+            .arg("--filter")
+            .arg("_row_index=1");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff(CAT_VIRTUAL_COLUMNS_OUTPUT));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_csv_virtual_columns() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg(SIMPLE_PARQUET_PATH)
+            .arg("--csv")
+            .arg("--quiet")
+            .arg("--columns")
+            .arg("foo,_file,_row_index");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff(CAT_CSV_VIRTUAL_COLUMNS_OUTPUT));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_csv_filter() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join(NUMBERS_FILE_NAME);
+        write_numbers_file(&file_path, 100, 10)?;
+
+        // the batches span row groups, the virtual columns still follow each row
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg(&file_path)
+            .arg("--csv")
+            .arg("--no-header")
+            .arg("--quiet")
+            .arg("--columns")
+            .arg("id,_row_group,_row_index")
+            .arg("--filter")
+            .arg("name=name-42");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff("42,4,42\n"));
+
+        // the values are compared as the default output prints them, whatever the format
+        for (value, rows) in [("0E0", 2693), ("0.0", 0)] {
+            for format in [vec![], vec!["--csv", "--no-header"]] {
+                let mut cmd = Command::cargo_bin("pqrs-learn")?;
+                let output = cmd.arg("cat")
+                    .arg(PEMS_1_PARQUET_PATH)
+                    .args(format)
+                    .arg("--quiet")
+                    .arg("--filter")
+                    .arg(format!("occupancy1={}", value))
+                    .output()?;
+                assert_eq!(String::from_utf8(output.stdout)?.lines().count(), rows);
+            }
+        }
+
+        dir.close()?;
+        Ok(())
+    }
+
     #[test]
     fn validate_cat_directory() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("pqrs-learn")?;
@@ -126,54 +655,1704 @@ mod integration {
     }
 
     #[test]
-    fn validate_merge() -> Result<(), Box<dyn std::error::Error>> {
+    fn validate_tail() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        let lines: Vec<&str> = CAT_OUTPUT.split("\n").collect();
+        cmd.arg("tail")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("-n")
+            .arg("1");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains(lines[2]).and(predicate::str::contains(lines[0]).not()));
+        Ok(())
+    }
+
+    #[test]
+    fn validate_slice_csv() -> Result<(), Box<dyn std::error::Error>> {
         let dir = tempdir()?;
-        let file_path = dir.path().join(MERGED_FILE_NAME);
-        let file_name = file_path.to_str().unwrap();
-        cmd.arg("merge")
-            .arg("--input")
-            .arg(PEMS_1_PARQUET_PATH)
-            .arg(PEMS_2_PARQUET_PATH)
-            .arg("--output")
-            .arg(file_name);
-        cmd.assert().success();
+        let file_path = dir.path().join(NUMBERS_FILE_NAME);
+        write_numbers_file(&file_path, 100, 10)?;
 
-        let mut rowcount_cmd = Command::cargo_bin("pqrs-learn")?;
-        rowcount_cmd.arg("rowcount").arg(file_name);
-        rowcount_cmd
-            .assert()
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("slice")
+            .arg(&file_path)
+            .arg("--offset")
+            .arg("25")
+            .arg("--limit")
+            .arg("3")
+            .arg("--csv")
+            .arg("--no-header");
+        cmd.assert()
             .success()
-            .stdout(predicate::str::contains("5573 rows"));
+            .stdout(predicate::str::diff(SLICE_CSV_OUTPUT));
 
         dir.close()?;
         Ok(())
     }
 
     #[test]
-    fn validate_rowcount() -> Result<(), Box<dyn std::error::Error>> {
+    fn validate_slice_across_row_groups() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join(NUMBERS_FILE_NAME);
+        write_numbers_file(&file_path, 100, 10)?;
+
         let mut cmd = Command::cargo_bin("pqrs-learn")?;
-        cmd.arg("rowcount").arg(CITIES_PARQUET_PATH);
+        cmd.arg("slice")
+            .arg(&file_path)
+            .arg("--offset")
+            .arg("8")
+            .arg("--limit")
+            .arg("4")
+            .arg("--json");
         cmd.assert()
             .success()
-            .stdout(predicate::str::contains("3 rows"));
+            .stdout(predicate::str::starts_with(r#"{"id":8,"name":"name-8"}"#)
+                .and(predicate::str::ends_with("{\"id\":11,\"name\":\"name-11\"}\n")));
 
+        dir.close()?;
         Ok(())
     }
 
     #[test]
-    fn validate_sample() -> Result<(), Box<dyn std::error::Error>> {
+    fn validate_get() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join(NUMBERS_FILE_NAME);
+        write_numbers_file(&file_path, 100, 10)?;
+
         let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("get")
+            .arg(&file_path)
+            .arg("99,25,3-4")
+            .arg("--location");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff(GET_OUTPUT))
+            .stderr(predicate::str::contains("row 25: row group 2, row 5 in group, pages: id #1"));
 
-        cmd.arg("sample")
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_get_json_across_pages() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join(NUMBERS_FILE_NAME);
+        write_numbers_file(&file_path, 100, 20)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("get")
+            .arg(&file_path)
+            .arg("6-9,17,18446744073709551614")
+            .arg("--json");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("RowOutOfRange"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("get")
+            .arg(&file_path)
+            .arg("6-9,17,38")
+            .arg("--json");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff(concat!(
+                "{\"id\":6,\"name\":\"name-6\"}\n",
+                "{\"id\":7,\"name\":\"name-7\"}\n",
+                "{\"id\":8,\"name\":\"name-8\"}\n",
+                "{\"id\":9,\"name\":\"name-9\"}\n",
+                "{\"id\":17,\"name\":\"name-17\"}\n",
+                "{\"id\":38,\"name\":\"name-38\"}\n")));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("get")
+            .arg(&file_path)
+            .arg("18446744073709551615");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("InvalidRowList"));
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_get_out_of_range() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("get")
             .arg(CITIES_PARQUET_PATH)
-            .arg("--records")
-            .arg("1");
+            .arg("1,3");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("RowOutOfRange"));
 
-        cmd.assert().success().stdout(
-            predicate::str::contains(SAMPLE_PARTIAL_OUTPUT_2)
-                .and(predicate::str::starts_with(SAMPLE_PARTIAL_OUTPUT_1)));
+        Ok(())
+    }
+
+    #[test]
+    fn validate_pages() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("pages")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--column")
+            .arg("continent")
+            .arg("--values");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains(PAGES_OUTPUT));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_pages_json() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("dump")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--json");
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let output = String::from_utf8(output)?;
+
+        assert_eq!(output.lines().count(), 4);
+        assert!(output.contains("\"page_type\":\"DICTIONARY_PAGE\",\"offset\":4,"));
+        Ok(())
+    }
 
+    #[test]
+    fn validate_dictionary() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("dictionary")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--column")
+            .arg("continent");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("2 entries"))
+            .stdout(predicate::str::contains("values: [Europe, North America]"))
+            .stdout(predicate::str::contains("No dictionary fallback in column continent"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_dictionary_fallback() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("fallback.parquet");
+        write_dictionary_fallback_file(&file_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("dictionary")
+            .arg(&file_path)
+            .arg("--column")
+            .arg("id");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("is PLAIN after"))
+            .stdout(predicate::str::contains("Dictionary fallback in column id in row groups: 0"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_page_index() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join(NUMBERS_FILE_NAME);
+        write_numbers_file(&file_path, 10, 10)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("page-index")
+            .arg(&file_path)
+            .arg("--column")
+            .arg("id");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("first row 4, min 4, max 7, nulls 0"))
+            .stdout(predicate::str::contains("first row 8, min 8, max 9, nulls 0"))
+            .stdout(predicate::str::contains("Every column has a column index and an offset index"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_page_index_missing() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("page-index")
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Row group 0, column continent: no column index nor offset index"))
+            .stdout(predicate::str::contains("Column country.name has no offset index in row groups: 0"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_bloom() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("bloom.parquet");
+        write_bloom_filter_file(&file_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("bloom")
+            .arg(&file_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Row group 2, column name: bloom filter @"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("bloom")
+            .arg(&file_path)
+            .arg("--column")
+            .arg("name")
+            .arg("--value")
+            .arg("name-15");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Row group 0: name-15 is absent"))
+            .stdout(predicate::str::contains("Row groups which might contain name-15: [1]"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_filter_bloom() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("bloom.parquet");
+        write_bloom_filter_file(&file_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("--debug")
+            .arg("cat")
+            .arg(&file_path)
+            .arg("--quiet")
+            .arg("--filter")
+            .arg("id=25")
+            .arg("-V")
+            .arg("_row_index");
+        cmd.assert()
+            .success()
+            .stdout("{id: 25, name: \"name-25\", _row_index: 25}\n")
+            .stderr(predicate::str::contains("Skipping row group 0"))
+            .stderr(predicate::str::contains("Skipping row group 2").not());
+
+        // only the bloom filters of the filtered columns are read, a broken one of another
+        // column goes unnoticed
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        let reader = SerializedFileReader::new(std::fs::File::open(&file_path)?)?;
+        let offset = reader.metadata().row_group(0).column(1).bloom_filter_offset().unwrap() as usize;
+        let mut bytes = std::fs::read(&file_path)?;
+        bytes[offset..offset + 8].fill(0xff);
+        std::fs::write(&file_path, &bytes)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg(&file_path)
+            .arg("--quiet")
+            .arg("--filter")
+            .arg("id=25");
+        cmd.assert()
+            .success()
+            .stdout("{id: 25, name: \"name-25\"}\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_levels() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("lists.parquet");
+        write_lists_file(&file_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("levels")
+            .arg(&file_path)
+            .arg("--column")
+            .arg("ids.list.item");
+        cmd.assert()
+            .success()
+            .stdout(LEVELS_OUTPUT);
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_footer() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("footer")
+            .arg(CITIES_PARQUET_PATH);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let footer: serde_json::Value = serde_json::from_slice(&output)?;
+
+        assert_eq!(footer["footer"]["metadata_length"], 388);
+        assert_eq!(footer["problems"].as_array().map(|p| p.len()), Some(0));
+        assert_eq!(footer["metadata"]["num_rows"], 3);
+        assert_eq!(footer["metadata"]["schema"][1]["converted_type"], "UTF8");
+        let column = &footer["metadata"]["row_groups"][0]["columns"][0];
+        assert_eq!(column["path"], "continent");
+        assert_eq!(column["statistics"]["max"], "North America");
+        Ok(())
+    }
+
+    #[test]
+    fn validate_footer_truncated() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("truncated.parquet");
+        let bytes = std::fs::read(CITIES_PARQUET_PATH)?;
+        std::fs::write(&file_path, &bytes[..100])?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("footer")
+            .arg(&file_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("\"footer_magic\":false"))
+            .stdout(predicate::str::contains("the file does not end with PAR1"))
+            .stdout(predicate::str::contains("\"metadata\":null"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_layout() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("layout")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--bar")
+            .arg("--width")
+            .arg("20");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("           0            4          4  header magic"))
+            .stdout(predicate::str::contains(
+                "           4           44         40  row group 0, column continent: dictionary page",
+            ))
+            .stdout(predicate::str::contains("         470          858        388  file metadata"))
+            .stdout(predicate::str::contains("No gaps nor overlaps"))
+            .stdout(predicate::str::contains("[dDDDDDDDDDDFFFFFFFFF]"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_layout_bloom_filters() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("bloom.parquet");
+        write_bloom_filter_file(&file_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("layout")
+            .arg(&file_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("row group 2, column name: bloom filter"))
+            .stdout(predicate::str::contains("row group 0, column id: column metadata"))
+            .stdout(predicate::str::contains("bloom filters"))
+            .stdout(predicate::str::contains("No gaps nor overlaps"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_verify() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("verify")
+            .arg(CITIES_PARQUET_PATH)
+            .arg(PEMS_1_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stdout(format!("{}: OK\n{}: OK\n", CITIES_PARQUET_PATH, PEMS_1_PARQUET_PATH));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_verify_truncated() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("truncated.parquet");
+        let bytes = std::fs::read(CITIES_PARQUET_PATH)?;
+        std::fs::write(&file_path, &bytes[..500])?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("verify")
+            .arg(&file_path);
+        cmd.assert()
+            .failure()
+            .stdout(predicate::str::contains("2 problems"))
+            .stdout(predicate::str::contains("  the file does not end with PAR1"))
+            .stderr(predicate::str::contains("VerificationFailed(1)"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_verify_corrupt_page() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("corrupt.parquet");
+        let mut bytes = std::fs::read(PEMS_1_PARQUET_PATH)?;
+        // scrambles the snappy compressed data page of the first column
+        for byte in &mut bytes[300..340] {
+            *byte ^= 0xff;
+        }
+        std::fs::write(&file_path, &bytes)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("verify")
+            .arg(&file_path);
+        cmd.assert()
+            .failure()
+            .stdout(predicate::str::contains(
+                "  row group 0, column timeperiod, offset 4: the page can not be decompressed",
+            ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_verify_negative_page_size() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("negative.parquet");
+        let mut bytes = std::fs::read(CITIES_PARQUET_PATH)?;
+        // the zigzag encoded compressed size of the first page header, 27 becomes -27
+        bytes[9] = 0x35;
+        std::fs::write(&file_path, &bytes)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("verify")
+            .arg(&file_path);
+        cmd.assert()
+            .failure()
+            .stdout(predicate::str::contains("has a negative compressed size -27"))
+            .stderr(predicate::str::contains("VerificationFailed(1)"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_verify_negative_chunk_offset() -> Result<(), Box<dyn std::error::Error>> {
+        use parquet::format::FileMetaData;
+        use thrift::protocol::{TCompactInputProtocol, TCompactOutputProtocol, TSerializable};
+
+        let dir = tempdir()?;
+        let file_path = dir.path().join("negative.parquet");
+        let bytes = std::fs::read(CITIES_PARQUET_PATH)?;
+        let footer_length = u32::from_le_bytes(bytes[bytes.len() - 8..bytes.len() - 4].try_into()?) as usize;
+        let metadata_offset = bytes.len() - 8 - footer_length;
+        let mut metadata = FileMetaData::read_from_in_protocol(&mut TCompactInputProtocol::new(
+            &bytes[metadata_offset..bytes.len() - 8],
+        ))?;
+        let column = metadata.row_groups[0].columns[0].meta_data.as_mut().unwrap();
+        column.data_page_offset = -4;
+        column.dictionary_page_offset = None;
+        let mut footer = vec![];
+        metadata.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut footer))?;
+        let mut rewritten = bytes[..metadata_offset].to_vec();
+        rewritten.extend_from_slice(&footer);
+        rewritten.extend_from_slice(&(footer.len() as u32).to_le_bytes());
+        rewritten.extend_from_slice(b"PAR1");
+        std::fs::write(&file_path, &rewritten)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("verify")
+            .arg(&file_path);
+        cmd.assert()
+            .failure()
+            .stdout(predicate::str::contains("  row group 0, column continent: the column chunk can not be located"))
+            .stdout(predicate::str::contains("starts at a negative offset -4"))
+            .stderr(predicate::str::contains("panicked").not());
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("pages")
+            .arg(&file_path);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("starts at a negative offset -4"))
+            .stderr(predicate::str::contains("panicked").not());
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_recover() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let sibling_path = dir.path().join("sibling.parquet");
+        write_bloom_filter_file(&sibling_path)?;
+        // cuts the file in the middle of its last row group
+        let file_path = dir.path().join("truncated.parquet");
+        let bytes = std::fs::read(&sibling_path)?;
+        std::fs::write(&file_path, &bytes[..1000])?;
+        let output_path = dir.path().join("recovered.parquet");
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("recover")
+            .arg(&file_path)
+            .arg("--schema")
+            .arg(&sibling_path)
+            .arg("--output")
+            .arg(&output_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Scanned 1000 bytes"))
+            .stdout(predicate::str::contains("dropped 1 pages from offset 869, they hold no data page"))
+            .stdout(predicate::str::contains("Recovered 20 rows in 2 row groups"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg(&output_path)
+            .arg("--filter")
+            .arg("id=15");
+        cmd.assert()
+            .success()
+            .stdout("{id: 15, name: \"name-15\"}\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_recover_corrupt_row_group() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let sibling_path = dir.path().join("sibling.parquet");
+        write_bloom_filter_file(&sibling_path)?;
+        // scrambles the pages of the second row group, and drops the footer
+        let file_path = dir.path().join("corrupt.parquet");
+        let mut bytes = std::fs::read(&sibling_path)?;
+        for byte in &mut bytes[700..720] {
+            *byte = 0xff;
+        }
+        std::fs::write(&file_path, &bytes[..bytes.len() - 8])?;
+        let output_path = dir.path().join("recovered.parquet");
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("recover")
+            .arg(&file_path)
+            .arg("--schema")
+            .arg(&sibling_path)
+            .arg("--output")
+            .arg(&output_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Recovered 20 rows in 2 row groups"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("verify")
+            .arg(&output_path);
+        cmd.assert()
+            .success();
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_recover_schema_file() -> Result<(), Box<dyn std::error::Error>> {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use parquet::schema::printer::print_schema;
+
+        let dir = tempdir()?;
+        let schema_path = dir.path().join("schema.txt");
+        let reader = SerializedFileReader::new(std::fs::File::open(PEMS_1_PARQUET_PATH)?)?;
+        let mut schema = vec![];
+        print_schema(&mut schema, reader.metadata().file_metadata().schema());
+        std::fs::write(&schema_path, schema)?;
+        // keeps the pages only, the codec of the file is then guessed
+        let file_path = dir.path().join("truncated.parquet");
+        let bytes = std::fs::read(PEMS_1_PARQUET_PATH)?;
+        std::fs::write(&file_path, &bytes[..13071])?;
+        let output_path = dir.path().join("recovered.parquet");
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("recover")
+            .arg(&file_path)
+            .arg("--schema")
+            .arg(&schema_path)
+            .arg("--output")
+            .arg(&output_path);
+        cmd.assert()
+            .success()
+            .stdout("Scanned 13071 bytes of ".to_owned()
+                + file_path.to_str().unwrap()
+                + ": 34 pages in 1 row groups\nRecovered 2693 rows in 1 row groups into "
+                + output_path.to_str().unwrap()
+                + "\n");
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("rowcount")
+            .arg(&output_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("2693 rows"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_lint() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("lint")
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert()
+            .failure()
+            .stdout(predicate::str::contains("data/cities.parquet: 13 findings"))
+            .stdout(predicate::str::contains(
+                "  error [buggy-statistics-writer] the min and max statistics of column continent",
+            ))
+            .stdout(predicate::str::contains(
+                "  info [converted-type-without-logical-type] field country.city has the converted type LIST",
+            ))
+            .stderr(predicate::str::contains("LintFailed(3, \"error\")"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("lint")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--severity")
+            .arg("buggy-statistics-writer=warning")
+            .arg("--disable")
+            .arg("uncompressed-column:continent");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("data/cities.parquet: 12 findings"))
+            .stdout(predicate::str::contains("  warning [buggy-statistics-writer]"))
+            .stdout(predicate::str::contains("column continent is not compressed").not());
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_lint_json() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("bloom.parquet");
+        write_bloom_filter_file(&file_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("lint")
+            .arg(&file_path)
+            .arg("--json")
+            .arg("--fail-on")
+            .arg("warning")
+            .arg("--disable")
+            .arg("uncompressed-column");
+        let output = cmd.assert().failure().get_output().stdout.clone();
+        let lint: serde_json::Value = serde_json::from_slice(&output)?;
+
+        let findings = lint["findings"].as_array().unwrap();
+        let rules = findings.iter().map(|f| f["rule"].as_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(rules, vec!["row-group-too-small", "row-group-too-small"]);
+        assert_eq!(findings[1]["severity"], "warning");
+        assert_eq!(findings[1]["row_groups"], serde_json::json!([1]));
+        Ok(())
+    }
+
+    #[test]
+    fn validate_lint_dictionary_once_per_column() -> Result<(), Box<dyn std::error::Error>> {
+        use std::sync::Arc;
+        use arrow::array::{ArrayRef, Int64Array};
+        use arrow::record_batch::RecordBatch;
+        use parquet::arrow::ArrowWriter;
+        use parquet::file::properties::WriterProperties;
+
+        // the dictionary of the distinct ids holds more than half of the values
+        let dir = tempdir()?;
+        let file_path = dir.path().join("dictionary.parquet");
+        let id: ArrayRef = Arc::new(Int64Array::from_iter_values(0..2000));
+        let batch = RecordBatch::try_from_iter(vec![("id", id)])?;
+        let props = WriterProperties::builder()
+            .set_dictionary_pagesize_limit(8192)
+            .build();
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(&file_path)?, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("lint")
+            .arg(&file_path)
+            .arg("--json");
+        let output = cmd.assert().get_output().stdout.clone();
+        let lint: serde_json::Value = serde_json::from_slice(&output)?;
+
+        let findings = lint["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|f| f["rule"] == "high-cardinality-dictionary")
+            .collect::<Vec<_>>();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0]["row_groups"], serde_json::json!([0]));
+        assert_eq!(findings[0]["message"], "column id has dictionaries of 1024 entries for 2000 values in 1 row groups");
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_advise() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("advise")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg("--column")
+            .arg("flow1")
+            .arg("--codecs")
+            .arg("uncompressed,zstd:3");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Advice for data/pems-1.snappy.parquet, from row groups [0]:"))
+            .stdout(predicate::str::contains("Column flow1, 65 Bytes as stored:"))
+            .stdout(predicate::str::contains("DELTA_BINARY_PACKED      ZSTD(3)"))
+            .stdout(predicate::str::contains("dictionary               UNCOMPRESSED"))
+            .stdout(predicate::str::contains(".set_column_compression(ColumnPath::from(\"flow1\"), "))
+            .stdout(predicate::str::contains("occupancy1").not());
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("advise")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--codecs")
+            .arg("snappy,zstd:99");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("UnknownCompression(\"zstd:99\")"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_advise_json() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("advise")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--codecs")
+            .arg("snappy")
+            .arg("--json");
+        let output = cmd.output()?;
+        assert!(output.status.success());
+        let advice: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let columns = advice["columns"].as_array().unwrap();
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[1]["column"], "country");
+        assert_eq!(columns[1]["leaves"][1], serde_json::json!(["country", "city", "bag", "array_element"]));
+        // dictionary and plain on both columns, the delta encodings on the string column
+        assert_eq!(columns[0]["trials"].as_array().unwrap().len(), 4);
+        assert_eq!(columns[1]["trials"].as_array().unwrap().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_bench() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("bench")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--runs")
+            .arg("2")
+            .arg("--batch-size")
+            .arg("2")
+            .arg("--column")
+            .arg("country");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Benchmark of data/cities.parquet, 2 runs after 1 warmup runs, times in ms:",
+            ))
+            .stdout(predicate::str::contains("\nfooter "))
+            .stdout(predicate::str::contains("arrow, batches of 2"))
+            .stdout(predicate::str::contains("column country"))
+            .stdout(predicate::str::contains("column continent").not());
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("bench")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--batch-size")
+            .arg("1024,0");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("invalid value '0' for '--batch-size"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_bench_json() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("bench")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg("--runs")
+            .arg("3")
+            .arg("--warmup")
+            .arg("0")
+            .arg("--no-row-iter")
+            .arg("--column")
+            .arg("flow1")
+            .arg("--json");
+        let output = cmd.output()?;
+        assert!(output.status.success());
+        let bench: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let measures = bench["measures"].as_array().unwrap();
+        let names = measures.iter().map(|m| m["name"].as_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["footer", "arrow, batches of 1024", "arrow, batches of 8192", "column flow1"]
+        );
+        assert_eq!(measures[0]["rows"], serde_json::Value::Null);
+        for measure in &measures[1..] {
+            assert_eq!(measure["rows"], 2693);
+            assert_eq!(measure["runs"].as_array().unwrap().len(), 3);
+            let summary = &measure["summary"];
+            assert!(summary["min"].as_f64() <= summary["median"].as_f64());
+            assert!(summary["median"].as_f64() <= summary["max"].as_f64());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_meta() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let file_path = dir.path().join("cities.parquet");
+        std::fs::copy(CITIES_PARQUET_PATH, &file_path)?;
+        let original = std::fs::read(CITIES_PARQUET_PATH)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("meta").arg("set").arg(&file_path).arg("lineage=etl-42").arg("owner=data");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("cities.parquet: 2 entries\nlineage: etl-42\nowner: data\n"));
+
+        // the data is copied verbatim, only the footer changes
+        let edited = std::fs::read(&file_path)?;
+        assert_eq!(edited[..466], original[..466]);
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("meta").arg("delete").arg(&file_path).arg("owner").arg("--json");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff("{\"lineage\":\"etl-42\"}\n"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("meta").arg("delete").arg(&file_path).arg("owner");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("UnknownMetadataKey(\"owner\")"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("meta").arg("set").arg(&file_path).arg("owner");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("InvalidKeyValue(\"owner\")"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat").arg(&file_path);
+        cmd.assert().success().stdout(predicate::str::contains(CAT_OUTPUT));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_meta_import() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let json_path = dir.path().join("metadata.json");
+        let output_path = dir.path().join("imported.parquet");
+        std::fs::write(&json_path, r#"{"lineage":"etl-42","empty":null}"#)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("meta")
+            .arg("import")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg(&json_path)
+            .arg("--output")
+            .arg(&output_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("imported.parquet: 3 entries\nempty\nlineage: etl-42\n"))
+            .stdout(predicate::str::contains("org.apache.spark.sql.parquet.row.metadata: {"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("meta")
+            .arg("import")
+            .arg(&output_path)
+            .arg(&json_path)
+            .arg("--replace")
+            .arg("--output")
+            .arg(&output_path);
+        cmd.assert().failure().stderr(predicate::str::contains("FileExists"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("meta").arg("import").arg(&output_path).arg(&json_path).arg("--replace");
+        cmd.assert().success().stdout(predicate::str::contains("imported.parquet: 2 entries\n"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("meta").arg("get").arg(&output_path).arg("lineage").arg("--json");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::diff("{\"lineage\":\"etl-42\"}\n"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("rowcount").arg(&output_path);
+        cmd.assert().success().stdout(predicate::str::contains("2693"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_schema_embedded() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join(TIMESTAMPS_FILE_NAME);
+        write_timestamps_file(&file_path)?;
+
+        let pandas = r#"pandas={"index_columns":[{"kind":"range","name":null,"start":0,"stop":1,"step":1}],"columns":[{"name":"event","field_name":"event","pandas_type":"datetimetz","numpy_type":"datetime64[ns]","metadata":{"timezone":"UTC"}},{"name":"when","field_name":"when","pandas_type":"datetime","numpy_type":"datetime64[ns]","metadata":null}],"creator":{"library":"pyarrow","version":"12.0.0"},"pandas_version":"2.0.1"}"#;
+        let spark = r#"org.apache.spark.sql.parquet.row.metadata={"type":"struct","fields":[{"name":"event","type":"timestamp","nullable":true,"metadata":{}},{"name":"local","type":"string","nullable":false,"metadata":{}},{"name":"day","type":"date","nullable":true,"metadata":{}}]}"#;
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("meta").arg("set").arg(&file_path).arg(pandas).arg(spark);
+        cmd.assert().success();
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg(&file_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "ARROW:schema:\n  event: Timestamp(Millisecond, Some(\"UTC\")), not null\n",
+            ))
+            .stdout(predicate::str::contains(
+                "pandas (written by pyarrow 12.0.0, pandas 2.0.1):\n  index: range(0, 1, 1)\n",
+            ))
+            .stdout(predicate::str::contains("org.apache.spark.sql.parquet.row.metadata:\n  event: timestamp\n"))
+            .stdout(predicate::str::contains(
+                "  field local is a string in org.apache.spark.sql.parquet.row.metadata, a timestamp in the parquet schema\n",
+            ))
+            .stdout(predicate::str::contains("  pandas has column when, the parquet schema does not\n"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        let output = cmd.arg("schema").arg("--json").arg(&file_path).output()?;
+        let schema: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let embedded = &schema["embedded_schemas"];
+        assert_eq!(embedded["arrow"]["fields"][0]["timezone"], "UTC");
+        assert_eq!(embedded["pandas"]["columns"][0]["metadata"]["timezone"], "UTC");
+        assert_eq!(embedded["spark"]["fields"][1]["nullable"], false);
+        assert_eq!(embedded["disagreements"].as_array().unwrap().len(), 4);
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg(PEMS_1_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("  flow1: integer\n"))
+            .stdout(predicate::str::contains("The embedded schemas agree with the parquet schema"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_geo() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("geo.parquet");
+        write_geo_file(&file_path, GEO_METADATA)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("geo").arg(&file_path).arg("--bbox").arg("5,0,20,5");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("GeoParquet 1.1.0, primary column geometry\n"))
+            .stdout(predicate::str::contains(
+                "Column geometry: WKB, types Point, LineString, CRS OGC:CRS84 (default), bbox [0, 0, 12, 24], covering bbox.xmin, bbox.ymin, bbox.xmax, bbox.ymax\n",
+            ))
+            .stdout(predicate::str::contains("Bounding boxes of geometry: [0, 0, 12, 24]\n"))
+            .stdout(predicate::str::contains(
+                "  row group 0: 4 rows, [0, 0, 3, 6] from the covering statistics, pruned\n",
+            ))
+            .stdout(predicate::str::contains(
+                "  row group 2: 4 rows, [8, 16, 12, 24] from the covering statistics, pruned\n",
+            ))
+            .stdout(predicate::str::contains("Row groups intersecting [5, 0, 20, 5]: 0 of 3\n"))
+            .stdout(predicate::str::contains("No problems"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("geo").arg(&file_path).arg("--decode").arg("--json");
+        let output = cmd.output()?;
+        assert!(output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        assert_eq!(report["row_groups"][2]["source"], "geometries");
+        assert_eq!(report["row_groups"][2]["geometry_types"], serde_json::json!(["LineString", "Point"]));
+        assert_eq!(
+            report["row_groups"][1]["bbox"],
+            serde_json::json!({"xmin": 4.0, "ymin": 8.0, "xmax": 7.0, "ymax": 14.0})
+        );
+
+        // the declared geometry types and bbox do not match the geometries
+        let bad_path = dir.path().join("bad.parquet");
+        let bad_metadata = GEO_METADATA
+            .replace(r#""Point","LineString""#, r#""Point","Circle""#)
+            .replace("[0,0,12,24]", "[0,0,10,20]");
+        write_geo_file(&bad_path, &bad_metadata)?;
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("geo").arg(&bad_path).arg("--decode");
+        cmd.assert()
+            .failure()
+            .stdout(predicate::str::contains("Circle"))
+            .stdout(predicate::str::contains(
+                "  row group 2 spans [8, 16, 12, 24], out of the declared bbox [0, 0, 10, 20]\n",
+            ))
+            .stdout(predicate::str::contains("  row group 2 holds LineString geometries, which are not declared\n"))
+            .stderr(predicate::str::contains("InvalidGeoMetadata"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("geo").arg(CITIES_PARQUET_PATH);
+        cmd.assert().failure().stderr(predicate::str::contains("has no geo metadata"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_geometry() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("geo.parquet");
+        write_geo_file(&file_path, GEO_METADATA)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg("--geometry")
+            .arg("wkt")
+            .arg("--bbox")
+            .arg("9.5,0,20,30")
+            .arg("--columns")
+            .arg("id,geometry")
+            .arg(&file_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("{id: 10, geometry: \"POINT (10 20)\"}\n"))
+            .stdout(predicate::str::contains("{id: 11, geometry: \"LINESTRING (11 22, 12 24)\"}\n"))
+            .stdout(predicate::str::contains("id: 9,").not());
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg("--csv")
+            .arg("--geometry")
+            .arg("wkt")
+            .arg("--bbox")
+            .arg("2.5,0,3.5,10")
+            .arg("--columns")
+            .arg("id,geometry")
+            .arg(&file_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("id,geometry\n3,POINT (3 6)\n"))
+            .stdout(predicate::str::contains("\n4,").not());
+
+        for (bbox, error) in [("170,-10,-170,10", "xmin 170 is greater than xmax -170"), ("0,10,1,-10", "ymin 10 is greater than ymax -10")] {
+            let mut cmd = Command::cargo_bin("pqrs-learn")?;
+            cmd.arg("cat")
+                .arg(format!("--bbox={}", bbox))
+                .arg(&file_path);
+            cmd.assert()
+                .failure()
+                .stderr(predicate::str::contains(error));
+        }
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("head")
+            .arg("--json")
+            .arg("--geometry")
+            .arg("geojson")
+            .arg("-n")
+            .arg("1")
+            .arg(&file_path);
+        let output = cmd.output()?;
+        assert!(output.status.success());
+        let row: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        assert_eq!(row["geometry"], serde_json::json!({"type": "Point", "coordinates": [0.0, 0.0]}));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_nested_geometry() -> Result<(), Box<dyn std::error::Error>> {
+        use std::sync::Arc;
+        use arrow::array::{ArrayRef, BinaryArray};
+        use arrow::record_batch::RecordBatch;
+        use parquet::arrow::ArrowWriter;
+        use parquet::file::properties::WriterProperties;
+        use parquet::format::KeyValue;
+
+        let dir = tempdir()?;
+        let file_path = dir.path().join("nested.parquet");
+        // geometry collections of a single geometry, nested far deeper than the stack allows
+        let mut wkb = [1, 7, 0, 0, 0, 1, 0, 0, 0].repeat(100_000);
+        wkb.extend(point_wkb(1.0, 2.0));
+        let geometry: ArrayRef = Arc::new(BinaryArray::from_iter_values([wkb]));
+        let batch = RecordBatch::try_from_iter(vec![("geometry", geometry)])?;
+        let geo_metadata = r#"{"version":"1.1.0","primary_column":"geometry","columns":{"geometry":{"encoding":"WKB","geometry_types":[]}}}"#;
+        let props = WriterProperties::builder()
+            .set_key_value_metadata(Some(vec![KeyValue::new("geo".to_string(), geo_metadata.to_string())]))
+            .build();
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(&file_path)?, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat")
+            .arg("--geometry")
+            .arg("wkt")
+            .arg(&file_path);
+        cmd.assert()
+            .success()
+            .stdout("{geometry: \"invalid WKB: the WKB nests geometries deeper than 64 levels\"}\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_bbox_not_binary() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("geo.parquet");
+        write_geo_file(&file_path, r#"{"version":"1.1.0","primary_column":"id","columns":{"id":{"encoding":"WKB","geometry_types":[]}}}"#)?;
+
+        for format in [None, Some("--csv")] {
+            let mut cmd = Command::cargo_bin("pqrs-learn")?;
+            cmd.arg("cat")
+                .args(format)
+                .arg("--bbox")
+                .arg("0,0,1,1")
+                .arg(&file_path);
+            cmd.assert()
+                .failure()
+                .stderr(predicate::str::contains("does not hold binary values"));
+        }
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_broken_geo_metadata() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("simple.parquet");
+        std::fs::copy(SIMPLE_PARQUET_PATH, &file_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("meta").arg("set").arg(&file_path).arg(r#"geo={"version":"1.0.0"}"#);
+        cmd.assert().success();
+
+        // the geo metadata is only read when geometries are rendered
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("head").arg("-n").arg("1").arg(&file_path);
+        cmd.assert().success();
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat").arg("--csv").arg(&file_path);
+        cmd.assert().success().stdout(predicate::str::starts_with("foo,bar\n"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat").arg("--geometry").arg("wkt").arg(&file_path);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("InvalidGeoMetadata"));
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_schema_ddl() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("types.parquet");
+        write_types_file(&file_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg("--format").arg("ddl").arg("--dialect").arg("postgres").arg(&file_path);
+        cmd.assert().success().stdout(predicate::str::diff(DDL_POSTGRES_OUTPUT));
+
+        // the nested types map
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg("-f").arg("ddl").arg("--dialect").arg("duckdb").arg("--table").arg("Events").arg(&file_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("CREATE TABLE \"Events\" (\n"))
+            .stdout(predicate::str::contains("  small USMALLINT,\n"))
+            .stdout(predicate::str::contains("  local TIMESTAMP_NS,\n"))
+            .stdout(predicate::str::contains("  tags INTEGER[],\n"))
+            .stdout(predicate::str::contains("  point STRUCT(x DOUBLE, y DOUBLE),\n"))
+            .stdout(predicate::str::contains("  attrs MAP(VARCHAR, INTEGER)\n);\n"))
+            .stdout(predicate::str::contains("-- warning").not());
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg("-f").arg("ddl").arg("--dialect").arg("spark").arg(&file_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("  created TIMESTAMP NOT NULL,\n"))
+            .stdout(predicate::str::contains("  local TIMESTAMP_NTZ,\n"))
+            .stdout(predicate::str::contains("  point STRUCT<x: DOUBLE, y: DOUBLE>,\n"))
+            .stdout(predicate::str::contains("  attrs MAP<STRING, INT>\n)\nUSING parquet;\n"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg("-f").arg("ddl").arg("--dialect").arg("bigquery").arg(&file_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("-- warning: tags: arrays can not be null"))
+            .stdout(predicate::str::contains("-- warning: attrs: maps are not supported"))
+            .stdout(predicate::str::contains("  price NUMERIC(10, 2),\n"))
+            .stdout(predicate::str::contains("  local DATETIME,\n"))
+            .stdout(predicate::str::contains("  attrs ARRAY<STRUCT<key STRING, value INT64>>\n);\n"));
+
+        // BigQuery has no arrays of arrays, which a list of maps would be
+        let message_path = dir.path().join("maps.txt");
+        std::fs::write(
+            &message_path,
+            "message maps {\n  OPTIONAL group maps (LIST) {\n    REPEATED group list {\n      OPTIONAL group element (MAP) {\n        REPEATED group key_value {\n          REQUIRED BYTE_ARRAY key (UTF8);\n          OPTIONAL INT32 value;\n        }\n      }\n    }\n  }\n}\n",
+        )?;
+        let maps_path = dir.path().join("maps.parquet");
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("create").arg("--schema").arg(&message_path).arg(&maps_path);
+        cmd.assert().success();
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg("-f").arg("ddl").arg("--dialect").arg("bigquery").arg(&maps_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("-- warning: maps: lists of lists or maps are not supported"))
+            .stdout(predicate::str::contains("  maps JSON\n"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg("-f").arg("ddl").arg("--json").arg(&file_path);
+        cmd.assert().failure().stderr(predicate::str::contains("cannot be used with"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_schema_export() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let types_path = dir.path().join("types.parquet");
+        write_types_file(&types_path)?;
+        let types_path = types_path.display().to_string();
+
+        // the exported schemas agree on the fields and describe the rows of the files
+        for path in [CITIES_PARQUET_PATH, PEMS_1_PARQUET_PATH, SIMPLE_PARQUET_PATH, types_path.as_str()] {
+            let mut cmd = Command::cargo_bin("pqrs-learn")?;
+            let output = cmd.arg("schema").arg("--format").arg("json-schema").arg(path).output()?;
+            assert!(output.status.success());
+            let json_schema: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+            assert_eq!(json_schema["$schema"], "https://json-schema.org/draft/2020-12/schema");
+
+            let mut cmd = Command::cargo_bin("pqrs-learn")?;
+            let output = cmd.arg("head").arg("--json").arg("-n").arg("50").arg(path).output()?;
+            assert!(output.status.success());
+            for line in String::from_utf8(output.stdout)?.lines() {
+                let row: serde_json::Value = serde_json::from_str(line)?;
+                assert!(json_schema_accepts(&json_schema, &row), "{} does not conform: {}", path, line);
+            }
+
+            let mut cmd = Command::cargo_bin("pqrs-learn")?;
+            let output = cmd.arg("schema").arg("--format").arg("avro").arg(path).output()?;
+            assert!(output.status.success());
+            let avro: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+            assert_eq!(avro["type"], "record");
+            let properties = json_schema["properties"].as_object().unwrap();
+            let required = json_schema["required"].as_array().cloned().unwrap_or_default();
+            let fields = avro["fields"].as_array().unwrap();
+            assert_eq!(fields.len(), properties.len());
+            for field in fields {
+                let name = field["name"].as_str().unwrap();
+                assert!(properties.contains_key(name), "{} misses {}", path, name);
+                let nullable = field["type"].as_array().is_some_and(|union| union[0] == "null");
+                assert_eq!(nullable, !required.contains(&field["name"]), "{} {}", path, name);
+            }
+
+            let mut cmd = Command::cargo_bin("pqrs-learn")?;
+            let output = cmd.arg("schema").arg("--format").arg("proto").arg(path).output()?;
+            assert!(output.status.success());
+            let proto = String::from_utf8(output.stdout)?;
+            assert!(proto.starts_with("syntax = \"proto3\";\n"));
+            for (i, name) in fields.iter().map(|f| f["name"].as_str().unwrap()).enumerate() {
+                assert!(proto.contains(&format!(" {} = {};", name, i + 1)), "{} misses {}", path, name);
+            }
+        }
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg("-f").arg("proto").arg("--table").arg("event_v1").arg(&types_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("import \"google/protobuf/timestamp.proto\";\n"))
+            .stdout(predicate::str::contains("message EventV1 {\n  message Point {\n    double x = 1;\n    optional double y = 2;\n  }\n"))
+            .stdout(predicate::str::contains("  optional string price = 3; // DECIMAL(10, 2)\n"))
+            .stdout(predicate::str::contains("  google.protobuf.Timestamp created = 4;\n"))
+            .stdout(predicate::str::contains("  repeated int32 tags = 7;\n"))
+            .stdout(predicate::str::contains("  map<string, int32> attrs = 9;\n"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        let output = cmd.arg("schema").arg("-f").arg("avro").arg(&types_path).output()?;
+        let avro: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        assert_eq!(avro["fields"][2]["type"][1], serde_json::json!({"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}));
+        assert_eq!(avro["fields"][3]["type"], serde_json::json!({"type": "long", "logicalType": "timestamp-micros"}));
+        assert_eq!(avro["fields"][4]["type"][1]["logicalType"], "local-timestamp-nanos");
+        assert_eq!(avro["fields"][7]["type"][1]["name"], "types_point");
+        assert_eq!(avro["fields"][8]["type"][1], serde_json::json!({"type": "map", "values": ["null", "int"]}));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_create() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let types_path = dir.path().join("types.parquet");
+        write_types_file(&types_path)?;
+
+        // the output of schema, header and message type, gives an empty file of the same schema
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        let output = cmd.arg("schema").arg(&types_path).output()?;
+        let text = String::from_utf8(output.stdout)?;
+        let start = text.find("message ").unwrap();
+        let message = &text[start..start + text[start..].find("\n}\n").unwrap() + 3];
+        let message_path = dir.path().join("types.txt");
+        std::fs::write(&message_path, &text)?;
+        let empty_path = dir.path().join("empty.parquet");
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("create").arg("--schema").arg(&message_path).arg(&empty_path);
+        cmd.assert().success();
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg(&empty_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("num of rows: 0\n"))
+            .stdout(predicate::str::contains(message));
+
+        // the output of schema --json and rows printed by cat --json
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        let output = cmd.arg("schema").arg("--json").arg(&types_path).output()?;
+        let schema_path = dir.path().join("types.json");
+        std::fs::write(&schema_path, output.stdout)?;
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        let rows = cmd.arg("cat").arg("--json").arg("--quiet").arg(&types_path).output()?.stdout;
+        let rows_path = dir.path().join("rows.json");
+        std::fs::write(&rows_path, &rows)?;
+        let full_path = dir.path().join("full.parquet");
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("create").arg("-s").arg(&schema_path).arg("-i").arg(&rows_path).arg("-c").arg("zstd:3").arg(&full_path);
+        cmd.assert().success();
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat").arg("--json").arg("--quiet").arg(&full_path);
+        cmd.assert().success().stdout(predicate::str::diff(String::from_utf8(rows)?));
+
+        // a JSON Schema, and CSV rows
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        let output = cmd.arg("schema").arg("--format").arg("json-schema").arg(SIMPLE_PARQUET_PATH).output()?;
+        let json_schema_path = dir.path().join("simple.schema.json");
+        std::fs::write(&json_schema_path, output.stdout)?;
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        let rows = cmd.arg("cat").arg("--csv").arg("--quiet").arg(SIMPLE_PARQUET_PATH).output()?.stdout;
+        assert!(String::from_utf8(rows.clone())?.starts_with("foo,bar\n1,2\n10,20\n"));
+        let rows_path = dir.path().join("rows.csv");
+        std::fs::write(&rows_path, &rows)?;
+        let simple_path = dir.path().join("simple.parquet");
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("create").arg("-s").arg(&json_schema_path).arg("-i").arg(&rows_path).arg(&simple_path);
+        cmd.assert().success();
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat").arg("--csv").arg("--quiet").arg(&simple_path);
+        cmd.assert().success().stdout(predicate::str::starts_with("foo,bar\n1,2\n10,20\n"));
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg(&simple_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("  OPTIONAL INT32 foo;\n  OPTIONAL INT64 bar;\n"));
+
+        let invalid_path = dir.path().join("invalid.txt");
+        std::fs::write(&invalid_path, "message m { OPTIONAL INT33 a; }")?;
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("create").arg("-s").arg(&invalid_path).arg(dir.path().join("invalid.parquet"));
+        cmd.assert().failure().stderr(predicate::str::contains("ParquetError"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("create").arg("-s").arg(&message_path).arg(&empty_path);
+        cmd.assert().failure().stderr(predicate::str::contains("FileExists"));
+
+        // rows which can not be decoded leave no file behind
+        let bad_rows_path = dir.path().join("bad.json");
+        std::fs::write(&bad_rows_path, "{\"foo\": \"x\"}\n")?;
+        let bad_path = dir.path().join("bad.parquet");
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("create").arg("-s").arg(SIMPLE_PARQUET_PATH).arg("-i").arg(&bad_rows_path).arg(&bad_path);
+        cmd.assert().failure().stderr(predicate::str::contains("JsonError"));
+        assert!(!bad_path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_create_json_schema_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let export = |format: &str, path: &std::path::Path| -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+            let mut cmd = Command::cargo_bin("pqrs-learn")?;
+            let output = cmd.arg("schema").arg("--format").arg(format).arg(path).output()?;
+            assert!(output.status.success());
+            Ok(output.stdout)
+        };
+
+        for path in [SIMPLE_PARQUET_PATH, CITIES_PARQUET_PATH, PEMS_1_PARQUET_PATH, PEMS_2_PARQUET_PATH] {
+            let path = std::path::Path::new(path);
+            let schema_path = dir.path().join("schema.json");
+            std::fs::write(&schema_path, export("json-schema", path)?)?;
+            let created_path = dir.path().join(path.file_name().unwrap());
+            let mut cmd = Command::cargo_bin("pqrs-learn")?;
+            cmd.arg("create").arg("-s").arg(&schema_path).arg(&created_path);
+            cmd.assert().success();
+
+            // the avro fields are listed in order, the JSON Schema properties are not
+            assert_eq!(export("json-schema", &created_path)?, export("json-schema", path)?);
+            assert_eq!(export("avro", &created_path)?, export("avro", path)?);
+        }
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("schema").arg(dir.path().join("cities.parquet"));
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("OPTIONAL BYTE_ARRAY name (STRING);\n    OPTIONAL group city (LIST)"));
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_generate() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let types_path = dir.path().join("types.parquet");
+        write_types_file(&types_path)?;
+
+        let generate = |path: &std::path::Path| -> Result<(), Box<dyn std::error::Error>> {
+            let mut cmd = Command::cargo_bin("pqrs-learn")?;
+            cmd.arg("generate").arg("-s").arg(&types_path).arg("-n").arg("2500").arg("--seed").arg("7")
+                .arg("--values").arg("id=sequence(1)")
+                .arg("--values").arg("price=normal(100,15)")
+                .arg("--values").arg("created=uniform(2023-01-01,2023-12-31)")
+                .arg("--nulls").arg("small=0.5")
+                .arg("--distinct").arg("price=3")
+                .arg("--length").arg("tags=2")
+                .arg("--batch-size").arg("1000").arg("--row-group-size").arg("1000")
+                .arg(path);
+            cmd.assert().success();
+            Ok(())
+        };
+        let first_path = dir.path().join("first.parquet");
+        let second_path = dir.path().join("second.parquet");
+        generate(&first_path)?;
+        generate(&second_path)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("rowcount").arg(&first_path);
+        cmd.assert().success().stdout(predicate::str::contains("2500 rows"));
+
+        // the same seed draws the same rows
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        let rows = String::from_utf8(cmd.arg("cat").arg("--json").arg("--quiet").arg(&first_path).output()?.stdout)?;
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("cat").arg("--json").arg("--quiet").arg(&second_path);
+        cmd.assert().success().stdout(predicate::str::diff(rows.clone()));
+
+        let rows = rows
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_str::<serde_json::Value>)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(rows.len(), 2500);
+        assert_eq!(rows[0]["id"], 1);
+        assert_eq!(rows[2499]["id"], 2500);
+        let nulls = rows.iter().filter(|row| row["small"].is_null()).count();
+        assert!((1000..1500).contains(&nulls), "{} nulls", nulls);
+        let prices = rows.iter().map(|row| row["price"].to_string()).collect::<std::collections::HashSet<_>>();
+        assert!(prices.len() <= 3);
+        assert!(rows.iter().all(|row| row["tags"].as_array().is_some_and(|tags| tags.len() == 2)));
+        assert!(rows.iter().all(|row| row["created"].as_str().is_some_and(|created| created.starts_with("2023-"))));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("generate").arg("-s").arg(&types_path).arg("--nulls").arg("id=0.1").arg(dir.path().join("invalid.parquet"));
+        cmd.assert().failure().stderr(predicate::str::contains("InvalidGenerator"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("generate").arg("-s").arg(&types_path).arg("--distinct").arg("nope=2").arg(dir.path().join("invalid.parquet"));
+        cmd.assert().failure().stderr(predicate::str::contains("UnknownColumn"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("generate").arg("-s").arg(PEMS_1_PARQUET_PATH).arg("-n").arg("3")
+            .arg("--values").arg("occupancy1=uniform(-1e308,1e308)")
+            .arg(dir.path().join("invalid.parquet"));
+        cmd.assert().failure().stderr(predicate::str::contains("the range of column occupancy1 is too wide to draw values of type Float64"));
+
+        // the pool of distinct values is no larger than the rows
+        let distinct_path = dir.path().join("distinct.parquet");
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("generate").arg("-s").arg(PEMS_1_PARQUET_PATH).arg("-n").arg("3")
+            .arg("--distinct").arg("flow1=5000000000")
+            .arg(dir.path().join("invalid.parquet"));
+        cmd.assert().failure().stderr(predicate::str::contains("column flow1 can have at most 4294967295 distinct values"));
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("generate").arg("-s").arg(PEMS_1_PARQUET_PATH).arg("-n").arg("3")
+            .arg("--distinct").arg("flow1=4000000000")
+            .arg(&distinct_path);
+        cmd.assert().success();
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("rowcount").arg(&distinct_path);
+        cmd.assert().success().stdout(predicate::str::contains("3 rows"));
+
+        // the list offsets of a batch would overflow, the file is not left behind
+        let overflow_path = dir.path().join("overflow.parquet");
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("generate").arg("-s").arg(CITIES_PARQUET_PATH).arg("-n").arg("2")
+            .arg("--length").arg("country.city=2000000000")
+            .arg(&overflow_path);
+        cmd.assert().failure().stderr(predicate::str::contains("InvalidGenerator"));
+        assert!(!overflow_path.exists());
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("generate").arg("-s").arg(CITIES_PARQUET_PATH).arg("--length").arg("country.city=3000000000")
+            .arg(&overflow_path);
+        cmd.assert().failure().stderr(predicate::str::contains("is not a length up to 2147483647"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_merge() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        let dir = tempdir()?;
+        let file_path = dir.path().join(MERGED_FILE_NAME);
+        let file_name = file_path.to_str().unwrap();
+        cmd.arg("merge")
+            .arg("--input")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg(PEMS_2_PARQUET_PATH)
+            .arg("--output")
+            .arg(file_name);
+        cmd.assert().success();
+
+        let mut rowcount_cmd = Command::cargo_bin("pqrs-learn")?;
+        rowcount_cmd.arg("rowcount").arg(file_name);
+        rowcount_cmd
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("5573 rows"));
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_rowcount() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("rowcount").arg(CITIES_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("3 rows"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_sample() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+
+        cmd.arg("sample")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--records")
+            .arg("1");
+
+        cmd.assert().success().stdout(
+            predicate::str::contains(SAMPLE_PARTIAL_OUTPUT_2)
+                .and(predicate::str::starts_with(SAMPLE_PARTIAL_OUTPUT_1)));
+
+        // more records than the file holds gives the whole file
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        let output = cmd.arg("sample").arg(CITIES_PARQUET_PATH).arg("--records").arg("2000000000").arg("--json").output()?;
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout)?.lines().count(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_sample_seed() -> Result<(), Box<dyn std::error::Error>> {
+        let mut outputs = vec![];
+        for _ in 0..2 {
+            let mut cmd = Command::cargo_bin("pqrs-learn")?;
+            cmd.arg("sample")
+                .arg(PEMS_1_PARQUET_PATH)
+                .arg("--records")
+                .arg("10")
+                .arg("--seed")
+                .arg("42")
+                .arg("--csv");
+            let output = cmd.assert().success().get_output().stdout.clone();
+            outputs.push(String::from_utf8(output)?);
+        }
+
+        assert_eq!(outputs[0], outputs[1]);
+        assert_eq!(outputs[0].lines().count(), 11);
+        Ok(())
+    }
+
+    #[test]
+    fn validate_sample_per_stratum() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("sample")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--stratify-by")
+            .arg("continent")
+            .arg("--per-stratum")
+            .arg("1")
+            .arg("--json");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("\"continent\":\"Europe\""))
+            .stdout(predicate::str::contains("\"continent\":\"North America\""))
+            .stderr(predicate::str::contains("Europe: 2 rows, 1 sampled"));
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("sample")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--stratify-by")
+            .arg("continent")
+            .arg("--per-stratum")
+            .arg("2000000000");
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("Europe: 2 rows, 2 sampled"))
+            .stderr(predicate::str::contains("North America: 1 rows, 1 sampled"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_sample_too_many_strata() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("sample")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--stratify-by")
+            .arg("continent")
+            .arg("--records")
+            .arg("2")
+            .arg("--max-strata")
+            .arg("1");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("TooManyStrata(1)"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_sample_row_groups_to_parquet() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join(NUMBERS_FILE_NAME);
+        let sample_path = dir.path().join("sample.parquet");
+        write_numbers_file(&file_path, 100, 10)?;
+
+        let mut cmd = Command::cargo_bin("pqrs-learn")?;
+        cmd.arg("sample")
+            .arg(&file_path)
+            .arg("--records")
+            .arg("7")
+            .arg("--row-groups")
+            .arg("--output")
+            .arg(&sample_path);
+        cmd.assert().success();
+
+        let mut rowcount_cmd = Command::cargo_bin("pqrs-learn")?;
+        rowcount_cmd.arg("rowcount").arg(&sample_path);
+        rowcount_cmd
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("7 rows"));
+
+        dir.close()?;
         Ok(())
     }
 